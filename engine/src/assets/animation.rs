@@ -1,6 +1,8 @@
 #[derive(Clone, Debug)]
 pub struct NodeData {
     pub name: Option<String>,
+    /// Index of the glTF mesh this node draws, if any.
+    pub mesh: Option<usize>,
     pub translation: [f32; 3],
     pub rotation: [f32; 4],    // [x, y, z, w]
     pub scale: [f32; 3],
@@ -1,7 +1,9 @@
 use crate::assets::animation::{AnimationClip, NodeTree, Skin};
 use crate::assets::gltf_loader::{LoaderGLTF, NormalVertex};
+use crate::core::error::AssetError;
 use crate::graphics::mesh::Mesh;
 use crate::scene::components::animator::Animator;
+use crate::{info, warn};
 use shipyard::{Component, Unique, track};
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -87,16 +89,29 @@ pub struct AssetHandle {
     pub id: String,
 }
 
+/// A glTF file loaded with [`AssetManager::load_scene`]: its node tree, and a model for each
+/// of its meshes with the vertices in the mesh's own space.
+#[derive(Clone)]
+pub struct SceneAsset {
+    pub node_tree: NodeTree,
+    /// One handle per glTF mesh, indexed by the nodes' `mesh`.
+    pub meshes: Vec<AssetHandle>,
+}
+
 #[derive(Unique, Component)]
 pub struct AssetManager {
     models: HashMap<String, Model>,
+    scenes: HashMap<String, SceneAsset>,
     unified_geometry: UnifiedGeometry,
+    /// Set when models were loaded after the last `build_unified_geometry`.
+    geometry_outdated: bool,
 }
 
 impl AssetManager {
     pub fn new() -> Self {
         Self {
             models: HashMap::new(),
+            scenes: HashMap::new(),
             unified_geometry: UnifiedGeometry {
                 vertex_buffer: None,
                 index_buffer: None,
@@ -105,12 +120,31 @@ impl AssetManager {
                 material_data: Vec::new(),
                 aabb_data: Vec::new(),
             },
+            geometry_outdated: true,
         }
     }
 
-    pub fn load_model(&mut self, filepath: &str) -> AssetHandle {
+    /// Loads a glTF model, or returns the handle of the already loaded one. A missing or broken
+    /// file leaves the manager unchanged, so the game can skip it or load a placeholder.
+    ///
+    /// `path:N` is the `N`th mesh of the file on its own, as registered by [`Self::load_scene`].
+    pub fn load_model(&mut self, filepath: &str) -> Result<AssetHandle, AssetError> {
+        if let Some((path, index)) = filepath.rsplit_once(':')
+            && let Ok(index) = index.parse::<usize>()
+        {
+            return self
+                .load_scene(path)?
+                .meshes
+                .get(index)
+                .cloned()
+                .ok_or_else(|| AssetError::Malformed {
+                    path: path.into(),
+                    message: format!("the file has no mesh {index}"),
+                });
+        }
+
         if !self.models.contains_key(filepath) {
-            let loader = LoaderGLTF::new(filepath, [0.0, 0.0, 0.0]);
+            let loader = LoaderGLTF::new(filepath, [0.0, 0.0, 0.0])?;
             let new_model = Model {
                 meshes: loader.get_meshes(),
                 draw_range: 0..0,
@@ -119,10 +153,49 @@ impl AssetManager {
                 animations: loader.get_animations(),
             };
             self.models.insert(filepath.to_string(), new_model);
+            self.geometry_outdated = true;
         }
-        AssetHandle {
+        Ok(AssetHandle {
             id: filepath.to_string(),
+        })
+    }
+
+    /// Loads a glTF file for spawning as a tree of entities, or returns the already loaded one.
+    /// Each mesh becomes a model with the id `path:N`; unlike [`Self::load_model`], node
+    /// transforms aren't baked into the vertices but left to the entities.
+    pub fn load_scene(&mut self, filepath: &str) -> Result<SceneAsset, AssetError> {
+        if let Some(scene) = self.scenes.get(filepath) {
+            return Ok(scene.clone());
         }
+
+        let loader = LoaderGLTF::new_per_mesh(filepath, [0.0, 0.0, 0.0])?;
+        let node_tree = loader.get_node_tree();
+        let skins = loader.get_skins();
+        let animations = loader.get_animations();
+        let meshes = loader
+            .get_node_meshes()
+            .into_iter()
+            .enumerate()
+            .map(|(index, meshes)| {
+                let id = format!("{filepath}:{index}");
+                self.models.insert(
+                    id.clone(),
+                    Model {
+                        meshes,
+                        draw_range: 0..0,
+                        node_tree: node_tree.clone(),
+                        skins: skins.clone(),
+                        animations: animations.clone(),
+                    },
+                );
+                AssetHandle { id }
+            })
+            .collect();
+        self.geometry_outdated = true;
+
+        let scene = SceneAsset { node_tree, meshes };
+        self.scenes.insert(filepath.to_string(), scene.clone());
+        Ok(scene)
     }
 
     /// Create an `Animator` component pre-loaded with the animation data for
@@ -131,11 +204,9 @@ impl AssetManager {
     pub fn create_animator(&self, handle: &AssetHandle) -> Option<Animator> {
         let model = self.models.get(&handle.id)?;
         let skin = model.skins.first()?.clone();
-        Some(Animator::new(
-            model.node_tree.clone(),
-            skin,
-            model.animations.clone(),
-        ))
+        let mut animator = Animator::new(model.node_tree.clone(), skin, model.animations.clone());
+        animator.model = Some(handle.clone());
+        Some(animator)
     }
 
     pub fn get_model(&self, handle: &AssetHandle) -> Option<&Model> {
@@ -146,7 +217,13 @@ impl AssetManager {
         self.models.get_mut(&handle.id)
     }
 
-    pub fn build_unified_geometry(&mut self, memory_allocator: Arc<StandardMemoryAllocator>) {
+    /// Packs every loaded model into one set of buffers and sets each model's draw range.
+    /// Without an allocator only the draws, materials and bounds are laid out, for renderers
+    /// that don't draw on a GPU.
+    pub fn build_unified_geometry(
+        &mut self,
+        memory_allocator: Option<Arc<StandardMemoryAllocator>>,
+    ) {
         let mut all_vertices: Vec<NormalVertex> = Vec::new();
         let mut all_indices: Vec<u32> = Vec::new();
         let mut mesh_draws: Vec<MeshDrawInfo> = Vec::new();
@@ -188,10 +265,12 @@ impl AssetManager {
                 let albedo_idx = if let Some(tex) = mesh.texture.as_ref() {
                     push_tex(&mut textures, &mut texture_dedup, Some(tex))
                 } else {
-                    eprintln!(
-                        "Warning: mesh has no GPU albedo texture during build_unified_geometry. \
-                         Was upload_texture_to_gpu called first?"
-                    );
+                    if memory_allocator.is_some() {
+                        warn!(
+                            "Mesh has no GPU albedo texture during build_unified_geometry. \
+                             Was upload_texture_to_gpu called first?"
+                        );
+                    }
                     0
                 };
 
@@ -252,7 +331,10 @@ impl AssetManager {
             model.draw_range = draw_start..mesh_draws.len();
         }
 
-        let vertex_buffer = if !all_vertices.is_empty() {
+        let vertex_buffer = if let Some(memory_allocator) = memory_allocator
+            .as_ref()
+            .filter(|_| !all_vertices.is_empty())
+        {
             Some(
                 Buffer::from_iter(
                     memory_allocator.clone(),
@@ -273,7 +355,10 @@ impl AssetManager {
             None
         };
 
-        let index_buffer = if !all_indices.is_empty() {
+        let index_buffer = if let Some(memory_allocator) = memory_allocator
+            .as_ref()
+            .filter(|_| !all_indices.is_empty())
+        {
             Some(
                 Buffer::from_iter(
                     memory_allocator.clone(),
@@ -294,7 +379,7 @@ impl AssetManager {
             None
         };
 
-        println!(
+        info!(
             "Unified geometry built: {} vertices, {} indices, {} draws, {} unique textures, {} materials",
             current_vertex_offset,
             current_index_offset,
@@ -311,6 +396,12 @@ impl AssetManager {
             material_data,
             aabb_data,
         };
+        self.geometry_outdated = false;
+    }
+
+    /// Whether models were loaded since the unified geometry was last built.
+    pub fn needs_rebuild(&self) -> bool {
+        self.geometry_outdated
     }
 
     pub fn get_unified_geometry(&self) -> &UnifiedGeometry {
@@ -3,6 +3,7 @@ use gltf;
 use gltf::animation::util::ReadOutputs;
 use image::{DynamicImage, GrayImage, ImageBuffer};
 use nalgebra_glm as glm;
+use std::path::Path;
 use std::sync::Arc;
 
 use crate::assets::animation::{
@@ -10,25 +11,62 @@ use crate::assets::animation::{
     SamplerOutput, TargetProperty,
 };
 use crate::assets::material::{Material, NormalMap, PbrMaterial};
+use crate::core::error::AssetError;
+use crate::debug;
 use crate::graphics::mesh::Mesh;
 use super::NormalVertex;
 
 pub struct LoaderGLTF {
     color: [f32; 3],
     meshes: Vec<Mesh>,
+    /// The primitives of each glTF mesh in the mesh's own space, indexed like the file's
+    /// meshes. Only filled by [`LoaderGLTF::new_per_mesh`].
+    node_meshes: Vec<Vec<Mesh>>,
     pub node_tree: NodeTree,
     pub skins: Vec<Skin>,
     pub animations: Vec<AnimationClip>,
 }
 
 impl LoaderGLTF {
-    pub fn new(file_name: &str, custom_color: [f32; 3]) -> Self {
-        let (doc, buffers, images) = gltf::import(file_name).unwrap();
+    /// Reads and parses a glTF or GLB file, baking every node's transform into the vertices of
+    /// one list of meshes. Fails if the file is missing, isn't valid glTF or holds data the
+    /// engine can't use.
+    pub fn new(file_name: &str, custom_color: [f32; 3]) -> Result<Self, AssetError> {
+        Self::load(file_name, custom_color, true)
+    }
+
+    /// Like [`LoaderGLTF::new`], but keeps each glTF mesh separate and in its own space, for
+    /// spawning the file's nodes as entities. See [`LoaderGLTF::get_node_meshes`].
+    pub fn new_per_mesh(file_name: &str, custom_color: [f32; 3]) -> Result<Self, AssetError> {
+        Self::load(file_name, custom_color, false)
+    }
+
+    fn load(file_name: &str, custom_color: [f32; 3], baked: bool) -> Result<Self, AssetError> {
+        let path = Path::new(file_name);
+        let (doc, buffers, images) = gltf::import(path).map_err(|source| AssetError::Gltf {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let malformed = |message| AssetError::Malformed {
+            path: path.to_path_buf(),
+            message,
+        };
         let mut meshes = Vec::new();
+        let mut node_meshes = Vec::new();
 
-        for scene in doc.scenes() {
-            for node in scene.nodes() {
-                collect_meshes(&node, &glm::identity(), &buffers, &images, custom_color, &mut meshes);
+        if baked {
+            for scene in doc.scenes() {
+                for node in scene.nodes() {
+                    collect_meshes(&node, &glm::identity(), &buffers, &images, custom_color, &mut meshes)
+                        .map_err(malformed)?;
+                }
+            }
+        } else {
+            for mesh in doc.meshes() {
+                let mut primitives = Vec::new();
+                load_primitives(&mesh, None, &buffers, &images, custom_color, &mut primitives)
+                    .map_err(malformed)?;
+                node_meshes.push(primitives);
             }
         }
 
@@ -36,13 +74,20 @@ impl LoaderGLTF {
         let skins = load_skins(&doc, &buffers);
         let animations = load_animations(&doc, &buffers);
 
-        LoaderGLTF { color: custom_color, meshes, node_tree, skins, animations }
+        Ok(LoaderGLTF { color: custom_color, meshes, node_meshes, node_tree, skins, animations })
     }
 
     pub fn get_meshes(&self) -> Vec<Mesh> {
         self.meshes.clone()
     }
 
+    /// The primitives of each glTF mesh, indexed by [`NodeData::mesh`]. Vertices are in the
+    /// mesh's space with the Y axis flipped like everywhere else; the node transforms are left
+    /// to the entities.
+    pub fn get_node_meshes(&self) -> Vec<Vec<Mesh>> {
+        self.node_meshes.clone()
+    }
+
     pub fn get_node_tree(&self) -> NodeTree {
         self.node_tree.clone()
     }
@@ -79,7 +124,7 @@ fn collect_meshes(
     images: &[gltf::image::Data],
     color: [f32; 3],
     meshes: &mut Vec<Mesh>,
-) {
+) -> Result<(), String> {
     let m = node.transform().matrix();
     let local = glm::Mat4::from_column_slice(&[
         m[0][0], m[0][1], m[0][2], m[0][3],
@@ -90,11 +135,24 @@ fn collect_meshes(
     let transform = parent_transform * local;
 
     for child in node.children() {
-        collect_meshes(&child, &transform, buffers, images, color, meshes);
+        collect_meshes(&child, &transform, buffers, images, color, meshes)?;
     }
 
-    let Some(mesh) = node.mesh() else { return };
+    let Some(mesh) = node.mesh() else { return Ok(()) };
 
+    load_primitives(&mesh, Some(&transform), buffers, images, color, meshes)
+}
+
+/// Reads the triangle primitives of `mesh`. With a `transform` static primitives are baked
+/// into it; skinned ones, and all of them without one, stay in the mesh's space.
+fn load_primitives(
+    mesh: &gltf::Mesh,
+    transform: Option<&glm::Mat4>,
+    buffers: &[gltf::buffer::Data],
+    images: &[gltf::image::Data],
+    color: [f32; 3],
+    meshes: &mut Vec<Mesh>,
+) -> Result<(), String> {
     for primitive in mesh.primitives() {
         if primitive.mode() != gltf::mesh::Mode::Triangles {
             continue;
@@ -104,7 +162,7 @@ fn collect_meshes(
 
         let positions: Vec<[f32; 3]> = reader
             .read_positions()
-            .expect("primitive has no positions")
+            .ok_or_else(|| format!("a primitive of mesh {} has no positions", mesh.index()))?
             .collect();
         let count = positions.len();
 
@@ -151,17 +209,18 @@ fn collect_meshes(
                 // handle the transformation to world space.  Static meshes bake the
                 // accumulated scene-graph transform into the vertices so they render
                 // correctly without per-node model matrices.
-                let (px, py, pz, nx, ny, nz, tx, ty, tz) = if is_skinned {
-                    (
+                let (px, py, pz, nx, ny, nz, tx, ty, tz) = match transform {
+                    Some(transform) if !is_skinned => {
+                        let p = transform * glm::vec4(pos[0], pos[1], pos[2], 1.0);
+                        let n = transform * glm::vec4(normals[i][0], normals[i][1], normals[i][2], 0.0);
+                        let t = transform * glm::vec4(tangents[i][0], tangents[i][1], tangents[i][2], 0.0);
+                        (p.x / p.w, p.y / p.w, p.z / p.w, n.x, n.y, n.z, t.x, t.y, t.z)
+                    }
+                    _ => (
                         pos[0], pos[1], pos[2],
                         normals[i][0], normals[i][1], normals[i][2],
                         tangents[i][0], tangents[i][1], tangents[i][2],
-                    )
-                } else {
-                    let p = transform * glm::vec4(pos[0], pos[1], pos[2], 1.0);
-                    let n = transform * glm::vec4(normals[i][0], normals[i][1], normals[i][2], 0.0);
-                    let t = transform * glm::vec4(tangents[i][0], tangents[i][1], tangents[i][2], 0.0);
-                    (p.x / p.w, p.y / p.w, p.z / p.w, n.x, n.y, n.z, t.x, t.y, t.z)
+                    ),
                 };
                 NormalVertex {
                     position: [px, -py, pz],
@@ -175,10 +234,10 @@ fn collect_meshes(
             })
             .collect();
 
-        let material = load_material(&primitive.material(), images);
+        let material = load_material(&primitive.material(), images)?;
 
-        println!(
-            "  mesh primitive: {} verts, skinned={}",
+        debug!(
+            "mesh primitive: {} verts, skinned={}",
             count, is_skinned
         );
         meshes.push(Mesh {
@@ -191,6 +250,7 @@ fn collect_meshes(
             is_skinned,
         });
     }
+    Ok(())
 }
 
 // ── node tree ─────────────────────────────────────────────────────────────────
@@ -202,6 +262,7 @@ fn build_node_tree(doc: &gltf::Document) -> NodeTree {
             let (translation, rotation, scale) = node.transform().decomposed();
             NodeData {
                 name: node.name().map(String::from),
+                mesh: node.mesh().map(|mesh| mesh.index()),
                 translation,
                 rotation,
                 scale,
@@ -319,20 +380,33 @@ fn load_animations(doc: &gltf::Document, buffers: &[gltf::buffer::Data]) -> Vec<
 
 // ── material loading ──────────────────────────────────────────────────────────
 
-fn gltf_image_to_dynamic(data: &gltf::image::Data) -> DynamicImage {
+fn gltf_image_to_dynamic(data: &gltf::image::Data) -> Result<DynamicImage, String> {
     use gltf::image::Format;
-    match data.format {
+    let too_small = || {
+        format!(
+            "a {}x{} {:?} texture has only {} bytes of pixel data",
+            data.width,
+            data.height,
+            data.format,
+            data.pixels.len()
+        )
+    };
+    let image = match data.format {
         Format::R8 => DynamicImage::ImageLuma8(
-            ImageBuffer::from_raw(data.width, data.height, data.pixels.clone()).unwrap(),
+            ImageBuffer::from_raw(data.width, data.height, data.pixels.clone())
+                .ok_or_else(too_small)?,
         ),
         Format::R8G8 => DynamicImage::ImageLumaA8(
-            ImageBuffer::from_raw(data.width, data.height, data.pixels.clone()).unwrap(),
+            ImageBuffer::from_raw(data.width, data.height, data.pixels.clone())
+                .ok_or_else(too_small)?,
         ),
         Format::R8G8B8 => DynamicImage::ImageRgb8(
-            ImageBuffer::from_raw(data.width, data.height, data.pixels.clone()).unwrap(),
+            ImageBuffer::from_raw(data.width, data.height, data.pixels.clone())
+                .ok_or_else(too_small)?,
         ),
         Format::R8G8B8A8 => DynamicImage::ImageRgba8(
-            ImageBuffer::from_raw(data.width, data.height, data.pixels.clone()).unwrap(),
+            ImageBuffer::from_raw(data.width, data.height, data.pixels.clone())
+                .ok_or_else(too_small)?,
         ),
         Format::R16G16B16A16 => {
             let rgba: Vec<u8> = data
@@ -348,7 +422,7 @@ fn gltf_image_to_dynamic(data: &gltf::image::Data) -> DynamicImage {
                 })
                 .collect();
             DynamicImage::ImageRgba8(
-                ImageBuffer::from_raw(data.width, data.height, rgba).unwrap(),
+                ImageBuffer::from_raw(data.width, data.height, rgba).ok_or_else(too_small)?,
             )
         }
         _ => DynamicImage::ImageRgba8(ImageBuffer::from_pixel(
@@ -356,25 +430,32 @@ fn gltf_image_to_dynamic(data: &gltf::image::Data) -> DynamicImage {
             data.height,
             image::Rgba([255, 255, 255, 255]),
         )),
-    }
+    };
+    Ok(image)
 }
 
-fn load_material(material: &gltf::Material, images: &[gltf::image::Data]) -> Material {
+fn load_material(
+    material: &gltf::Material,
+    images: &[gltf::image::Data],
+) -> Result<Material, String> {
     let pbr = material.pbr_metallic_roughness();
 
     let base_color_factor = pbr.base_color_factor();
 
-    let base_color_texture = pbr.base_color_texture().map(|info| {
-        let img = gltf_image_to_dynamic(&images[info.texture().source().index()]);
-        Arc::new(img.to_rgba8())
-    });
+    let base_color_texture = pbr
+        .base_color_texture()
+        .map(|info| {
+            let img = gltf_image_to_dynamic(&images[info.texture().source().index()])?;
+            Ok::<_, String>(Arc::new(img.to_rgba8()))
+        })
+        .transpose()?;
 
     let metallic_factor = pbr.metallic_factor();
     let roughness_factor = pbr.roughness_factor();
 
     let (metallic_texture, roughness_texture) =
         if let Some(info) = pbr.metallic_roughness_texture() {
-            let img = gltf_image_to_dynamic(&images[info.texture().source().index()]);
+            let img = gltf_image_to_dynamic(&images[info.texture().source().index()])?;
             let rgba = img.to_rgba8();
 
             let metallic = if metallic_factor > 0.0 {
@@ -402,15 +483,18 @@ fn load_material(material: &gltf::Material, images: &[gltf::image::Data]) -> Mat
             (None, None)
         };
 
-    let normal = material.normal_texture().map(|info| {
-        let img = gltf_image_to_dynamic(&images[info.texture().source().index()]);
-        NormalMap {
-            texture: Arc::new(img.to_rgb8()),
-            factor: info.scale(),
-        }
-    });
+    let normal = material
+        .normal_texture()
+        .map(|info| {
+            let img = gltf_image_to_dynamic(&images[info.texture().source().index()])?;
+            Ok::<_, String>(NormalMap {
+                texture: Arc::new(img.to_rgb8()),
+                factor: info.scale(),
+            })
+        })
+        .transpose()?;
 
-    Material {
+    Ok(Material {
         pbr: PbrMaterial {
             base_color_factor,
             base_color_texture,
@@ -420,5 +504,5 @@ fn load_material(material: &gltf::Material, images: &[gltf::image::Data]) -> Mat
             roughness_texture,
         },
         normal,
-    }
+    })
 }
@@ -1,428 +1,700 @@
 use crate::assets::asset_manager::{self, AssetManager};
-use crate::graphics::skybox::{HdrSkyboxImages, SkyboxImages};
-use crate::input::input_manager::InputManager;
-use crate::physics::physics_engine::PhysicsEngine;
+use crate::core::config::EngineConfig;
+use crate::core::cvars::CVarRegistry;
+use crate::core::error::EngineError;
+use crate::core::events::{self, WindowResized};
+use crate::core::frame_pacer::FramePacer;
+use crate::core::plugin::Plugin;
+#[cfg(feature = "profiler")]
+use crate::core::profiler;
+use crate::core::schedule::Schedule;
+use crate::core::simulation::Simulation;
+use crate::core::state::{AppControl, StateStack};
+use crate::core::window::{self, WindowInfo, WindowMode, WindowSettings};
+use crate::graphics::backend::{RenderBackend, RenderFrame, RenderSettings};
+use crate::input::recording::{InputEvent, InputRecorder, InputRecording};
 use crate::prelude::pointlight::Pointlight;
-use crate::scene::components::animator::Animator;
+use crate::profile_scope;
 use crate::scene::components::camera::Camera;
-use crate::scene::components::delta_time::DeltaTime;
 use crate::scene::components::directional_light::DirectionalLight;
+use crate::scene::components::hierarchy::GlobalTransform;
 use crate::scene::components::object3d::Object3D;
 use crate::scene::components::transform::Transform;
-use nalgebra_glm::{look_at, vec3};
+use crate::{error, info};
+use nalgebra_glm::{TMat4, look_at, vec3};
 use sdl3::Sdl;
-use sdl3::event::{Event, WindowEvent};
+use sdl3::event::{DisplayEvent, Event, WindowEvent};
 use sdl3::keyboard::Keycode;
 use sdl3::video::Window;
 use shipyard::{Get, IntoIter, UniqueView, View, ViewMut, World};
-use vulkano::sync;
-use vulkano::sync::GpuFuture;
+use std::io;
+use std::path::{Path, PathBuf};
 
-use crate::graphics::renderer::{CulledDrawBuffers, Renderer};
-
-const PHYSICS_DT: f32 = 1.0 / 120.0;
+use crate::graphics::draw_list::DrawList;
+use crate::graphics::renderer::{CapturedFrame, Renderer};
 
 pub trait Game {
     fn on_init(&mut self);
     fn on_update(&mut self, delta_time: f32);
     fn on_render(&mut self);
     fn on_event(&mut self, event: &Event);
+    /// Called at the end of a frame in which quitting was requested, before the states are
+    /// asked. Return `false` to keep running, e.g. to ask about unsaved changes first.
+    fn on_quit_requested(&mut self) -> bool {
+        true
+    }
+    /// Called once when the application shuts down, after every state has exited and before
+    /// the GPU resources are released. The place to save settings or progress.
+    fn on_shutdown(&mut self) {}
+    /// Registers the game's systems. Called once after `on_init`, before the `Startup` stage
+    /// runs; the engine's own systems are already in `schedule` at that point.
+    fn build_schedule(&mut self, _schedule: &mut Schedule) {}
     fn get_world(&self) -> &World;
     fn get_world_mut(&mut self) -> &mut World;
 }
 
-pub struct Application<G: Game> {
-    pub game: G,
-    pub last_frame: std::time::Instant,
+/// Writes every rendered frame to `directory` as `frame_00000.png`, `frame_00001.png`, ...
+/// while advancing the game by exactly `1 / fps` seconds per frame, however long each frame
+/// takes to render. Start one with [`Application::capture_sequence`].
+#[derive(Clone, Debug)]
+pub struct CaptureSequence {
+    pub directory: PathBuf,
+    pub fps: f32,
+    /// Stop after this many frames; `None` captures until the application exits.
+    pub frames: Option<u32>,
+    /// Also write the HDR scene colour before tone mapping as `frame_00000.exr`, ...
+    pub hdr: bool,
+}
+
+impl CaptureSequence {
+    pub fn new(directory: impl Into<PathBuf>, fps: f32) -> Self {
+        Self {
+            directory: directory.into(),
+            fps,
+            frames: None,
+            hdr: false,
+        }
+    }
+}
+
+struct Screenshot {
+    path: PathBuf,
+    hdr_path: Option<PathBuf>,
+}
+
+/// A [`Simulation`] with a window and a renderer: polls SDL events, renders every frame and
+/// handles screenshots, input recording and replay. Renders with Vulkan through [`Renderer`]
+/// unless created [`with_backend`](Application::with_backend).
+pub struct Application<G: Game, R: RenderBackend = Renderer> {
+    // Fields are dropped in order: the game's world (and the GPU buffers in it), then the
+    // renderer, whose surface must go before the window it was created from.
+    /// The game, its states and plugins. Renderer and window fields of its config are applied
+    /// at creation.
+    pub simulation: Simulation<G>,
+    pub renderer: R,
+    /// `None` when running headless.
+    pub window: Option<Window>,
     pub sdl: Sdl,
-    pub window: Window,
-    pub renderer: Renderer,
-    pub physics_accumulator: f32,
-    pub previous_frame_end: Option<Box<dyn vulkano::sync::GpuFuture>>,
+    pub frame_pacer: FramePacer,
+    input_recorder: Option<InputRecorder>,
+    input_replay: Option<InputRecording>,
+    screenshot: Option<Screenshot>,
+    /// The running sequence and the number of frames written so far.
+    capture_sequence: Option<(CaptureSequence, u32)>,
+    shut_down: bool,
+    /// The window settings last applied; the [`WindowSettings`] unique is compared against it.
+    window_settings: WindowSettings,
 }
 
 impl<G: Game> Application<G> {
-    pub fn new(game: G, title: &str, width: u32, height: u32) -> Self {
-        let sdl = sdl3::init().unwrap();
-        let video = sdl.video().unwrap();
-
-        let window = video
-            .window(title, width, height)
-            .vulkan()
-            .resizable()
+    /// Opens the window and sets up the renderer. Fails if SDL, the window or Vulkan can't be
+    /// initialised.
+    pub fn new(game: G, config: EngineConfig) -> Result<Self, EngineError> {
+        let sdl = sdl3::init().map_err(|e| EngineError::Window(e.to_string()))?;
+        let video = sdl
+            .video()
+            .map_err(|e| EngineError::Window(e.to_string()))?;
+
+        let mut window_builder = video.window(
+            &config.window.title,
+            config.window.width,
+            config.window.height,
+        );
+        window_builder.vulkan();
+        if config.window.resizable {
+            window_builder.resizable();
+        }
+        if config.window.high_dpi {
+            window_builder.high_pixel_density();
+        }
+        let window = window_builder
             .build()
-            .unwrap();
+            .map_err(|e| EngineError::Window(e.to_string()))?;
 
-        let mut renderer = Renderer::new(&window);
+        let renderer = Renderer::new(&window, &config.renderer)?;
+
+        Ok(Self::with_renderer(
+            game,
+            sdl,
+            Some(window),
+            renderer,
+            config,
+        ))
+    }
+
+    /// Creates an application without a window that renders into an offscreen image.
+    /// Drive it with [`Application::run_frames`] and fetch the result with
+    /// [`Application::read_frame`]. The image size is taken from `config.window`.
+    pub fn new_headless(game: G, config: EngineConfig) -> Result<Self, EngineError> {
+        let sdl = sdl3::init().map_err(|e| EngineError::Window(e.to_string()))?;
+        let renderer =
+            Renderer::new_headless(config.window.width, config.window.height, &config.renderer)?;
+
+        Ok(Self::with_renderer(game, sdl, None, renderer, config))
+    }
 
+    fn with_renderer(
+        game: G,
+        sdl: Sdl,
+        window: Option<Window>,
+        mut renderer: Renderer,
+        config: EngineConfig,
+    ) -> Self {
         renderer.set_view(&look_at(
             &vec3(0.0, 0.0, 0.1),
             &vec3(0.0, 0.0, 0.0),
             &vec3(0.0, 1.0, 0.0),
         ));
 
-        let mut _previous_frame_end =
-            Some(Box::new(sync::now(renderer.device.clone())) as Box<dyn GpuFuture>);
+        Self::with_parts(game, sdl, window, renderer, config)
+    }
+}
+
+impl<G: Game, R: RenderBackend> Application<G, R> {
+    /// Creates an application without a window that renders through `renderer`, e.g. a
+    /// [`RecordingRenderer`](crate::graphics::recording_renderer::RecordingRenderer) in tests.
+    /// SDL is initialised without video, so no display or GPU is needed.
+    pub fn with_backend(game: G, renderer: R, config: EngineConfig) -> Result<Self, EngineError> {
+        let sdl = sdl3::init().map_err(|e| EngineError::Window(e.to_string()))?;
+        Ok(Self::with_parts(game, sdl, None, renderer, config))
+    }
+
+    fn with_parts(
+        game: G,
+        sdl: Sdl,
+        window: Option<Window>,
+        renderer: R,
+        config: EngineConfig,
+    ) -> Self {
+        // The window always starts windowed; a fullscreen mode from the config is applied in
+        // `init` like any later change.
+        let window_settings = WindowSettings {
+            mode: WindowMode::Windowed,
+            present_mode: renderer.present_mode(),
+            ..WindowSettings::from_config(&config)
+        };
 
         Self {
-            game,
-            last_frame: std::time::Instant::now(),
+            frame_pacer: FramePacer::new(&config.frame),
+            simulation: Simulation::new(game, config),
             sdl,
             window,
             renderer,
-            physics_accumulator: 0.0,
-            previous_frame_end: _previous_frame_end,
+            input_recorder: None,
+            input_replay: None,
+            screenshot: None,
+            capture_sequence: None,
+            shut_down: false,
+            window_settings,
         }
     }
 
-    pub fn run(mut self) {
-        self.game.get_world_mut().add_unique(AssetManager::new());
-        self.game.get_world_mut().add_unique(InputManager::new());
-        self.game.get_world_mut().add_unique(PhysicsEngine::new());
-        self.game
-            .get_world_mut()
-            .add_unique(DirectionalLight::new([0.1, 1.0, 0.1, 1.0], [4.0, 4.0, 4.0]));
+    /// Adds a plugin after the built-in ones. Plugins are built when the application starts,
+    /// so they have to be added before [`Application::run`] or [`Application::run_frames`].
+    pub fn add_plugin(&mut self, plugin: impl Plugin + 'static) -> &mut Self {
+        self.simulation.add_plugin(plugin);
+        self
+    }
 
-        self.game.on_init();
+    /// Removes a built-in (or previously added) plugin, e.g. `PhysicsPlugin` for a game without
+    /// physics. The application itself can't render without `RenderPlugin`.
+    pub fn disable_plugin<P: Plugin>(&mut self) -> &mut Self {
+        self.simulation.disable_plugin::<P>();
+        self
+    }
 
-        let mut event_pump = self.sdl.event_pump().unwrap();
+    /// Names of the plugins the application runs, in the order they are built.
+    pub fn plugin_names(&self) -> Vec<&'static str> {
+        self.simulation.plugin_names()
+    }
+
+    /// Starts the simulation, uploads everything `Game::on_init` and the states it pushed
+    /// loaded to the GPU and bakes the environment lighting. Called once by
+    /// [`Application::run`] and [`Application::run_frames`].
+    fn init(&mut self) {
+        if self.simulation.is_started() {
+            return;
+        }
+
+        // Before `on_init`, which may want to know the window size
+        self.update_window_info();
+        let entered = self.simulation.build();
 
         //uploads all the object3d samplers before the real operation
         self.upload_samplers_objects3d();
 
         self.build_unified_geometry();
 
-        let hdr =
-            HdrSkyboxImages::from_equirect("data/skybox/citrus_orchard_road_puresky_4k.hdr", 512);
-        let mut skybox = self.renderer.upload_hdr_skybox(hdr);
-        let irradiance = self.renderer.bake_irradiance_map(&skybox);
-        let prefiltered = self.renderer.bake_prefiltered_env(&skybox);
-        let brdf_lut = self.renderer.bake_brdf_lut();
+        self.renderer
+            .set_environment(&self.simulation.config.environment);
 
-        self.renderer.set_ambient([1.0, 1.0, 1.0], 0.3);
+        self.simulation.startup(&entered);
 
-        crate::physics::physics_engine::physics_bodies_creation_system(self.game.get_world_mut());
+        self.apply_window_settings();
+    }
 
-        self.sdl.mouse().set_relative_mouse_mode(&self.window, true);
-        self.sdl.mouse().show_cursor(false);
+    /// Applies changes to the [`WindowSettings`] unique: the window mode and size, then the
+    /// present mode. The outcome is reported in [`WindowInfo`].
+    fn apply_window_settings(&mut self) {
+        let Ok(requested) = self
+            .simulation
+            .game
+            .get_world()
+            .get_unique::<&WindowSettings>()
+            .map(|settings| WindowSettings::clone(&settings))
+        else {
+            return;
+        };
+        if requested == self.window_settings {
+            return;
+        }
 
-        'running: loop {
-            for event in event_pump.poll_iter() {
-                match event {
-                    Event::Quit { .. }
-                    | Event::KeyDown {
-                        keycode: Some(Keycode::F8),
-                        ..
-                    } => break 'running,
-
-                    Event::KeyDown { keycode, .. } => {
-                        match keycode {
-                            Some(Keycode::KpPlus) => {
-                                self.renderer.ao_radius = (self.renderer.ao_radius + 0.05).min(5.0);
-                                println!("AO radius: {:.2}", self.renderer.ao_radius);
-                            }
-                            Some(Keycode::KpMinus) => {
-                                self.renderer.ao_radius = (self.renderer.ao_radius - 0.05).max(0.0);
-                                println!("AO radius: {:.2}", self.renderer.ao_radius);
-                            }
-                            Some(Keycode::KpMultiply) => {
-                                self.renderer.ao_att_scale =
-                                    (self.renderer.ao_att_scale + 0.05).min(2.0);
-                                println!("AO attScale: {:.2}", self.renderer.ao_att_scale);
-                            }
-                            Some(Keycode::KpDivide) => {
-                                self.renderer.ao_att_scale =
-                                    (self.renderer.ao_att_scale - 0.05).max(0.0);
-                                println!("AO attScale: {:.2}", self.renderer.ao_att_scale);
-                            }
-                            Some(Keycode::Kp7) => {
-                                self.renderer.ao_dist_scale =
-                                    (self.renderer.ao_dist_scale + 0.1).min(10.0);
-                                println!("AO distScale: {:.2}", self.renderer.ao_dist_scale);
-                            }
-                            Some(Keycode::Kp4) => {
-                                self.renderer.ao_dist_scale =
-                                    (self.renderer.ao_dist_scale - 0.1).max(0.1);
-                                println!("AO distScale: {:.2}", self.renderer.ao_dist_scale);
-                            }
-                            Some(Keycode::Kp8) => {
-                                self.renderer.ao_blur_depth_threshold =
-                                    (self.renderer.ao_blur_depth_threshold + 10.0).min(1000.0);
-                                println!(
-                                    "AO blurDepthThreshold: {:.0}",
-                                    self.renderer.ao_blur_depth_threshold
-                                );
-                            }
-                            Some(Keycode::Kp5) => {
-                                self.renderer.ao_blur_depth_threshold =
-                                    (self.renderer.ao_blur_depth_threshold - 10.0).max(0.0);
-                                println!(
-                                    "AO blurDepthThreshold: {:.0}",
-                                    self.renderer.ao_blur_depth_threshold
-                                );
-                            }
-                            Some(Keycode::Kp9) => {
-                                self.renderer.ao_composite_scale =
-                                    (self.renderer.ao_composite_scale + 0.05).min(1.0);
-                                println!(
-                                    "AO compositeScale: {:.2}",
-                                    self.renderer.ao_composite_scale
-                                );
-                            }
-                            Some(Keycode::Kp6) => {
-                                self.renderer.ao_composite_scale =
-                                    (self.renderer.ao_composite_scale - 0.05).max(0.0);
-                                println!(
-                                    "AO compositeScale: {:.2}",
-                                    self.renderer.ao_composite_scale
-                                );
-                            }
-                            Some(Keycode::F1) => {
-                                self.renderer.fxaa_enabled = !self.renderer.fxaa_enabled;
-                                println!(
-                                    "FXAA: {}",
-                                    if self.renderer.fxaa_enabled {
-                                        "ON"
-                                    } else {
-                                        "OFF"
-                                    }
-                                );
-                            }
-                            Some(Keycode::F2) => {
-                                self.renderer.shadow_softness =
-                                    (self.renderer.shadow_softness + 0.5).min(16.0);
-                                println!("Shadow softness: {:.1}", self.renderer.shadow_softness);
-                            }
-                            Some(Keycode::F3) => {
-                                self.renderer.shadow_softness =
-                                    (self.renderer.shadow_softness - 0.5).max(0.0);
-                                println!("Shadow softness: {:.1}", self.renderer.shadow_softness);
-                            }
-                            Some(Keycode::F4) => {
-                                self.renderer.exposure = (self.renderer.exposure + 0.25);
-                                println!("Exposure: {:.2}", self.renderer.exposure);
-                            }
-                            Some(Keycode::F5) => {
-                                self.renderer.exposure = (self.renderer.exposure - 0.25);
-                                println!("Exposure: {:.2}", self.renderer.exposure);
-                            }
-                            _ => self.game.on_event(&event),
-                        }
+        if let Some(window) = &mut self.window {
+            match window::apply_to_window(window, &requested, &self.window_settings) {
+                Ok(()) => self.renderer.resize(window.size_in_pixels().into()),
+                Err(e) => error!("Failed to change the window mode: {e}"),
+            }
+        }
+        if requested.present_mode != self.window_settings.present_mode {
+            self.renderer.set_present_mode(requested.present_mode);
+        }
 
-                        let mut input_manager = self
-                            .game
-                            .get_world_mut()
-                            .get_unique::<&mut InputManager>()
-                            .unwrap();
+        self.window_settings = requested;
+        self.update_window_info();
+    }
 
-                        input_manager.pressed_keys.insert(keycode.unwrap());
-                        input_manager.released_keys.remove(&keycode.unwrap());
-                    }
-                    Event::KeyUp { keycode, .. } => {
-                        let mut input_manager = self
-                            .game
-                            .get_world_mut()
-                            .get_unique::<&mut InputManager>()
-                            .unwrap();
-
-                        input_manager.pressed_keys.remove(&keycode.unwrap());
-                        input_manager.released_keys.insert(keycode.unwrap());
-                    }
-                    Event::MouseButtonDown { mouse_btn, .. } => {
-                        let mut input_manager = self
-                            .game
-                            .get_world_mut()
-                            .get_unique::<&mut InputManager>()
-                            .unwrap();
-
-                        input_manager
-                            .pressed_mouse_buttons
-                            .insert(mouse_btn.to_owned());
-                    }
-                    Event::MouseButtonUp { mouse_btn, .. } => {
-                        let mut input_manager = self
-                            .game
-                            .get_world_mut()
-                            .get_unique::<&mut InputManager>()
-                            .unwrap();
-
-                        input_manager.pressed_mouse_buttons.remove(&mouse_btn);
-                    }
+    /// Matches the swapchain to the window's current pixel size and refreshes `WindowInfo`.
+    fn refresh_pixel_size(&mut self) {
+        if let Some(window) = &self.window {
+            self.renderer.resize(window.size_in_pixels().into());
+        }
+        self.update_window_info();
+    }
 
-                    // Handle mouse motion for camera control
-                    Event::MouseMotion { xrel, yrel, .. } => {
-                        let dx = xrel as f32;
-                        let dy = yrel as f32;
+    fn update_window_info(&self) {
+        let mode = self.window_settings.mode;
+        let present_mode = self.renderer.present_mode();
+        let info = match &self.window {
+            Some(window) => WindowInfo::of(window, mode, present_mode),
+            None => {
+                let size = self.renderer.image_extent();
+                WindowInfo {
+                    mode,
+                    size,
+                    pixel_size: size,
+                    display_scale: 1.0,
+                    present_mode,
+                }
+            }
+        };
+        self.simulation.game.get_world().add_unique(info);
+    }
 
-                        let mut input_manager = self
-                            .game
-                            .get_world_mut()
-                            .get_unique::<&mut InputManager>()
-                            .unwrap();
+    /// Carries out the state changes requested this frame. Entering a state uploads the models
+    /// it loaded.
+    fn apply_state_transitions(&mut self) {
+        let entered = self.simulation.apply_state_transitions();
+        if entered.is_empty() {
+            return;
+        }
 
-                        input_manager.mouse_motion.0 = dx;
-                        input_manager.mouse_motion.1 = dy;
-                    }
+        let needs_rebuild = self
+            .simulation
+            .game
+            .get_world()
+            .get_unique::<&AssetManager>()
+            .unwrap()
+            .needs_rebuild();
+        if needs_rebuild {
+            self.upload_samplers_objects3d();
+            self.build_unified_geometry();
+        }
 
-                    Event::Window { win_event, .. } => match win_event {
-                        WindowEvent::Resized { .. } => {
-                            self.renderer.recreate_swapchain();
-                        }
-                        _ => self.game.on_event(&event),
-                    },
+        self.simulation.run_state_startups(&entered);
+    }
 
-                    _ => self.game.on_event(&event),
-                }
-            }
+    /// The game's states, bottom first.
+    pub fn states(&self) -> &StateStack {
+        self.simulation.states()
+    }
 
-            // Time delta
-            let dt = self.last_frame.elapsed().as_secs_f32();
-            //adds deltatime component
-            self.game.get_world().add_unique(DeltaTime(dt));
+    /// Whether [`AppControl::quit`] was called and not cancelled. [`Application::run`] and
+    /// [`Application::run_frames`] stop after the frame it was called in.
+    pub fn is_quit_requested(&self) -> bool {
+        self.simulation.is_quit_requested()
+    }
 
-            self.last_frame = std::time::Instant::now();
+    /// Ends the game: exits every state, calls `Game::on_shutdown` and waits until the GPU has
+    /// finished the frames in flight, so the world, the renderer and the window can then be
+    /// released in that order. Runs when [`Application::run`] returns or the application is
+    /// dropped; later calls do nothing.
+    pub fn shutdown(&mut self) {
+        if self.shut_down {
+            return;
+        }
+        self.shut_down = true;
 
-            self.game.on_update(dt);
-            self.update_animators(dt);
-            self.game.on_render();
+        self.simulation.shutdown();
 
-            self.previous_frame_end
-                .as_mut()
-                .take()
-                .unwrap()
-                .cleanup_finished();
+        self.renderer.cleanup_finished();
+        self.renderer.wait_idle();
+    }
 
-            self.physics_accumulator += dt;
+    /// Runs `frames` update + render iterations with a fixed `dt` and no event processing.
+    /// Intended for headless rendering and tests.
+    pub fn run_frames(&mut self, frames: u32, dt: f32) {
+        self.init();
 
-            while self.physics_accumulator >= PHYSICS_DT {
-                crate::physics::physics_engine::physics_kinematic(self.game.get_world_mut());
-                crate::physics::physics_engine::physics_kinematic_impulses(
-                    self.game.get_world_mut(),
-                );
-                crate::physics::physics_engine::physics_step(self.game.get_world_mut());
-                crate::physics::physics_engine::physics_sync_out(self.game.get_world_mut());
-                self.physics_accumulator -= PHYSICS_DT;
+        for _ in 0..frames {
+            if self.is_quit_requested() {
+                break;
             }
-
-            self.renderer.start();
-            let culled = self.build_culled_draw_list();
-            self.render_shadows(culled.as_ref());
-            self.render_objects3d(culled.as_ref());
-            self.renderer.ambient(&irradiance, &prefiltered, &brdf_lut);
-            self.render_directional();
-            //self.renderer.directional(&directional_light);
-            self.render_pointlights();
-            self.renderer.skybox(&mut skybox);
-            //self.renderer.light_object(&directional_light);
-            self.renderer.finish(&mut self.previous_frame_end);
-
-            self.update_camera_view();
+            self.step_frame(dt, Vec::new());
         }
     }
 
-    pub fn update_camera_view(&mut self) {
-        let world = self.game.get_world();
-        world.run(|cameras: View<Camera>, transforms: View<Transform>| {
-            for (camera, transform) in (&cameras, &transforms).iter().filter(|(c, _)| c.active) {
-                //FPS camera: look from position in the direction we're facing
-                let pos = transform.get_position_vector();
-                let position = vec3(pos[0], pos[1], pos[2]);
+    /// Runs one frame with `input` as if it had come from SDL. Lets headless applications be
+    /// driven with input, e.g. to record it.
+    pub fn run_frame_with_input(&mut self, dt: f32, input: Vec<InputEvent>) {
+        self.init();
+        self.step_frame(dt, input);
+    }
 
-                let forward = camera.get_forward_vector();
-                let target = position + forward; // Look ahead from our position
-                let up = vec3(0.0, 1.0, 0.0);
+    /// Writes every frame's input and delta time to `path` until the application exits.
+    pub fn record_input(&mut self, path: impl AsRef<Path>) -> io::Result<()> {
+        self.input_recorder = Some(InputRecorder::create(path)?);
+        Ok(())
+    }
 
-                let view = look_at(&position, &target, &up);
-                self.renderer.set_view(&view);
-                break;
-            }
+    /// Plays `recording` back instead of live input and the real clock. Once it runs out the
+    /// application continues with live input.
+    pub fn replay_input(&mut self, recording: InputRecording) {
+        self.input_replay = Some(recording);
+    }
+
+    pub fn is_replaying(&self) -> bool {
+        self.input_replay.is_some()
+    }
+
+    /// Runs console commands against the [`CVarRegistry`], e.g. `r.exposure 1.5` or
+    /// `bind F6 "toggle r.fxaa"`, and returns their output. Only available after
+    /// initialisation.
+    pub fn execute(&mut self, commands: &str) -> Result<String, String> {
+        let output = self.simulation.execute(commands)?;
+        self.apply_cvars();
+        Ok(output)
+    }
+
+    /// Saves the next rendered frame to `path` (format by extension, e.g. `.png`) and, with
+    /// `hdr_path`, the HDR scene colour before tone mapping as floats (e.g. `.exr`).
+    pub fn screenshot(&mut self, path: impl Into<PathBuf>, hdr_path: Option<PathBuf>) {
+        self.screenshot = Some(Screenshot {
+            path: path.into(),
+            hdr_path,
         });
     }
 
-    fn build_culled_draw_list(&mut self) -> Option<CulledDrawBuffers> {
-        let world = self.game.get_world();
-        let asset_manager = world.get_unique::<&AssetManager>().unwrap();
-        let unified = asset_manager.get_unified_geometry();
-
-        let mut draw_list: Vec<(usize, Transform, u32)> = Vec::new();
-        let mut all_joint_matrices: Vec<[[f32; 4]; 4]> = Vec::new();
-
-        world.run(
-            |objects: View<Object3D>, transforms: View<Transform>, animators: View<Animator>| {
-                for (entity_id, (object, transform)) in (&objects, &transforms).iter().with_id() {
-                    if let Some(model) = asset_manager.get_model(&object.model) {
-                        let entity_skin_offset = if let Ok(animator) = animators.get(entity_id) {
-                            let offset = all_joint_matrices.len() as u32;
-                            all_joint_matrices.extend_from_slice(animator.joint_matrices());
-                            offset
-                        } else {
-                            crate::assets::asset_manager::NO_SKIN
-                        };
-                        for draw_idx in model.draw_range.clone() {
-                            // Only pass the skin offset for draws that actually have joint data.
-                            // Non-skinned primitives (e.g. rigid helmet attachments) must use
-                            // NO_SKIN so they don't incorrectly follow the root bone.
-                            let skin_offset = if unified.mesh_draws[draw_idx].is_skinned {
-                                entity_skin_offset
-                            } else {
-                                crate::assets::asset_manager::NO_SKIN
-                            };
-                            draw_list.push((draw_idx, transform.clone(), skin_offset));
+    /// Starts writing every frame to an image sequence at a fixed simulated frame rate.
+    pub fn capture_sequence(&mut self, sequence: CaptureSequence) {
+        self.capture_sequence = Some((sequence, 0));
+    }
+
+    pub fn is_capturing_sequence(&self) -> bool {
+        self.capture_sequence.is_some()
+    }
+
+    fn is_bound(&self, keycode: Keycode) -> bool {
+        self.simulation
+            .game
+            .get_world()
+            .get_unique::<&CVarRegistry>()
+            .is_ok_and(|cvars| cvars.binding(keycode).is_some())
+    }
+
+    /// Copies the engine cvars to the renderer, the frame pacer and the physics step.
+    fn apply_cvars(&mut self) {
+        self.simulation.apply_cvars();
+
+        let world = self.simulation.game.get_world();
+        let cvars = world.get_unique::<&CVarRegistry>().unwrap();
+
+        self.renderer
+            .apply_settings(&RenderSettings::from_cvars(&cvars));
+
+        self.frame_pacer.max_fps = cvars.float("frame.max_fps");
+        self.frame_pacer.unfocused_fps = cvars.float("frame.unfocused_fps");
+        self.frame_pacer.max_dt = cvars.float("frame.max_dt");
+        self.frame_pacer.smoothing = cvars.float("frame.smoothing");
+    }
+
+    /// How the engine's and the game's systems were split into parallel batches per stage.
+    /// Only available after the first frame (or [`Application::run`]) has initialised the game.
+    pub fn batch_plan(&self) -> String {
+        self.simulation.batch_plan()
+    }
+
+    /// The last rendered frame as tightly packed RGBA8 rows, if the application is headless.
+    pub fn read_frame(&self) -> Option<Vec<u8>> {
+        self.renderer.read_frame()
+    }
+
+    pub fn run(mut self) {
+        self.init();
+
+        let mut event_pump = self.sdl.event_pump().unwrap();
+
+        if let Some(window) = &self.window {
+            self.sdl.mouse().set_relative_mouse_mode(window, true);
+            self.sdl.mouse().show_cursor(false);
+        }
+
+        loop {
+            let mut frame_input = Vec::new();
+
+            for event in event_pump.poll_iter() {
+                // Closing the window asks for confirmation like any other quit request
+                if let Event::Quit { .. } = event {
+                    self.simulation
+                        .game
+                        .get_world()
+                        .get_unique::<&mut AppControl>()
+                        .unwrap()
+                        .quit();
+                    continue;
+                }
+
+                // Input reaches the `on_event` hooks from `step_frame`, so live and replayed
+                // input are dispatched the same way. While replaying, live input is dropped so
+                // the recording plays back unchanged.
+                if let Some(input) = InputEvent::from_sdl(&event) {
+                    if self.input_replay.is_some() {
+                        continue;
+                    }
+
+                    if let Event::KeyDown {
+                        keycode: Some(Keycode::F12),
+                        repeat: false,
+                        ..
+                    } = event
+                    {
+                        let time = std::time::SystemTime::now()
+                            .duration_since(std::time::UNIX_EPOCH)
+                            .unwrap_or_default();
+                        let name = format!("screenshot_{}.png", time.as_millis());
+                        self.screenshot(Path::new("screenshots").join(name), None);
+                    }
+
+                    frame_input.push(input);
+                    continue;
+                }
+
+                match event {
+                    Event::Window { win_event, .. } => match win_event {
+                        WindowEvent::FocusGained | WindowEvent::FocusLost => {
+                            self.frame_pacer
+                                .set_focused(matches!(win_event, WindowEvent::FocusGained));
+                            self.simulation.dispatch_event(&event);
+                        }
+                        WindowEvent::Resized(width, height) => {
+                            self.refresh_pixel_size();
+                            events::send_event(
+                                self.simulation.game.get_world(),
+                                WindowResized {
+                                    width: width.max(0) as u32,
+                                    height: height.max(0) as u32,
+                                },
+                            );
                         }
+                        // A DPI change, e.g. moving to a display with another scale, can change
+                        // the pixel size without a `Resized`
+                        WindowEvent::PixelSizeChanged(..) | WindowEvent::DisplayChanged(_) => {
+                            self.refresh_pixel_size();
+                            self.simulation.dispatch_event(&event);
+                        }
+                        _ => self.simulation.dispatch_event(&event),
+                    },
+
+                    Event::Display {
+                        display_event: DisplayEvent::ContentScaleChanged,
+                        ..
+                    } => {
+                        self.refresh_pixel_size();
+                        self.simulation.dispatch_event(&event);
                     }
+
+                    _ => self.simulation.dispatch_event(&event),
                 }
-            },
-        );
+            }
 
-        self.renderer
-            .cull_pass(unified, &draw_list, &all_joint_matrices)
+            // Sleeps off the rest of the frame under the frame rate cap
+            let dt = self.frame_pacer.next_frame();
+
+            self.step_frame(dt, frame_input);
+
+            if self.is_quit_requested() {
+                break;
+            }
+        }
+
+        self.shutdown();
     }
 
-    pub fn update_animators(&mut self, delta_time: f32) {
-        self.game
-            .get_world_mut()
-            .run(|mut animators: ViewMut<Animator>| {
-                for animator in (&mut animators).iter() {
-                    animator.update(delta_time);
+    /// Runs one frame with the given input. When a replay is active, its recorded frame time
+    /// and input are used instead; when recording, the frame is written out.
+    fn step_frame(&mut self, mut dt: f32, mut input: Vec<InputEvent>) {
+        #[cfg(feature = "profiler")]
+        profiler::new_frame();
+        profile_scope!("frame");
+
+        if let Some(replay) = &mut self.input_replay {
+            match replay.next_frame() {
+                Some(frame) => {
+                    dt = frame.dt;
+                    input = frame.events;
                 }
-            });
-    }
+                None => {
+                    info!("Input replay finished");
+                    self.input_replay = None;
+                }
+            }
+        }
 
-    pub fn render_shadows(&mut self, culled: Option<&CulledDrawBuffers>) {
-        let world = self.game.get_world();
-        let asset_manager = world.get_unique::<&AssetManager>().unwrap();
-        let unified = asset_manager.get_unified_geometry();
-        let directional = world.get_unique::<&DirectionalLight>().unwrap();
-        self.renderer.shadow_pass(&directional, unified, culled);
+        if let Some((sequence, _)) = &self.capture_sequence {
+            dt = 1.0 / sequence.fps;
+        }
+
+        if let Some(recorder) = &mut self.input_recorder
+            && let Err(e) = recorder.record_frame(dt, &input)
+        {
+            error!("Input recording stopped: {e}");
+            self.input_recorder = None;
+        }
+
+        self.dispatch_input(&input);
+        self.simulation.begin_frame(&input);
+        self.apply_cvars();
+
+        self.simulation.update(dt);
+        self.renderer.cleanup_finished();
+
+        let hdr = match (&self.screenshot, &self.capture_sequence) {
+            (None, None) => None,
+            (screenshot, sequence) => Some(
+                screenshot.as_ref().is_some_and(|s| s.hdr_path.is_some())
+                    || sequence.as_ref().is_some_and(|(s, _)| s.hdr),
+            ),
+        };
+        if let Some(hdr) = hdr {
+            self.renderer.request_capture(hdr);
+        }
+
+        self.render();
+
+        if let Some(capture) = self.renderer.take_capture() {
+            self.save_capture(capture);
+        }
+
+        self.apply_state_transitions();
+        self.simulation.confirm_quit();
+        self.apply_window_settings();
+
+        self.simulation.end_frame();
     }
 
-    pub fn render_objects3d(&mut self, culled: Option<&CulledDrawBuffers>) {
-        let world = self.game.get_world();
-        let asset_manager = world.get_unique::<&AssetManager>().unwrap();
-        let unified = asset_manager.get_unified_geometry();
-        self.renderer.geometry(unified, culled);
+    /// Passes the frame's input to the `on_event` hooks. F12 takes screenshots and bound keys
+    /// belong to the console (their commands run in `begin_frame`), so neither is passed on.
+    fn dispatch_input(&mut self, input: &[InputEvent]) {
+        for event in input {
+            if let InputEvent::KeyDown(keycode) = *event
+                && (keycode == Keycode::F12 || self.is_bound(keycode))
+            {
+                continue;
+            }
+            self.simulation.dispatch_event(&event.to_sdl());
+        }
     }
 
-    pub fn render_pointlights(&mut self) {
-        let world = self.game.get_world();
-        let asset_manager = world.get_unique::<&AssetManager>().unwrap();
+    fn save_capture(&mut self, capture: Result<CapturedFrame, String>) {
+        let capture = match capture {
+            Ok(capture) => capture,
+            Err(e) => {
+                error!("Frame capture failed: {e}");
+                self.screenshot = None;
+                self.capture_sequence = None;
+                return;
+            }
+        };
 
-        world.run(|mut pointlights: View<Pointlight>| {
-            for object in pointlights.iter() {
-                self.renderer.pointlight(&object);
+        if let Some(screenshot) = self.screenshot.take() {
+            match write_capture(&capture, &screenshot.path, screenshot.hdr_path.as_deref()) {
+                Ok(()) => info!("Saved screenshot {}", screenshot.path.display()),
+                Err(e) => error!("Failed to save {}: {e}", screenshot.path.display()),
             }
-        });
-    }
+        }
 
-    pub fn render_directional(&mut self) {
-        let world = self.game.get_world();
+        if let Some((sequence, written)) = &mut self.capture_sequence {
+            let name = format!("frame_{:05}", *written);
+            let path = sequence.directory.join(format!("{name}.png"));
+            let hdr_path = sequence
+                .hdr
+                .then(|| sequence.directory.join(format!("{name}.exr")));
+
+            if let Err(e) = write_capture(&capture, &path, hdr_path.as_deref()) {
+                error!("Failed to save {}: {e}, stopping capture", path.display());
+                self.capture_sequence = None;
+                return;
+            }
 
+            *written += 1;
+            if sequence.frames.is_some_and(|frames| *written >= frames) {
+                info!(
+                    "Captured {written} frames to {}",
+                    sequence.directory.display()
+                );
+                self.capture_sequence = None;
+            }
+        }
+    }
+
+    fn render(&mut self) {
+        let world = self.simulation.game.get_world();
+        let asset_manager = world.get_unique::<&AssetManager>().unwrap();
+        let draw_list = world.get_unique::<&DrawList>().unwrap();
         let directional = world.get_unique::<&DirectionalLight>().unwrap();
-        self.renderer.directional(&directional);
+        let pointlights = world
+            .run(|pointlights: View<Pointlight>| pointlights.iter().cloned().collect::<Vec<_>>());
+
+        self.renderer.render(&RenderFrame {
+            assets: &asset_manager,
+            draw_list: &draw_list,
+            directional: &directional,
+            pointlights: &pointlights,
+            view: camera_view(world),
+        });
     }
 
     pub fn upload_samplers_objects3d(&mut self) {
-        let world = self.game.get_world();
+        let world = self.simulation.game.get_world();
         let mut asset_manager = world.get_unique::<&mut AssetManager>().unwrap();
 
         world.run(|mut objects: ViewMut<Object3D>| {
@@ -431,18 +703,62 @@ impl<G: Game> Application<G> {
                     model
                         .meshes
                         .iter_mut()
-                        .for_each(|m| self.renderer.upload_texture_to_gpu(m));
+                        .for_each(|m| self.renderer.upload_mesh(m));
                 }
             }
         });
     }
 
     pub fn build_unified_geometry(&mut self) {
-        let world = self.game.get_world();
+        let world = self.simulation.game.get_world();
         let mut asset_manager = world.get_unique::<&mut AssetManager>().unwrap();
 
-        asset_manager.build_unified_geometry(self.renderer.memory_allocator.clone());
-        self.renderer
-            .build_bindless_material_set(asset_manager.get_unified_geometry());
+        self.renderer.build_geometry(&mut asset_manager);
+    }
+}
+
+impl<G: Game, R: RenderBackend> Drop for Application<G, R> {
+    fn drop(&mut self) {
+        // Don't run game code while unwinding, but still let the GPU finish
+        if std::thread::panicking() {
+            self.renderer.wait_idle();
+            return;
+        }
+        self.shutdown();
+    }
+}
+
+/// The view matrix of the first active camera: looking from its world position in the
+/// direction it faces.
+fn camera_view(world: &World) -> Option<TMat4<f32>> {
+    world.run(
+        |cameras: View<Camera>, transforms: View<Transform>, globals: View<GlobalTransform>| {
+            let (id, (camera, transform)) = (&cameras, &transforms)
+                .iter()
+                .with_id()
+                .find(|(_, (camera, _))| camera.active)?;
+            let pos = globals.get(id).map_or_else(
+                |_| transform.get_position_vector(),
+                |global| global.get_position_vector(),
+            );
+            let position = vec3(pos[0], pos[1], pos[2]);
+            let target = position + camera.get_forward_vector();
+            Some(look_at(&position, &target, &vec3(0.0, 1.0, 0.0)))
+        },
+    )
+}
+
+fn write_capture(
+    capture: &CapturedFrame,
+    path: &Path,
+    hdr_path: Option<&Path>,
+) -> image::ImageResult<()> {
+    if let Some(directory) = path.parent() {
+        std::fs::create_dir_all(directory)?;
+    }
+    capture.save(path)?;
+    if let Some(hdr_path) = hdr_path {
+        capture.save_hdr(hdr_path)?;
     }
+    Ok(())
 }
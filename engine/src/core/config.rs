@@ -0,0 +1,407 @@
+use std::fmt;
+use std::path::Path;
+
+use vulkano::swapchain::PresentMode;
+
+use crate::core::log::Filter;
+use crate::core::window::WindowMode;
+use crate::scene::scene_file::{strip_comment, unquote};
+
+/// Startup settings for the whole engine.
+///
+/// Start from [`EngineConfig::default`] or [`EngineConfig::load`] and override individual
+/// fields from code before handing the config to `Application::new`.
+///
+/// The file format is a small INI dialect: `[section]` headers followed by `key = value`
+/// lines, `#` starts a comment. Keys are the field names below, e.g.
+///
+/// ```text
+/// [window]
+/// title = My Game
+/// width = 1280
+///
+/// [renderer]
+/// present_mode = mailbox
+/// shadow_map_size = 2048
+///
+/// [log]
+/// level = info,vulkano_engine::physics=warn
+/// file = game.log
+/// ```
+#[derive(Clone, Debug)]
+pub struct EngineConfig {
+    pub window: WindowConfig,
+    pub renderer: RendererConfig,
+    pub frame: FrameConfig,
+    pub physics: PhysicsConfig,
+    pub environment: EnvironmentConfig,
+    pub console: ConsoleConfig,
+    pub log: LogConfig,
+}
+
+#[derive(Clone, Debug)]
+pub struct WindowConfig {
+    pub title: String,
+    pub width: u32,
+    pub height: u32,
+    pub resizable: bool,
+    pub mode: WindowMode,
+    /// Render at the display's full pixel resolution on high-DPI screens instead of upscaling.
+    pub high_dpi: bool,
+}
+
+#[derive(Clone, Debug)]
+pub struct RendererConfig {
+    pub present_mode: PresentMode,
+    pub surface_format: SurfaceFormatPreference,
+    pub shadow_map_size: u32,
+    pub shadow_softness: f32,
+    pub exposure: f32,
+    pub fxaa_enabled: bool,
+    pub ao_radius: f32,
+    pub ao_att_scale: f32,
+    pub ao_dist_scale: f32,
+    pub ao_blur_depth_threshold: f32,
+    pub ao_composite_scale: f32,
+    pub ao_composite_bias: f32,
+}
+
+/// Which of the surface formats reported by the driver the swapchain should use.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SurfaceFormatPreference {
+    /// Whatever the driver lists first.
+    First,
+    /// A UNORM format. The composite pass applies gamma itself, so this is the accurate choice.
+    Unorm,
+    /// An sRGB format.
+    Srgb,
+}
+
+/// Frame rate limits and how the frame time is turned into `DeltaTime`, see
+/// [`FramePacer`](crate::core::frame_pacer::FramePacer).
+#[derive(Clone, Debug)]
+pub struct FrameConfig {
+    /// 0 means uncapped.
+    pub max_fps: f32,
+    /// Cap while the window is unfocused; 0 uses `max_fps`.
+    pub unfocused_fps: f32,
+    /// Longest frame time the game sees, in seconds; 0 disables the clamp.
+    pub max_dt: f32,
+    /// 0 passes frame times through, values towards 1 smooth them more.
+    pub smoothing: f32,
+}
+
+#[derive(Clone, Debug)]
+pub struct PhysicsConfig {
+    /// Length of one fixed physics step in seconds. Also used as the simulation's own timestep.
+    pub fixed_dt: f32,
+    pub gravity: [f32; 3],
+}
+
+impl PhysicsConfig {
+    /// Slowest and fastest fixed step rates, in steps per second. Also the range of the
+    /// `physics.rate` cvar.
+    pub const MIN_RATE: f32 = 10.0;
+    pub const MAX_RATE: f32 = 1000.0;
+}
+
+#[derive(Clone, Debug)]
+pub struct EnvironmentConfig {
+    /// Equirectangular HDR used for the skybox and image-based lighting.
+    pub skybox: String,
+    pub skybox_face_size: u32,
+    pub ambient_color: [f32; 3],
+    pub ambient_intensity: f32,
+    pub sun_position: [f32; 4],
+    pub sun_color: [f32; 3],
+}
+
+#[derive(Clone, Debug)]
+pub struct ConsoleConfig {
+    /// Console commands run once the game has registered its cvars. A missing file is ignored.
+    pub autoexec: String,
+}
+
+/// Applied with [`log::init`](crate::core::log::init).
+#[derive(Clone, Debug)]
+pub struct LogConfig {
+    /// Which messages are kept, e.g. `info,vulkano_engine::physics=warn`; see [`Filter`].
+    pub level: Filter,
+    pub stderr: bool,
+    /// Also write the log to this file, rotated once it reaches `max_file_size` bytes.
+    pub file: Option<String>,
+    pub max_file_size: u64,
+    /// How many rotated files are kept next to the current one.
+    pub max_files: u32,
+}
+
+impl Default for EngineConfig {
+    fn default() -> Self {
+        Self {
+            window: WindowConfig {
+                title: "SDL3 + Vulkano".to_string(),
+                width: 1920,
+                height: 1080,
+                resizable: true,
+                mode: WindowMode::Windowed,
+                high_dpi: true,
+            },
+            renderer: RendererConfig {
+                present_mode: PresentMode::Immediate,
+                surface_format: SurfaceFormatPreference::First,
+                shadow_map_size: 4096,
+                shadow_softness: 2.0,
+                exposure: 1.0,
+                fxaa_enabled: true,
+                ao_radius: 0.05,
+                ao_att_scale: 0.95,
+                ao_dist_scale: 1.7,
+                ao_blur_depth_threshold: 100.0,
+                ao_composite_scale: 1.0,
+                ao_composite_bias: 0.0,
+            },
+            frame: FrameConfig {
+                max_fps: 0.0,
+                unfocused_fps: 30.0,
+                max_dt: 0.1,
+                smoothing: 0.0,
+            },
+            physics: PhysicsConfig {
+                fixed_dt: 1.0 / 120.0,
+                gravity: [0.0, -9.81, 0.0],
+            },
+            environment: EnvironmentConfig {
+                skybox: "data/skybox/citrus_orchard_road_puresky_4k.hdr".to_string(),
+                skybox_face_size: 512,
+                ambient_color: [1.0, 1.0, 1.0],
+                ambient_intensity: 0.3,
+                sun_position: [0.1, 1.0, 0.1, 1.0],
+                sun_color: [4.0, 4.0, 4.0],
+            },
+            console: ConsoleConfig {
+                autoexec: "autoexec.cfg".to_string(),
+            },
+            log: LogConfig {
+                level: Filter::default(),
+                stderr: true,
+                file: None,
+                max_file_size: 4 * 1024 * 1024,
+                max_files: 3,
+            },
+        }
+    }
+}
+
+impl EngineConfig {
+    /// Loads a config file on top of the defaults.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let mut config = Self::default();
+        config.apply_file(path)?;
+        Ok(config)
+    }
+
+    /// Applies every setting in the file at `path` to this config.
+    pub fn apply_file(&mut self, path: impl AsRef<Path>) -> Result<(), ConfigError> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).map_err(|source| ConfigError::Io {
+            path: path.display().to_string(),
+            source,
+        })?;
+        self.apply_str(&text)
+    }
+
+    /// Applies settings in the config file format to this config. A `#` starts a comment
+    /// unless it is inside a double-quoted value, e.g. `title = "Build #2"`.
+    pub fn apply_str(&mut self, text: &str) -> Result<(), ConfigError> {
+        let mut section = String::new();
+
+        for (index, raw_line) in text.lines().enumerate() {
+            let line_number = index + 1;
+            let line = strip_comment(raw_line).trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                section = name.trim().to_string();
+                continue;
+            }
+
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(ConfigError::Parse {
+                    line: line_number,
+                    message: format!("expected `key = value`, got `{line}`"),
+                });
+            };
+
+            let key = format!("{}.{}", section, key.trim());
+            unquote(value.trim())
+                .and_then(|value| self.set(&key, &value))
+                .map_err(|message| ConfigError::Parse {
+                    line: line_number,
+                    message,
+                })?;
+        }
+
+        Ok(())
+    }
+
+    /// Sets a single `section.key` to `value`, parsed the same way as in a config file.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        match key {
+            "window.title" => self.window.title = value.to_string(),
+            "window.width" => self.window.width = parse(key, value)?,
+            "window.height" => self.window.height = parse(key, value)?,
+            "window.resizable" => self.window.resizable = parse(key, value)?,
+            "window.mode" => {
+                self.window.mode = match value {
+                    "windowed" => WindowMode::Windowed,
+                    "borderless" => WindowMode::Borderless,
+                    "fullscreen" => WindowMode::Fullscreen,
+                    _ => return Err(format!("unknown window mode `{value}`")),
+                }
+            }
+            "window.high_dpi" => self.window.high_dpi = parse(key, value)?,
+
+            "renderer.present_mode" => self.renderer.present_mode = parse_present_mode(value)?,
+            "renderer.surface_format" => {
+                self.renderer.surface_format = match value {
+                    "first" => SurfaceFormatPreference::First,
+                    "unorm" => SurfaceFormatPreference::Unorm,
+                    "srgb" => SurfaceFormatPreference::Srgb,
+                    _ => return Err(format!("unknown surface format `{value}`")),
+                }
+            }
+            "renderer.shadow_map_size" => self.renderer.shadow_map_size = parse(key, value)?,
+            "renderer.shadow_softness" => self.renderer.shadow_softness = parse(key, value)?,
+            "renderer.exposure" => self.renderer.exposure = parse(key, value)?,
+            "renderer.fxaa_enabled" => self.renderer.fxaa_enabled = parse(key, value)?,
+            "renderer.ao_radius" => self.renderer.ao_radius = parse(key, value)?,
+            "renderer.ao_att_scale" => self.renderer.ao_att_scale = parse(key, value)?,
+            "renderer.ao_dist_scale" => self.renderer.ao_dist_scale = parse(key, value)?,
+            "renderer.ao_blur_depth_threshold" => {
+                self.renderer.ao_blur_depth_threshold = parse(key, value)?
+            }
+            "renderer.ao_composite_scale" => self.renderer.ao_composite_scale = parse(key, value)?,
+            "renderer.ao_composite_bias" => self.renderer.ao_composite_bias = parse(key, value)?,
+
+            "frame.max_fps" => self.frame.max_fps = parse(key, value)?,
+            "frame.unfocused_fps" => self.frame.unfocused_fps = parse(key, value)?,
+            "frame.max_dt" => self.frame.max_dt = parse(key, value)?,
+            "frame.smoothing" => self.frame.smoothing = parse(key, value)?,
+
+            "physics.fixed_dt" => {
+                let dt: f32 = parse(key, value)?;
+                if dt <= 0.0 {
+                    return Err(format!("`{key}` must be positive"));
+                }
+                check_physics_rate(key, 1.0 / dt)?;
+                self.physics.fixed_dt = dt;
+            }
+            "physics.rate" => {
+                let hz: f32 = parse(key, value)?;
+                if hz <= 0.0 {
+                    return Err(format!("`{key}` must be positive"));
+                }
+                check_physics_rate(key, hz)?;
+                self.physics.fixed_dt = 1.0 / hz;
+            }
+            "physics.gravity" => self.physics.gravity = parse_array(key, value)?,
+
+            "environment.skybox" => self.environment.skybox = value.to_string(),
+            "environment.skybox_face_size" => {
+                self.environment.skybox_face_size = parse(key, value)?
+            }
+            "environment.ambient_color" => {
+                self.environment.ambient_color = parse_array(key, value)?
+            }
+            "environment.ambient_intensity" => {
+                self.environment.ambient_intensity = parse(key, value)?
+            }
+            "environment.sun_position" => self.environment.sun_position = parse_array(key, value)?,
+            "environment.sun_color" => self.environment.sun_color = parse_array(key, value)?,
+
+            "console.autoexec" => self.console.autoexec = value.to_string(),
+
+            "log.level" => self.log.level = value.parse()?,
+            "log.stderr" => self.log.stderr = parse(key, value)?,
+            "log.file" => self.log.file = Some(value).filter(|v| !v.is_empty()).map(str::to_string),
+            "log.max_file_size" => self.log.max_file_size = parse(key, value)?,
+            "log.max_files" => self.log.max_files = parse(key, value)?,
+
+            _ => return Err(format!("unknown setting `{key}`")),
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn parse<T: std::str::FromStr>(key: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid value `{value}` for `{key}`"))
+}
+
+/// Parses `N` comma or whitespace separated numbers, e.g. `0.0, -9.81, 0.0`.
+pub(crate) fn parse_array<const N: usize>(key: &str, value: &str) -> Result<[f32; N], String> {
+    let values = value
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|v| !v.is_empty())
+        .map(|v| parse::<f32>(key, v))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    values
+        .try_into()
+        .map_err(|_| format!("`{key}` expects {N} numbers, got `{value}`"))
+}
+
+fn check_physics_rate(key: &str, hz: f32) -> Result<(), String> {
+    if (PhysicsConfig::MIN_RATE..=PhysicsConfig::MAX_RATE).contains(&hz) {
+        Ok(())
+    } else {
+        Err(format!(
+            "`{key}` must give {} to {} steps per second, got {hz}",
+            PhysicsConfig::MIN_RATE,
+            PhysicsConfig::MAX_RATE,
+        ))
+    }
+}
+
+fn parse_present_mode(value: &str) -> Result<PresentMode, String> {
+    match value {
+        "immediate" => Ok(PresentMode::Immediate),
+        "mailbox" => Ok(PresentMode::Mailbox),
+        "fifo" | "vsync" => Ok(PresentMode::Fifo),
+        "fifo_relaxed" => Ok(PresentMode::FifoRelaxed),
+        _ => Err(format!("unknown present mode `{value}`")),
+    }
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io {
+        path: String,
+        source: std::io::Error,
+    },
+    Parse {
+        line: usize,
+        message: String,
+    },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io { path, source } => write!(f, "failed to read config {path}: {source}"),
+            ConfigError::Parse { line, message } => write!(f, "config line {line}: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConfigError::Io { source, .. } => Some(source),
+            ConfigError::Parse { .. } => None,
+        }
+    }
+}
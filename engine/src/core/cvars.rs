@@ -0,0 +1,493 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::path::Path;
+
+use sdl3::keyboard::Keycode;
+use shipyard::{Component, Unique};
+
+use crate::core::config::{EngineConfig, PhysicsConfig};
+use crate::core::log;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CVarValue {
+    Bool(bool),
+    Int(i32),
+    Float(f32),
+}
+
+impl fmt::Display for CVarValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CVarValue::Bool(value) => write!(f, "{}", if *value { "on" } else { "off" }),
+            CVarValue::Int(value) => write!(f, "{value}"),
+            CVarValue::Float(value) => write!(f, "{value}"),
+        }
+    }
+}
+
+/// A named runtime setting. Numeric values are kept inside `min..=max`.
+#[derive(Clone, Debug)]
+pub struct CVar {
+    pub description: String,
+    pub value: CVarValue,
+    pub default: CVarValue,
+    pub min: f32,
+    pub max: f32,
+}
+
+impl CVar {
+    fn parse_value(&self, text: &str) -> Option<CVarValue> {
+        match self.value {
+            CVarValue::Bool(_) => match text {
+                "1" | "on" | "true" | "yes" => Some(CVarValue::Bool(true)),
+                "0" | "off" | "false" | "no" => Some(CVarValue::Bool(false)),
+                _ => None,
+            },
+            CVarValue::Int(_) => text.parse().ok().map(CVarValue::Int),
+            CVarValue::Float(_) => text.parse().ok().map(CVarValue::Float),
+        }
+    }
+
+    fn clamped(&self, value: CVarValue) -> CVarValue {
+        match value {
+            CVarValue::Bool(_) => value,
+            CVarValue::Int(v) => CVarValue::Int(v.clamp(self.min as i32, self.max as i32)),
+            CVarValue::Float(v) => CVarValue::Float(v.clamp(self.min, self.max)),
+        }
+    }
+}
+
+/// Runtime settings of the engine and the game, addressed by name (`r.exposure`,
+/// `player.move_speed`, ...).
+///
+/// Values are changed with console commands, either one at a time through
+/// [`CVarRegistry::execute`], from a file with [`CVarRegistry::exec_file`], or by keys bound to a
+/// command. Commands can be separated with `;`, and arguments containing spaces quoted:
+///
+/// ```text
+/// r.exposure 1.5            # set
+/// r.exposure                # show
+/// add r.ao_radius 0.05      # add to a number, clamped to its range
+/// toggle r.fxaa             # flip a bool
+/// reset r.exposure          # back to the default
+/// bind F4 "add r.exposure 0.25"
+/// unbind F4
+/// list
+/// log info,game=debug     # set the log filter, see `core::log::Filter`
+/// ```
+#[derive(Component, Unique, Default)]
+pub struct CVarRegistry {
+    vars: BTreeMap<String, CVar>,
+    binds: HashMap<Keycode, String>,
+}
+
+impl CVarRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A registry with the engine's own variables, starting at the values in `config`, and the
+    /// default tuning keys bound to them.
+    pub fn with_engine_defaults(config: &EngineConfig) -> Self {
+        let renderer = &config.renderer;
+        let mut cvars = Self::new();
+
+        cvars.register_float(
+            "r.ao_radius",
+            renderer.ao_radius,
+            0.0,
+            5.0,
+            "SSAO sample radius",
+        );
+        cvars.register_float(
+            "r.ao_att_scale",
+            renderer.ao_att_scale,
+            0.0,
+            2.0,
+            "SSAO attenuation scale",
+        );
+        cvars.register_float(
+            "r.ao_dist_scale",
+            renderer.ao_dist_scale,
+            0.1,
+            10.0,
+            "SSAO distance scale",
+        );
+        cvars.register_float(
+            "r.ao_blur_depth_threshold",
+            renderer.ao_blur_depth_threshold,
+            0.0,
+            1000.0,
+            "Depth difference at which the SSAO blur stops",
+        );
+        cvars.register_float(
+            "r.ao_composite_scale",
+            renderer.ao_composite_scale,
+            0.0,
+            1.0,
+            "Strength of SSAO in the final image",
+        );
+        cvars.register_float(
+            "r.ao_composite_bias",
+            renderer.ao_composite_bias,
+            -1.0,
+            1.0,
+            "Offset added to SSAO in the final image",
+        );
+        cvars.register_float(
+            "r.shadow_softness",
+            renderer.shadow_softness,
+            0.0,
+            16.0,
+            "Shadow filter radius in texels",
+        );
+        cvars.register_float(
+            "r.exposure",
+            renderer.exposure,
+            0.0,
+            16.0,
+            "Tonemapping exposure",
+        );
+        cvars.register_bool("r.fxaa", renderer.fxaa_enabled, "FXAA on the final image");
+        cvars.register_float(
+            "frame.max_fps",
+            config.frame.max_fps,
+            0.0,
+            1000.0,
+            "Frame rate cap, 0 for uncapped",
+        );
+        cvars.register_float(
+            "frame.unfocused_fps",
+            config.frame.unfocused_fps,
+            0.0,
+            1000.0,
+            "Frame rate cap while the window is unfocused, 0 to use frame.max_fps",
+        );
+        cvars.register_float(
+            "frame.max_dt",
+            config.frame.max_dt,
+            0.0,
+            1.0,
+            "Longest frame time passed to the game in seconds, 0 for no limit",
+        );
+        cvars.register_float(
+            "frame.smoothing",
+            config.frame.smoothing,
+            0.0,
+            0.99,
+            "Frame time smoothing, 0 for raw frame times",
+        );
+        cvars.register_float(
+            "physics.rate",
+            1.0 / config.physics.fixed_dt,
+            PhysicsConfig::MIN_RATE,
+            PhysicsConfig::MAX_RATE,
+            "Fixed physics steps per second",
+        );
+
+        for (key, command) in [
+            (Keycode::KpPlus, "add r.ao_radius 0.05"),
+            (Keycode::KpMinus, "add r.ao_radius -0.05"),
+            (Keycode::KpMultiply, "add r.ao_att_scale 0.05"),
+            (Keycode::KpDivide, "add r.ao_att_scale -0.05"),
+            (Keycode::Kp7, "add r.ao_dist_scale 0.1"),
+            (Keycode::Kp4, "add r.ao_dist_scale -0.1"),
+            (Keycode::Kp8, "add r.ao_blur_depth_threshold 10"),
+            (Keycode::Kp5, "add r.ao_blur_depth_threshold -10"),
+            (Keycode::Kp9, "add r.ao_composite_scale 0.05"),
+            (Keycode::Kp6, "add r.ao_composite_scale -0.05"),
+            (Keycode::F1, "toggle r.fxaa"),
+            (Keycode::F2, "add r.shadow_softness 0.5"),
+            (Keycode::F3, "add r.shadow_softness -0.5"),
+            (Keycode::F4, "add r.exposure 0.25"),
+            (Keycode::F5, "add r.exposure -0.25"),
+        ] {
+            cvars.bind(key, command);
+        }
+
+        cvars
+    }
+
+    pub fn register_bool(&mut self, name: &str, default: bool, description: &str) {
+        self.register(name, CVarValue::Bool(default), 0.0, 1.0, description);
+    }
+
+    pub fn register_int(
+        &mut self,
+        name: &str,
+        default: i32,
+        min: i32,
+        max: i32,
+        description: &str,
+    ) {
+        self.register(
+            name,
+            CVarValue::Int(default),
+            min as f32,
+            max as f32,
+            description,
+        );
+    }
+
+    pub fn register_float(
+        &mut self,
+        name: &str,
+        default: f32,
+        min: f32,
+        max: f32,
+        description: &str,
+    ) {
+        self.register(name, CVarValue::Float(default), min, max, description);
+    }
+
+    /// Registering a name twice replaces the old variable.
+    fn register(&mut self, name: &str, default: CVarValue, min: f32, max: f32, description: &str) {
+        let mut cvar = CVar {
+            description: description.to_string(),
+            value: default,
+            default,
+            min,
+            max,
+        };
+        cvar.value = cvar.clamped(default);
+        cvar.default = cvar.value;
+        self.vars.insert(name.to_string(), cvar);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&CVar> {
+        self.vars.get(name)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &CVar)> {
+        self.vars.iter().map(|(name, cvar)| (name.as_str(), cvar))
+    }
+
+    /// Value of a registered float variable. Panics if `name` isn't one.
+    pub fn float(&self, name: &str) -> f32 {
+        match self.get(name).map(|cvar| cvar.value) {
+            Some(CVarValue::Float(value)) => value,
+            _ => panic!("`{name}` is not a registered float cvar"),
+        }
+    }
+
+    /// Value of a registered int variable. Panics if `name` isn't one.
+    pub fn int(&self, name: &str) -> i32 {
+        match self.get(name).map(|cvar| cvar.value) {
+            Some(CVarValue::Int(value)) => value,
+            _ => panic!("`{name}` is not a registered int cvar"),
+        }
+    }
+
+    /// Value of a registered bool variable. Panics if `name` isn't one.
+    pub fn bool(&self, name: &str) -> bool {
+        match self.get(name).map(|cvar| cvar.value) {
+            Some(CVarValue::Bool(value)) => value,
+            _ => panic!("`{name}` is not a registered bool cvar"),
+        }
+    }
+
+    /// Sets `name` from its text form, clamped to the variable's range.
+    pub fn set(&mut self, name: &str, value: &str) -> Result<CVarValue, String> {
+        let cvar = self.cvar_mut(name)?;
+        let parsed = cvar
+            .parse_value(value)
+            .ok_or_else(|| format!("invalid value `{value}` for `{name}`"))?;
+        cvar.value = cvar.clamped(parsed);
+        Ok(cvar.value)
+    }
+
+    pub fn bind(&mut self, key: Keycode, command: &str) {
+        self.binds.insert(key, command.to_string());
+    }
+
+    pub fn unbind(&mut self, key: Keycode) {
+        self.binds.remove(&key);
+    }
+
+    pub fn binding(&self, key: Keycode) -> Option<&str> {
+        self.binds.get(&key).map(String::as_str)
+    }
+
+    /// Runs every command in the file at `path`, one per line; `#` starts a comment.
+    pub fn exec_file(&mut self, path: impl AsRef<Path>) -> Result<(), String> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("failed to read {}: {e}", path.display()))?;
+
+        for (index, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap().trim();
+            if !line.is_empty() {
+                self.execute(line)
+                    .map_err(|e| format!("{}:{}: {e}", path.display(), index + 1))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Runs one or more `;`-separated console commands and returns their output.
+    pub fn execute(&mut self, commands: &str) -> Result<String, String> {
+        let mut output = Vec::new();
+        for command in split_commands(commands) {
+            let args = tokenize(&command);
+            if args.is_empty() {
+                continue;
+            }
+            let line = self.execute_one(&args)?;
+            if !line.is_empty() {
+                output.push(line);
+            }
+        }
+        Ok(output.join("\n"))
+    }
+
+    fn execute_one(&mut self, args: &[String]) -> Result<String, String> {
+        let arg = |i: usize| -> Result<&str, String> {
+            args.get(i)
+                .map(String::as_str)
+                .ok_or_else(|| format!("`{}` is missing an argument", args[0]))
+        };
+
+        match args[0].as_str() {
+            "set" => {
+                let value = self.set(arg(1)?, arg(2)?)?;
+                Ok(format!("{} = {value}", args[1]))
+            }
+            "toggle" => {
+                let name = arg(1)?;
+                let cvar = self.cvar_mut(name)?;
+                match cvar.value {
+                    CVarValue::Bool(value) => cvar.value = CVarValue::Bool(!value),
+                    _ => return Err(format!("`{name}` is not a bool")),
+                }
+                Ok(format!("{name} = {}", cvar.value))
+            }
+            "add" => {
+                let name = arg(1)?;
+                let delta: f32 = arg(2)?
+                    .parse()
+                    .map_err(|_| format!("invalid amount `{}`", args[2]))?;
+                let cvar = self.cvar_mut(name)?;
+                let value = match cvar.value {
+                    CVarValue::Int(value) => CVarValue::Int(value + delta as i32),
+                    CVarValue::Float(value) => CVarValue::Float(value + delta),
+                    CVarValue::Bool(_) => return Err(format!("`{name}` is not a number")),
+                };
+                cvar.value = cvar.clamped(value);
+                Ok(format!("{name} = {}", cvar.value))
+            }
+            "reset" => {
+                let name = arg(1)?;
+                let cvar = self.cvar_mut(name)?;
+                cvar.value = cvar.default;
+                Ok(format!("{name} = {}", cvar.value))
+            }
+            "bind" => {
+                let key = parse_key(arg(1)?)?;
+                let command = args[2..].join(" ");
+                if command.is_empty() {
+                    return Err("`bind` is missing a command".to_string());
+                }
+                self.bind(key, &command);
+                Ok(String::new())
+            }
+            "unbind" => {
+                self.unbind(parse_key(arg(1)?)?);
+                Ok(String::new())
+            }
+            "log" => {
+                if args.len() > 1 {
+                    log::set_filter(args[1..].join(",").parse()?);
+                }
+                Ok(format!("log = {}", log::filter()))
+            }
+            "list" => Ok(self
+                .vars
+                .iter()
+                .map(|(name, cvar)| match cvar.value {
+                    CVarValue::Bool(_) => format!(
+                        "{name} = {} (default {}) {}",
+                        cvar.value, cvar.default, cvar.description
+                    ),
+                    _ => format!(
+                        "{name} = {} (default {}, {}..{}) {}",
+                        cvar.value, cvar.default, cvar.min, cvar.max, cvar.description
+                    ),
+                })
+                .collect::<Vec<_>>()
+                .join("\n")),
+            name => match args.get(1) {
+                Some(value) => {
+                    let value = self.set(name, value)?;
+                    Ok(format!("{name} = {value}"))
+                }
+                None => {
+                    let cvar = self
+                        .get(name)
+                        .ok_or_else(|| format!("unknown cvar `{name}`"))?;
+                    Ok(format!("{name} = {}", cvar.value))
+                }
+            },
+        }
+    }
+
+    fn cvar_mut(&mut self, name: &str) -> Result<&mut CVar, String> {
+        self.vars
+            .get_mut(name)
+            .ok_or_else(|| format!("unknown cvar `{name}`"))
+    }
+}
+
+fn parse_key(name: &str) -> Result<Keycode, String> {
+    Keycode::from_name(name).ok_or_else(|| format!("unknown key `{name}`"))
+}
+
+/// Splits on `;` outside of double quotes.
+fn split_commands(text: &str) -> Vec<String> {
+    let mut commands = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+
+    for c in text.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                current.push(c);
+            }
+            ';' if !quoted => commands.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+    commands.push(current);
+    commands
+}
+
+/// Splits on whitespace outside of double quotes and strips the quotes.
+fn tokenize(command: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut has_token = false;
+
+    for c in command.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                has_token = true;
+            }
+            c if c.is_whitespace() && !quoted => {
+                if has_token {
+                    args.push(std::mem::take(&mut current));
+                    has_token = false;
+                }
+            }
+            _ => {
+                current.push(c);
+                has_token = true;
+            }
+        }
+    }
+    if has_token {
+        args.push(current);
+    }
+    args
+}
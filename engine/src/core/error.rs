@@ -0,0 +1,164 @@
+use std::fmt;
+use std::path::PathBuf;
+
+use vulkano::{LoadingError, Validated, VulkanError};
+
+use crate::core::config::ConfigError;
+
+/// Everything that can stop the engine from starting or a game from loading a resource.
+#[derive(Debug)]
+pub enum EngineError {
+    Config(ConfigError),
+    /// SDL couldn't be initialised or the window couldn't be created.
+    Window(String),
+    Graphics(GraphicsError),
+    Asset(AssetError),
+}
+
+/// Failures while setting up Vulkan in [`Renderer::new`](crate::graphics::renderer::Renderer::new)
+/// and [`Renderer::new_headless`](crate::graphics::renderer::Renderer::new_headless).
+#[derive(Debug)]
+pub enum GraphicsError {
+    /// No Vulkan loader is installed.
+    Library(LoadingError),
+    /// SDL couldn't report the instance extensions or create the window surface.
+    Surface(String),
+    /// No device supports the required extensions and features with a graphics queue that
+    /// can present to the window.
+    NoSuitableDevice,
+    /// The surface reports no formats to create a swapchain with.
+    NoSurfaceFormat,
+    Vulkan {
+        operation: &'static str,
+        source: Validated<VulkanError>,
+    },
+}
+
+/// Failures while loading a model or an image from disk.
+#[derive(Debug)]
+pub enum AssetError {
+    Gltf {
+        path: PathBuf,
+        source: gltf::Error,
+    },
+    Image {
+        path: PathBuf,
+        source: image::ImageError,
+    },
+    /// The file was read but its contents can't be used, e.g. a primitive without positions.
+    Malformed {
+        path: PathBuf,
+        message: String,
+    },
+}
+
+impl GraphicsError {
+    pub(crate) fn vulkan(operation: &'static str) -> impl FnOnce(Validated<VulkanError>) -> Self {
+        move |source| GraphicsError::Vulkan { operation, source }
+    }
+}
+
+impl AssetError {
+    /// The file the error is about.
+    pub fn path(&self) -> &std::path::Path {
+        match self {
+            AssetError::Gltf { path, .. }
+            | AssetError::Image { path, .. }
+            | AssetError::Malformed { path, .. } => path,
+        }
+    }
+}
+
+impl fmt::Display for EngineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EngineError::Config(e) => write!(f, "{e}"),
+            EngineError::Window(message) => write!(f, "failed to create the window: {message}"),
+            EngineError::Graphics(e) => write!(f, "{e}"),
+            EngineError::Asset(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl fmt::Display for GraphicsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GraphicsError::Library(source) => write!(f, "failed to load Vulkan: {source}"),
+            GraphicsError::Surface(message) => {
+                write!(f, "failed to create the window surface: {message}")
+            }
+            GraphicsError::NoSuitableDevice => write!(f, "no suitable Vulkan device found"),
+            GraphicsError::NoSurfaceFormat => write!(f, "the window surface has no formats"),
+            GraphicsError::Vulkan { operation, source } => {
+                write!(f, "failed to {operation}: {source}")
+            }
+        }
+    }
+}
+
+impl fmt::Display for AssetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AssetError::Gltf { path, source } => {
+                write!(f, "failed to load {}: {source}", path.display())
+            }
+            AssetError::Image { path, source } => {
+                write!(f, "failed to load {}: {source}", path.display())
+            }
+            AssetError::Malformed { path, message } => {
+                write!(f, "{}: {message}", path.display())
+            }
+        }
+    }
+}
+
+impl std::error::Error for EngineError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            EngineError::Config(e) => Some(e),
+            EngineError::Window(_) => None,
+            EngineError::Graphics(e) => Some(e),
+            EngineError::Asset(e) => Some(e),
+        }
+    }
+}
+
+impl std::error::Error for GraphicsError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            GraphicsError::Library(source) => Some(source),
+            GraphicsError::Vulkan { source, .. } => Some(source),
+            GraphicsError::Surface(_)
+            | GraphicsError::NoSuitableDevice
+            | GraphicsError::NoSurfaceFormat => None,
+        }
+    }
+}
+
+impl std::error::Error for AssetError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AssetError::Gltf { source, .. } => Some(source),
+            AssetError::Image { source, .. } => Some(source),
+            AssetError::Malformed { .. } => None,
+        }
+    }
+}
+
+impl From<ConfigError> for EngineError {
+    fn from(e: ConfigError) -> Self {
+        EngineError::Config(e)
+    }
+}
+
+impl From<GraphicsError> for EngineError {
+    fn from(e: GraphicsError) -> Self {
+        EngineError::Graphics(e)
+    }
+}
+
+impl From<AssetError> for EngineError {
+    fn from(e: AssetError) -> Self {
+        EngineError::Asset(e)
+    }
+}
@@ -0,0 +1,192 @@
+//! Typed event queues for systems that need to tell each other something happened, instead of
+//! polling components. Each event type lives in an [`Events`] unique registered with
+//! [`add_event`]. Events are double-buffered: anything sent during a frame can be read until the
+//! end of the next one, so a reader that runs earlier in the frame than the writer still sees it.
+//!
+//! ```ignore
+//! add_event::<Explosion>(world);
+//!
+//! fn explode(mut explosions: EventWriter<Explosion>) {
+//!     explosions.send(Explosion { radius: 2.0 });
+//! }
+//!
+//! #[derive(Unique, Default)]
+//! struct SoundReader(EventReader<Explosion>);
+//!
+//! fn play_sounds(explosions: UniqueView<Events<Explosion>>, mut reader: UniqueViewMut<SoundReader>) {
+//!     for explosion in reader.0.read(&explosions) { ... }
+//! }
+//! ```
+
+use std::any::TypeId;
+use std::marker::PhantomData;
+
+use shipyard::{Unique, UniqueViewMut, World};
+
+/// The queue of `T` events sent this frame and the last one.
+#[derive(Unique)]
+pub struct Events<T: Send + Sync + 'static> {
+    previous: Vec<T>,
+    current: Vec<T>,
+    /// Id of the first event in `previous`. Ids keep counting up so readers can tell which
+    /// events they have already seen.
+    first_id: usize,
+}
+
+/// Systems send events through a mutable view of the queue.
+pub type EventWriter<'v, T> = UniqueViewMut<'v, Events<T>>;
+
+/// One reader's position in an [`Events`] queue. Every reader keeps its own, in a unique,
+/// a component or a game state field, so several readers can see the same events.
+pub struct EventReader<T> {
+    next_id: usize,
+    _marker: PhantomData<fn() -> T>,
+}
+
+/// Engine event sent when the window's size changes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WindowResized {
+    pub width: u32,
+    pub height: u32,
+}
+
+impl<T: Send + Sync + 'static> Events<T> {
+    pub fn new() -> Self {
+        Self {
+            previous: Vec::new(),
+            current: Vec::new(),
+            first_id: 0,
+        }
+    }
+
+    pub fn send(&mut self, event: T) {
+        self.current.push(event);
+    }
+
+    pub fn send_batch(&mut self, events: impl IntoIterator<Item = T>) {
+        self.current.extend(events);
+    }
+
+    /// Every event that can still be read, oldest first.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.previous.iter().chain(&self.current)
+    }
+
+    pub fn len(&self) -> usize {
+        self.previous.len() + self.current.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Ends the frame: last frame's events are dropped and this frame's become readable for one
+    /// more frame. The application calls this for every registered type after each frame.
+    pub fn update(&mut self) {
+        self.first_id += self.previous.len();
+        self.previous.clear();
+        std::mem::swap(&mut self.previous, &mut self.current);
+    }
+
+    /// Drops every event, read or not.
+    pub fn clear(&mut self) {
+        self.first_id += self.len();
+        self.previous.clear();
+        self.current.clear();
+    }
+
+    fn next_id(&self) -> usize {
+        self.first_id + self.len()
+    }
+}
+
+impl<T: Send + Sync + 'static> Default for Events<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Send + Sync + 'static> EventReader<T> {
+    /// A reader that will see every event still in the queue on its first read.
+    pub fn new() -> Self {
+        Self {
+            next_id: 0,
+            _marker: PhantomData,
+        }
+    }
+
+    /// A reader that skips the events already in `events` and only sees newer ones.
+    pub fn from_now(events: &Events<T>) -> Self {
+        Self {
+            next_id: events.next_id(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// The events sent since this reader's last read. Events the reader fell more than a frame
+    /// behind on are gone and skipped.
+    pub fn read<'e>(&mut self, events: &'e Events<T>) -> impl Iterator<Item = &'e T> {
+        let skip = self.next_id.saturating_sub(events.first_id);
+        self.next_id = events.next_id();
+        events.iter().skip(skip)
+    }
+
+    /// Whether [`EventReader::read`] would return anything.
+    pub fn has_unread(&self, events: &Events<T>) -> bool {
+        self.next_id < events.next_id()
+    }
+}
+
+impl<T: Send + Sync + 'static> Default for EventReader<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The `Events::update` of every registered event type.
+#[derive(Unique, Default)]
+struct EventRegistry {
+    updates: Vec<(TypeId, fn(&World))>,
+}
+
+/// Adds an empty `Events<T>` unique and ends its frame in [`update_events`]. Registering the
+/// same type again keeps the existing queue.
+pub fn add_event<T: Send + Sync + 'static>(world: &World) {
+    if world.get_unique::<&EventRegistry>().is_err() {
+        world.add_unique(EventRegistry::default());
+    }
+
+    {
+        let mut registry = world.get_unique::<&mut EventRegistry>().unwrap();
+        let type_id = TypeId::of::<T>();
+        if registry.updates.iter().any(|(id, _)| *id == type_id) {
+            return;
+        }
+        registry.updates.push((type_id, update_queue::<T>));
+    }
+    world.add_unique(Events::<T>::new());
+}
+
+fn update_queue<T: Send + Sync + 'static>(world: &World) {
+    if let Ok(mut events) = world.get_unique::<&mut Events<T>>() {
+        events.update();
+    }
+}
+
+/// Ends the frame of every queue registered with [`add_event`].
+pub fn update_events(world: &World) {
+    let Ok(registry) = world.get_unique::<&EventRegistry>() else {
+        return;
+    };
+    for (_, update) in &registry.updates {
+        update(world);
+    }
+}
+
+/// Sends `event` if `T` was registered with [`add_event`], e.g. by a plugin the game may have
+/// disabled.
+pub fn send_event<T: Send + Sync + 'static>(world: &World, event: T) {
+    if let Ok(mut events) = world.get_unique::<&mut Events<T>>() {
+        events.send(event);
+    }
+}
@@ -0,0 +1,119 @@
+use std::time::{Duration, Instant};
+
+use crate::core::config::FrameConfig;
+
+/// How long before a frame is due the pacer stops sleeping and spins instead. OS sleeps
+/// overshoot by up to a millisecond or two, which is enough to miss a 144 Hz frame.
+const SPIN_MARGIN: Duration = Duration::from_micros(1500);
+
+/// Decides when the next frame starts and what `DeltaTime` it gets: caps the frame rate (with
+/// a separate cap while the window is unfocused), clamps spikes and optionally smooths the
+/// frame time.
+pub struct FramePacer {
+    /// Frames per second while focused; 0 means uncapped.
+    pub max_fps: f32,
+    /// Frames per second while the window is unfocused; 0 falls back to `max_fps`.
+    pub unfocused_fps: f32,
+    /// Longest frame time handed to the game, so a stall (e.g. dragging the window) doesn't
+    /// turn into one huge step. 0 disables the clamp.
+    pub max_dt: f32,
+    /// How much of the previous frame time is kept, from 0 (raw frame times) to just below 1
+    /// (very smooth, slow to react).
+    pub smoothing: f32,
+    focused: bool,
+    last_frame: Instant,
+    /// When the last capped frame was due. Frames are paced against this rather than the
+    /// actual start so oversleeping doesn't add up.
+    deadline: Instant,
+    smoothed_dt: Option<f32>,
+}
+
+impl FramePacer {
+    pub fn new(config: &FrameConfig) -> Self {
+        let now = Instant::now();
+        Self {
+            max_fps: config.max_fps,
+            unfocused_fps: config.unfocused_fps,
+            max_dt: config.max_dt,
+            smoothing: config.smoothing,
+            focused: true,
+            last_frame: now,
+            deadline: now,
+            smoothed_dt: None,
+        }
+    }
+
+    pub fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    /// The frame rate currently aimed for, or `None` when uncapped.
+    pub fn target_fps(&self) -> Option<f32> {
+        let fps = if !self.focused && self.unfocused_fps > 0.0 {
+            self.unfocused_fps
+        } else {
+            self.max_fps
+        };
+        (fps > 0.0).then_some(fps)
+    }
+
+    /// Waits until the next frame is due and returns its `DeltaTime`.
+    pub fn next_frame(&mut self) -> f32 {
+        let raw_dt = self.wait();
+        self.filter(raw_dt)
+    }
+
+    /// Sleeps until the next frame is due under the current cap and returns the real time
+    /// since the previous frame in seconds.
+    pub fn wait(&mut self) -> f32 {
+        if let Some(fps) = self.target_fps() {
+            let interval = Duration::from_secs_f32(1.0 / fps);
+            let deadline = self.deadline + interval;
+            let now = Instant::now();
+            if deadline > now {
+                sleep_until(deadline);
+                self.deadline = deadline;
+            } else {
+                // Fell behind: start over from now instead of rushing to catch up
+                self.deadline = now;
+            }
+        }
+
+        let now = Instant::now();
+        let dt = now.duration_since(self.last_frame).as_secs_f32();
+        self.last_frame = now;
+        dt
+    }
+
+    /// Turns a measured frame time into the one the game sees: clamped to `max_dt`, then
+    /// smoothed.
+    pub fn filter(&mut self, raw_dt: f32) -> f32 {
+        let dt = if self.max_dt > 0.0 {
+            raw_dt.min(self.max_dt)
+        } else {
+            raw_dt
+        };
+
+        let smoothing = self.smoothing.clamp(0.0, 0.99);
+        let dt = match self.smoothed_dt {
+            Some(previous) => previous * smoothing + dt * (1.0 - smoothing),
+            None => dt,
+        };
+        self.smoothed_dt = Some(dt);
+        dt
+    }
+}
+
+fn sleep_until(deadline: Instant) {
+    let now = Instant::now();
+    if deadline > now + SPIN_MARGIN {
+        std::thread::sleep(deadline - now - SPIN_MARGIN);
+    }
+    while Instant::now() < deadline {
+        std::thread::yield_now();
+    }
+}
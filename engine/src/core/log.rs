@@ -0,0 +1,482 @@
+//! Leveled logging.
+//!
+//! Messages are written with the [`error!`](crate::error), [`warn!`](crate::warn),
+//! [`info!`](crate::info), [`debug!`](crate::debug) and [`trace!`](crate::trace) macros. Each has
+//! a target, the module it was written from unless given explicitly, and is kept only if the
+//! [`Filter`] allows its level for that target. Kept messages go to every installed [`LogSink`]:
+//! stderr by default, a rotating [`FileSink`], or a [`ConsoleSink`] holding the latest lines for
+//! an in-game console.
+//!
+//! ```ignore
+//! log::set_filter("info,vulkano_engine::physics=warn,game=debug".parse()?);
+//! log::add_sink(FileSink::new("game.log", 1 << 20, 3)?);
+//!
+//! info!("loaded {} models", count);
+//! warn!(target: "game::save", "save file is from an older version");
+//! ```
+//!
+//! The logger is global and locked while a message is written, so a sink must not log itself.
+
+use std::cmp::Reverse;
+use std::collections::VecDeque;
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock, PoisonError};
+use std::time::Instant;
+
+use crate::core::config::LogConfig;
+
+/// Logs a message at `level`: `log!(Level::Info, "...")` or
+/// `log!(target: "game", Level::Info, "...")`.
+#[macro_export]
+macro_rules! log {
+    (target: $target:expr, $level:expr, $($arg:tt)+) => {{
+        let level = $level;
+        let target = $target;
+        if $crate::core::log::enabled(level, target) {
+            $crate::core::log::log(level, target, format_args!($($arg)+));
+        }
+    }};
+    ($level:expr, $($arg:tt)+) => {
+        $crate::log!(target: module_path!(), $level, $($arg)+)
+    };
+}
+
+/// Something failed and the engine or the game can't do what was asked.
+#[macro_export]
+macro_rules! error {
+    (target: $target:expr, $($arg:tt)+) => {
+        $crate::log!(target: $target, $crate::core::log::Level::Error, $($arg)+)
+    };
+    ($($arg:tt)+) => {
+        $crate::log!($crate::core::log::Level::Error, $($arg)+)
+    };
+}
+
+/// Something is wrong but was worked around, e.g. with a fallback.
+#[macro_export]
+macro_rules! warn {
+    (target: $target:expr, $($arg:tt)+) => {
+        $crate::log!(target: $target, $crate::core::log::Level::Warn, $($arg)+)
+    };
+    ($($arg:tt)+) => {
+        $crate::log!($crate::core::log::Level::Warn, $($arg)+)
+    };
+}
+
+/// Progress worth seeing by default, at most a few lines per action.
+#[macro_export]
+macro_rules! info {
+    (target: $target:expr, $($arg:tt)+) => {
+        $crate::log!(target: $target, $crate::core::log::Level::Info, $($arg)+)
+    };
+    ($($arg:tt)+) => {
+        $crate::log!($crate::core::log::Level::Info, $($arg)+)
+    };
+}
+
+/// Details for tracking down a problem, e.g. one line per loaded primitive.
+#[macro_export]
+macro_rules! debug {
+    (target: $target:expr, $($arg:tt)+) => {
+        $crate::log!(target: $target, $crate::core::log::Level::Debug, $($arg)+)
+    };
+    ($($arg:tt)+) => {
+        $crate::log!($crate::core::log::Level::Debug, $($arg)+)
+    };
+}
+
+/// Per-frame or per-entity noise.
+#[macro_export]
+macro_rules! trace {
+    (target: $target:expr, $($arg:tt)+) => {
+        $crate::log!(target: $target, $crate::core::log::Level::Trace, $($arg)+)
+    };
+    ($($arg:tt)+) => {
+        $crate::log!($crate::core::log::Level::Trace, $($arg)+)
+    };
+}
+
+/// Severity of a message, from the most to the least severe.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Level {
+    Error = 1,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl Level {
+    pub fn name(self) -> &'static str {
+        match self {
+            Level::Error => "error",
+            Level::Warn => "warn",
+            Level::Info => "info",
+            Level::Debug => "debug",
+            Level::Trace => "trace",
+        }
+    }
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(&self.name().to_uppercase())
+    }
+}
+
+impl FromStr for Level {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "error" => Ok(Level::Error),
+            "warn" | "warning" => Ok(Level::Warn),
+            "info" => Ok(Level::Info),
+            "debug" => Ok(Level::Debug),
+            "trace" => Ok(Level::Trace),
+            _ => Err(format!("unknown log level `{s}`")),
+        }
+    }
+}
+
+/// The most verbose level kept for each target; `None` turns a target off.
+///
+/// Written as comma separated entries: a bare level sets the default, `target=level` overrides
+/// it for a target and everything under it (`vulkano_engine::assets` also covers
+/// `vulkano_engine::assets::gltf_loader`). The longest matching target wins:
+///
+/// ```text
+/// info,vulkano_engine::physics=warn,vulkano_engine::assets::gltf_loader=off
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Filter {
+    default: Option<Level>,
+    /// Sorted longest target first, so the first match is the most specific.
+    targets: Vec<(String, Option<Level>)>,
+}
+
+impl Filter {
+    /// Keeps `level` and everything more severe for every target.
+    pub fn new(level: Level) -> Self {
+        Self {
+            default: Some(level),
+            targets: Vec::new(),
+        }
+    }
+
+    /// The most verbose level kept for `target`, `None` if it is off.
+    pub fn level_for(&self, target: &str) -> Option<Level> {
+        self.targets
+            .iter()
+            .find(|(prefix, _)| {
+                target
+                    .strip_prefix(prefix.as_str())
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+            })
+            .map_or(self.default, |(_, level)| *level)
+    }
+
+    pub fn enabled(&self, level: Level, target: &str) -> bool {
+        self.level_for(target).is_some_and(|max| level <= max)
+    }
+
+    /// The most verbose level kept for any target.
+    fn max_level(&self) -> Option<Level> {
+        self.targets
+            .iter()
+            .map(|(_, level)| *level)
+            .chain([self.default])
+            .max()
+            .flatten()
+    }
+}
+
+impl Default for Filter {
+    fn default() -> Self {
+        Self::new(Level::Info)
+    }
+}
+
+impl FromStr for Filter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_level = |value: &str| -> Result<Option<Level>, String> {
+            match value.trim() {
+                "off" => Ok(None),
+                value => value.parse().map(Some),
+            }
+        };
+
+        let mut filter = Filter::default();
+        for entry in s.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            match entry.split_once('=') {
+                Some((target, level)) => {
+                    let target = target.trim().to_string();
+                    let level = parse_level(level)?;
+                    filter.targets.retain(|(t, _)| *t != target);
+                    filter.targets.push((target, level));
+                }
+                None => filter.default = parse_level(entry)?,
+            }
+        }
+        filter
+            .targets
+            .sort_by_key(|(target, _)| Reverse(target.len()));
+        Ok(filter)
+    }
+}
+
+impl fmt::Display for Filter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = |level: Option<Level>| level.map_or("off", Level::name);
+        write!(f, "{}", name(self.default))?;
+        for (target, level) in &self.targets {
+            write!(f, ",{target}={}", name(*level))?;
+        }
+        Ok(())
+    }
+}
+
+/// One message on its way to the sinks.
+pub struct Record<'a> {
+    pub level: Level,
+    pub target: &'a str,
+    pub args: fmt::Arguments<'a>,
+}
+
+impl fmt::Display for Record<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:<5} {}: {}", self.level, self.target, self.args)
+    }
+}
+
+/// Where messages end up.
+pub trait LogSink: Send {
+    fn write(&mut self, record: &Record);
+
+    fn flush(&mut self) {}
+}
+
+/// Writes every message as a line to stderr.
+pub struct StderrSink;
+
+impl LogSink for StderrSink {
+    fn write(&mut self, record: &Record) {
+        eprintln!("{record}");
+    }
+}
+
+/// Appends messages to a file with the seconds since the sink was created. Once the file would
+/// grow past `max_size` bytes it is renamed to `<path>.1`, the older ones to `<path>.2` and so
+/// on up to `<path>.<max_files>`, and a new file is started.
+pub struct FileSink {
+    path: PathBuf,
+    file: File,
+    size: u64,
+    max_size: u64,
+    max_files: u32,
+    start: Instant,
+}
+
+impl FileSink {
+    pub fn new(path: impl Into<PathBuf>, max_size: u64, max_files: u32) -> io::Result<Self> {
+        let path = path.into();
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let size = file.metadata()?.len();
+        Ok(Self {
+            path,
+            file,
+            size,
+            max_size,
+            max_files,
+            start: Instant::now(),
+        })
+    }
+
+    fn rotated_path(&self, index: u32) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(format!(".{index}"));
+        path.into()
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        for index in (1..self.max_files).rev() {
+            let from = self.rotated_path(index);
+            if from.exists() {
+                fs::rename(&from, self.rotated_path(index + 1))?;
+            }
+        }
+        if self.max_files > 0 {
+            fs::rename(&self.path, self.rotated_path(1))?;
+        }
+        self.file = File::create(&self.path)?;
+        self.size = 0;
+        Ok(())
+    }
+}
+
+impl LogSink for FileSink {
+    fn write(&mut self, record: &Record) {
+        let line = format!("[{:>9.3}] {record}\n", self.start.elapsed().as_secs_f64());
+        let len = line.len() as u64;
+        if self.size > 0
+            && self.size + len > self.max_size
+            && let Err(e) = self.rotate()
+        {
+            eprintln!("Failed to rotate {}: {e}", self.path.display());
+        }
+        match self.file.write_all(line.as_bytes()) {
+            Ok(()) => self.size += len,
+            Err(e) => eprintln!("Failed to write to {}: {e}", self.path.display()),
+        }
+    }
+
+    fn flush(&mut self) {
+        let _ = self.file.flush();
+    }
+}
+
+/// A line kept by a [`ConsoleSink`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConsoleLine {
+    pub level: Level,
+    pub target: String,
+    pub message: String,
+}
+
+/// Keeps the latest `capacity` messages for an in-game console to draw. Clones share the
+/// lines, so keep one and install another with [`add_sink`].
+#[derive(Clone)]
+pub struct ConsoleSink {
+    lines: Arc<Mutex<VecDeque<ConsoleLine>>>,
+    capacity: usize,
+}
+
+impl ConsoleSink {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            lines: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+            capacity,
+        }
+    }
+
+    /// The kept lines, oldest first.
+    pub fn lines(&self) -> Vec<ConsoleLine> {
+        lock(&self.lines).iter().cloned().collect()
+    }
+
+    pub fn clear(&self) {
+        lock(&self.lines).clear();
+    }
+}
+
+impl LogSink for ConsoleSink {
+    fn write(&mut self, record: &Record) {
+        let mut lines = lock(&self.lines);
+        if lines.len() == self.capacity {
+            lines.pop_front();
+        }
+        if self.capacity > 0 {
+            lines.push_back(ConsoleLine {
+                level: record.level,
+                target: record.target.to_string(),
+                message: record.args.to_string(),
+            });
+        }
+    }
+}
+
+struct Logger {
+    filter: Filter,
+    sinks: Vec<Box<dyn LogSink>>,
+}
+
+/// The most verbose level any target keeps, so disabled messages are skipped without locking.
+static MAX_LEVEL: AtomicU8 = AtomicU8::new(Level::Info as u8);
+
+fn logger() -> MutexGuard<'static, Logger> {
+    static LOGGER: OnceLock<Mutex<Logger>> = OnceLock::new();
+    lock(LOGGER.get_or_init(|| {
+        Mutex::new(Logger {
+            filter: Filter::default(),
+            sinks: vec![Box::new(StderrSink)],
+        })
+    }))
+}
+
+/// A panic while logging shouldn't silence the log for good.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Sets up logging from the `[log]` config section: the filter, stderr output and the log file.
+pub fn init(config: &LogConfig) -> io::Result<()> {
+    let file = match &config.file {
+        Some(path) => Some(FileSink::new(path, config.max_file_size, config.max_files)?),
+        None => None,
+    };
+
+    set_filter(config.level.clone());
+    let mut logger = logger();
+    logger.sinks.clear();
+    if config.stderr {
+        logger.sinks.push(Box::new(StderrSink));
+    }
+    if let Some(file) = file {
+        logger.sinks.push(Box::new(file));
+    }
+    Ok(())
+}
+
+pub fn set_filter(filter: Filter) {
+    let max_level = filter.max_level().map_or(0, |level| level as u8);
+    logger().filter = filter;
+    MAX_LEVEL.store(max_level, Ordering::Relaxed);
+}
+
+pub fn filter() -> Filter {
+    logger().filter.clone()
+}
+
+/// Adds a sink after the installed ones.
+pub fn add_sink(sink: impl LogSink + 'static) {
+    logger().sinks.push(Box::new(sink));
+}
+
+/// Removes every sink, including the default stderr one.
+pub fn clear_sinks() {
+    let mut logger = logger();
+    for sink in &mut logger.sinks {
+        sink.flush();
+    }
+    logger.sinks.clear();
+}
+
+/// Whether a message at `level` for `target` would be kept.
+pub fn enabled(level: Level, target: &str) -> bool {
+    level as u8 <= MAX_LEVEL.load(Ordering::Relaxed) && logger().filter.enabled(level, target)
+}
+
+/// Writes a message to every sink without checking the filter. Called by the macros.
+pub fn log(level: Level, target: &str, args: fmt::Arguments) {
+    let record = Record {
+        level,
+        target,
+        args,
+    };
+    for sink in &mut logger().sinks {
+        sink.write(&record);
+    }
+}
+
+pub fn flush() {
+    for sink in &mut logger().sinks {
+        sink.flush();
+    }
+}
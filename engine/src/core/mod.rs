@@ -1 +1,13 @@
 pub mod application;
+pub mod config;
+pub mod cvars;
+pub mod error;
+pub mod events;
+pub mod frame_pacer;
+pub mod log;
+pub mod plugin;
+pub mod profiler;
+pub mod schedule;
+pub mod simulation;
+pub mod state;
+pub mod window;
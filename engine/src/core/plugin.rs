@@ -0,0 +1,49 @@
+//! Plugins package a subsystem's uniques, systems and frame hooks so an application can pick
+//! which ones it runs. The engine's own input, physics, animation and rendering support are
+//! plugins too; see [`default_plugins`].
+
+use sdl3::event::Event;
+use shipyard::World;
+
+use crate::core::config::EngineConfig;
+use crate::core::schedule::Schedule;
+use crate::graphics::RenderPlugin;
+use crate::input::InputPlugin;
+use crate::physics::PhysicsPlugin;
+use crate::scene::{AnimationPlugin, TransformPlugin};
+
+pub trait Plugin {
+    /// Identifies the plugin for
+    /// [`Application::disable_plugin`](crate::core::application::Application::disable_plugin).
+    fn name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
+
+    /// Adds the plugin's uniques and systems. Called once, in the order the plugins were
+    /// added, before `Game::on_init`.
+    fn build(&mut self, world: &mut World, schedule: &mut Schedule, config: &EngineConfig);
+
+    /// Called once after `Game::on_init` and the `Startup` stage.
+    fn startup(&mut self, _world: &mut World) {}
+
+    /// Called every frame once the input is applied, before the `PreUpdate` stage.
+    fn update(&mut self, _world: &mut World, _delta_time: f32) {}
+
+    /// Called for every window event that is passed on to the game.
+    fn on_event(&mut self, _world: &mut World, _event: &Event) {}
+
+    /// Called when a state switch resets the world, after every entity was deleted. Plugins
+    /// put back the uniques that belong to a single level.
+    fn reset(&mut self, _world: &mut World, _config: &EngineConfig) {}
+}
+
+/// The engine's built-in plugins, in the order an application adds them.
+pub fn default_plugins() -> Vec<Box<dyn Plugin>> {
+    vec![
+        Box::new(InputPlugin),
+        Box::new(PhysicsPlugin),
+        Box::new(AnimationPlugin),
+        Box::new(TransformPlugin),
+        Box::new(RenderPlugin),
+    ]
+}
@@ -0,0 +1,272 @@
+//! Frame profiler, compiled in with the `profiler` cargo feature.
+//!
+//! CPU time is measured with [`profile_scope!`](crate::profile_scope) guards, GPU time with
+//! timestamp queries the renderer writes around each of its passes. Every scope is both added
+//! to per-name totals (see [`report`]) and kept as a trace event that [`write_chrome_trace`]
+//! saves in the Chrome trace format, viewable in `chrome://tracing` or Perfetto.
+//!
+//! Without the feature `profile_scope!` expands to nothing and none of this module exists.
+
+/// Measures CPU time from this point to the end of the enclosing block under `name`.
+///
+/// ```ignore
+/// fn update_boids() {
+///     profile_scope!("boids");
+///     // ...
+/// }
+/// ```
+#[cfg(feature = "profiler")]
+#[macro_export]
+macro_rules! profile_scope {
+    ($name:expr) => {
+        let _profile_scope = $crate::core::profiler::Scope::new($name);
+    };
+}
+
+/// Measures CPU time from this point to the end of the enclosing block under `name`.
+#[cfg(not(feature = "profiler"))]
+#[macro_export]
+macro_rules! profile_scope {
+    ($name:expr) => {};
+}
+
+#[cfg(feature = "profiler")]
+pub use enabled::*;
+
+#[cfg(feature = "profiler")]
+mod enabled {
+    use std::collections::BTreeMap;
+    use std::fmt::Write as _;
+    use std::io;
+    use std::path::Path;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::{Mutex, OnceLock};
+    use std::time::{Duration, Instant};
+
+    /// Trace events stop being kept after this many; totals keep counting.
+    const MAX_TRACE_EVENTS: usize = 1_000_000;
+
+    /// Trace thread id used for GPU scopes. CPU threads are numbered from 1.
+    const GPU_TRACK: u64 = 0;
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+    pub enum Timeline {
+        Cpu,
+        Gpu,
+    }
+
+    /// Totals for one scope name over every frame since the last [`reset`].
+    #[derive(Clone, Debug)]
+    pub struct ScopeStats {
+        pub timeline: Timeline,
+        pub name: &'static str,
+        pub calls: u64,
+        pub total: Duration,
+        pub max: Duration,
+    }
+
+    struct TraceEvent {
+        name: &'static str,
+        track: u64,
+        start_us: f64,
+        duration_us: f64,
+    }
+
+    struct State {
+        frames: u64,
+        stats: BTreeMap<(Timeline, &'static str), ScopeStats>,
+        events: Vec<TraceEvent>,
+    }
+
+    static STATE: Mutex<State> = Mutex::new(State {
+        frames: 0,
+        stats: BTreeMap::new(),
+        events: Vec::new(),
+    });
+
+    static NEXT_THREAD: AtomicU64 = AtomicU64::new(1);
+
+    thread_local! {
+        static THREAD: u64 = NEXT_THREAD.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Guard created by [`profile_scope!`](crate::profile_scope); records its lifetime when
+    /// dropped.
+    pub struct Scope {
+        name: &'static str,
+        start: Instant,
+    }
+
+    impl Scope {
+        pub fn new(name: &'static str) -> Self {
+            Self {
+                name,
+                start: Instant::now(),
+            }
+        }
+    }
+
+    impl Drop for Scope {
+        fn drop(&mut self) {
+            let duration = self.start.elapsed();
+            let track = THREAD.with(|id| *id);
+            record(
+                Timeline::Cpu,
+                self.name,
+                track,
+                micros_since_epoch(self.start),
+                duration,
+            );
+        }
+    }
+
+    fn epoch() -> Instant {
+        static EPOCH: OnceLock<Instant> = OnceLock::new();
+        *EPOCH.get_or_init(Instant::now)
+    }
+
+    fn micros_since_epoch(instant: Instant) -> f64 {
+        instant.saturating_duration_since(epoch()).as_secs_f64() * 1e6
+    }
+
+    /// Microseconds since the profiler's epoch, the time base of the trace.
+    pub(crate) fn now_us() -> f64 {
+        micros_since_epoch(Instant::now())
+    }
+
+    /// Adds a GPU pass measured by the renderer, starting `start_us` into the trace.
+    pub(crate) fn record_gpu(name: &'static str, start_us: f64, duration: Duration) {
+        record(Timeline::Gpu, name, GPU_TRACK, start_us, duration);
+    }
+
+    fn record(
+        timeline: Timeline,
+        name: &'static str,
+        track: u64,
+        start_us: f64,
+        duration: Duration,
+    ) {
+        let mut state = STATE.lock().unwrap();
+
+        let stats = state
+            .stats
+            .entry((timeline, name))
+            .or_insert_with(|| ScopeStats {
+                timeline,
+                name,
+                calls: 0,
+                total: Duration::ZERO,
+                max: Duration::ZERO,
+            });
+        stats.calls += 1;
+        stats.total += duration;
+        stats.max = stats.max.max(duration);
+
+        if state.events.len() < MAX_TRACE_EVENTS {
+            state.events.push(TraceEvent {
+                name,
+                track,
+                start_us,
+                duration_us: duration.as_secs_f64() * 1e6,
+            });
+        }
+    }
+
+    /// Marks the start of a frame; averages in [`report`] are per frame.
+    pub fn new_frame() {
+        STATE.lock().unwrap().frames += 1;
+    }
+
+    /// Number of frames since the last [`reset`].
+    pub fn frames() -> u64 {
+        STATE.lock().unwrap().frames
+    }
+
+    /// Totals of every scope seen since the last [`reset`], CPU scopes first.
+    pub fn stats() -> Vec<ScopeStats> {
+        STATE.lock().unwrap().stats.values().cloned().collect()
+    }
+
+    /// Drops all totals and trace events.
+    pub fn reset() {
+        let mut state = STATE.lock().unwrap();
+        state.frames = 0;
+        state.stats.clear();
+        state.events.clear();
+    }
+
+    /// A table of the average time per frame, calls per frame and longest call of every scope.
+    pub fn report() -> String {
+        let frames = frames().max(1);
+        let mut report = format!(
+            "{:<4} {:<28} {:>10} {:>8} {:>10}\n",
+            "", "scope", "ms/frame", "calls", "max ms"
+        );
+
+        for stats in stats() {
+            let timeline = match stats.timeline {
+                Timeline::Cpu => "cpu",
+                Timeline::Gpu => "gpu",
+            };
+            let _ = writeln!(
+                report,
+                "{:<4} {:<28} {:>10.3} {:>8.1} {:>10.3}",
+                timeline,
+                stats.name,
+                stats.total.as_secs_f64() * 1e3 / frames as f64,
+                stats.calls as f64 / frames as f64,
+                stats.max.as_secs_f64() * 1e3,
+            );
+        }
+        report
+    }
+
+    /// Every recorded scope as Chrome trace event JSON.
+    pub fn chrome_trace() -> String {
+        let state = STATE.lock().unwrap();
+        let mut json = String::from("{\"displayTimeUnit\":\"ms\",\"traceEvents\":[\n");
+
+        let _ = write!(
+            json,
+            "{{\"name\":\"thread_name\",\"ph\":\"M\",\"pid\":1,\"tid\":{GPU_TRACK},\"args\":{{\"name\":\"GPU\"}}}}"
+        );
+        for event in &state.events {
+            let _ = write!(
+                json,
+                ",\n{{\"name\":\"{}\",\"cat\":\"{}\",\"ph\":\"X\",\"pid\":1,\"tid\":{},\"ts\":{:.3},\"dur\":{:.3}}}",
+                escape_json(event.name),
+                if event.track == GPU_TRACK {
+                    "gpu"
+                } else {
+                    "cpu"
+                },
+                event.track,
+                event.start_us,
+                event.duration_us,
+            );
+        }
+
+        json.push_str("\n]}\n");
+        json
+    }
+
+    /// Saves [`chrome_trace`] to `path`.
+    pub fn write_chrome_trace(path: impl AsRef<Path>) -> io::Result<()> {
+        std::fs::write(path, chrome_trace())
+    }
+
+    fn escape_json(text: &str) -> String {
+        let mut escaped = String::with_capacity(text.len());
+        for c in text.chars() {
+            match c {
+                '"' => escaped.push_str("\\\""),
+                '\\' => escaped.push_str("\\\\"),
+                c if c.is_control() => {
+                    let _ = write!(escaped, "\\u{:04x}", c as u32);
+                }
+                c => escaped.push(c),
+            }
+        }
+        escaped
+    }
+}
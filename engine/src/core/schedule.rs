@@ -0,0 +1,156 @@
+use shipyard::{IntoWorkloadSystem, Workload, World};
+
+use crate::profile_scope;
+
+/// Named points in the frame where systems run, in execution order.
+///
+/// `Startup` runs once after `Game::on_init`. Every frame then runs `PreUpdate`, `FixedUpdate`
+/// zero or more times (once per fixed physics step), `Update`, `PostUpdate` and `PreRender`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Stage {
+    Startup,
+    PreUpdate,
+    FixedUpdate,
+    Update,
+    PostUpdate,
+    PreRender,
+}
+
+impl Stage {
+    pub const ALL: [Stage; 6] = [
+        Stage::Startup,
+        Stage::PreUpdate,
+        Stage::FixedUpdate,
+        Stage::Update,
+        Stage::PostUpdate,
+        Stage::PreRender,
+    ];
+
+    /// Name of the shipyard workload backing this stage.
+    pub fn label(self) -> &'static str {
+        match self {
+            Stage::Startup => "stage::startup",
+            Stage::PreUpdate => "stage::pre_update",
+            Stage::FixedUpdate => "stage::fixed_update",
+            Stage::Update => "stage::update",
+            Stage::PostUpdate => "stage::post_update",
+            Stage::PreRender => "stage::pre_render",
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Collects the engine's and the game's systems per [`Stage`] and turns them into one shipyard
+/// workload per stage.
+///
+/// Systems of a stage run in parallel when their borrows don't conflict; see
+/// [`Schedule::batch_plan`] for how a stage was split into batches. Systems whose borrows
+/// conflict run in the order they were added. Use shipyard's `before_all` / `after_all` / `tag`
+/// on a system to order it explicitly, e.g.
+///
+/// ```ignore
+/// schedule.add_system(Stage::FixedUpdate, steer_boids.before_all(physics_step));
+/// ```
+pub struct Schedule {
+    scope: Option<&'static str>,
+    workloads: Vec<Workload>,
+}
+
+impl Schedule {
+    pub fn new() -> Self {
+        Self::with_scope(None)
+    }
+
+    /// A schedule whose workloads are named `<scope>/stage::update`, ... so they can be
+    /// registered next to the engine's. Game states use it for their own systems; run its
+    /// stages with [`Schedule::run_scoped`].
+    pub fn scoped(scope: &'static str) -> Self {
+        Self::with_scope(Some(scope))
+    }
+
+    fn with_scope(scope: Option<&'static str>) -> Self {
+        let mut schedule = Self {
+            scope,
+            workloads: Vec::new(),
+        };
+        schedule.workloads = Stage::ALL
+            .iter()
+            .map(|&stage| schedule.empty_workload(stage))
+            .collect();
+        schedule
+    }
+
+    fn empty_workload(&self, stage: Stage) -> Workload {
+        match self.scope {
+            Some(scope) => Workload::new(scoped_label(scope, stage)),
+            None => Workload::new(stage.label()),
+        }
+    }
+
+    /// Adds `system` to the end of `stage`.
+    pub fn add_system<B, R, S: IntoWorkloadSystem<B, R>>(
+        &mut self,
+        stage: Stage,
+        system: S,
+    ) -> &mut Self {
+        let empty = self.empty_workload(stage);
+        let workload = &mut self.workloads[stage.index()];
+        let current = std::mem::replace(workload, empty);
+        *workload = current.with_system(system);
+        self
+    }
+
+    /// Adds a whole workload (for example a game's own grouping of systems) to `stage`.
+    pub fn add_workload(&mut self, stage: Stage, other: Workload) -> &mut Self {
+        let empty = self.empty_workload(stage);
+        let workload = &mut self.workloads[stage.index()];
+        let current = std::mem::replace(workload, empty);
+        *workload = current.merge(other);
+        self
+    }
+
+    /// Registers every stage as a workload of `world`. Fails if a stage's ordering constraints
+    /// can't be satisfied or a stage was already registered.
+    pub fn add_to_world(self, world: &World) -> Result<(), shipyard::error::AddWorkload> {
+        for workload in self.workloads {
+            workload.add_to_world(world)?;
+        }
+        Ok(())
+    }
+
+    /// Runs the workload registered for `stage`.
+    pub fn run(world: &World, stage: Stage) {
+        profile_scope!(stage.label());
+        world
+            .run_workload(stage.label())
+            .unwrap_or_else(|e| panic!("Failed to run {:?} stage: {e:?}", stage));
+    }
+
+    /// Runs `stage` of a schedule created with [`Schedule::scoped`].
+    pub fn run_scoped(world: &World, scope: &'static str, stage: Stage) {
+        profile_scope!(scope);
+        world
+            .run_workload(scoped_label(scope, stage))
+            .unwrap_or_else(|e| panic!("Failed to run {:?} stage of {scope}: {e:?}", stage));
+    }
+
+    /// Human-readable description of every registered workload: its batches, the systems in
+    /// each batch, what they borrow and which borrow forced a new batch. Systems in the same
+    /// batch run in parallel.
+    pub fn batch_plan(world: &World) -> String {
+        format!("{:#?}", world.workloads_info())
+    }
+}
+
+fn scoped_label(scope: &str, stage: Stage) -> String {
+    format!("{scope}/{}", stage.label())
+}
+
+impl Default for Schedule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
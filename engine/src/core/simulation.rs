@@ -0,0 +1,456 @@
+//! The game without graphics: the world, the states, the plugins and the update stages, with
+//! no window, no SDL video and no Vulkan. [`Application`](crate::core::application::Application)
+//! renders on top of one; on its own it runs a dedicated server or the game's systems in tests.
+//!
+//! ```ignore
+//! let mut simulation = Simulation::new(game, EngineConfig::default());
+//! simulation.run_ticks(120, 1.0 / 60.0);
+//! let world = simulation.game.get_world();
+//! ```
+
+use std::path::Path;
+
+use sdl3::event::Event;
+use shipyard::World;
+
+use crate::core::application::Game;
+use crate::core::config::{EngineConfig, PhysicsConfig};
+use crate::core::cvars::CVarRegistry;
+use crate::core::events::{self, Events, WindowResized};
+use crate::core::frame_pacer::FramePacer;
+use crate::core::plugin::{self, Plugin};
+use crate::core::schedule::{Schedule, Stage};
+use crate::core::state::{AppControl, StateStack};
+use crate::core::window::WindowSettings;
+use crate::input::input_manager::InputManager;
+use crate::input::recording::InputEvent;
+use crate::physics::physics_engine::PhysicsEngine;
+use crate::profile_scope;
+use crate::scene::components::delta_time::DeltaTime;
+use crate::scene::components::fixed_time::FixedTime;
+use crate::scene::prefab::PrefabLibrary;
+use crate::scene::scene_file::SceneRegistry;
+use crate::{error, info, warn};
+
+pub struct Simulation<G: Game> {
+    pub game: G,
+    /// Settings the game was created with. Environment and physics fields may still be changed
+    /// before the first frame; the physics step later through the `physics.rate` cvar.
+    pub config: EngineConfig,
+    pub physics_accumulator: f32,
+    /// `physics.rate` cvar value last copied to the physics step.
+    physics_rate: f32,
+    states: StateStack,
+    plugins: Vec<Box<dyn Plugin>>,
+    started: bool,
+    shut_down: bool,
+}
+
+impl<G: Game> Simulation<G> {
+    /// Runs the game with the default plugins. `RenderPlugin` stays in: it only adds the
+    /// asset manager and the draw list, which games load their models into.
+    pub fn new(game: G, config: EngineConfig) -> Self {
+        Self {
+            game,
+            config,
+            physics_accumulator: 0.0,
+            physics_rate: 0.0,
+            states: StateStack::new(),
+            plugins: plugin::default_plugins(),
+            started: false,
+            shut_down: false,
+        }
+    }
+
+    /// Adds a plugin after the built-in ones. Plugins are built when the simulation starts, so
+    /// they have to be added before the first tick.
+    pub fn add_plugin(&mut self, plugin: impl Plugin + 'static) -> &mut Self {
+        assert!(
+            !self.started,
+            "plugins must be added before the application starts"
+        );
+        self.plugins.push(Box::new(plugin));
+        self
+    }
+
+    /// Removes a built-in (or previously added) plugin, e.g. `PhysicsPlugin` for a game without
+    /// physics.
+    pub fn disable_plugin<P: Plugin>(&mut self) -> &mut Self {
+        assert!(
+            !self.started,
+            "plugins must be disabled before the application starts"
+        );
+        let name = std::any::type_name::<P>();
+        self.plugins.retain(|plugin| plugin.name() != name);
+        self
+    }
+
+    /// Names of the plugins the simulation runs, in the order they are built.
+    pub fn plugin_names(&self) -> Vec<&'static str> {
+        self.plugins.iter().map(|plugin| plugin.name()).collect()
+    }
+
+    pub fn is_started(&self) -> bool {
+        self.started
+    }
+
+    /// Inserts the engine uniques, builds the plugins and the schedule, runs `Game::on_init`
+    /// and the `Startup` stage. Called by the first tick; later calls do nothing.
+    pub fn init(&mut self) {
+        if self.started {
+            return;
+        }
+        let entered = self.build();
+        self.startup(&entered);
+    }
+
+    /// The first half of [`Simulation::init`], up to entering the states `Game::on_init`
+    /// pushed. Returns their names for [`Simulation::startup`]; the application uploads what
+    /// they loaded in between.
+    pub(crate) fn build(&mut self) -> Vec<&'static str> {
+        self.started = true;
+
+        // A config built in code can bypass the file's range check; run at the nearest
+        // supported rate, like the cvar does
+        let rate = 1.0 / self.config.physics.fixed_dt;
+        let clamped = rate.clamp(PhysicsConfig::MIN_RATE, PhysicsConfig::MAX_RATE);
+        if clamped != rate {
+            warn!("physics.fixed_dt gives {rate} steps per second, using {clamped}");
+            self.config.physics.fixed_dt = 1.0 / clamped;
+        }
+
+        self.game
+            .get_world_mut()
+            .add_unique(FixedTime::new(self.config.physics.fixed_dt));
+        self.game.get_world_mut().add_unique(AppControl::default());
+        events::add_event::<WindowResized>(self.game.get_world());
+        self.game
+            .get_world()
+            .add_unique(WindowSettings::from_config(&self.config));
+
+        let cvars = CVarRegistry::with_engine_defaults(&self.config);
+        self.physics_rate = cvars.float("physics.rate");
+        self.game.get_world_mut().add_unique(cvars);
+        self.game
+            .get_world_mut()
+            .add_unique(SceneRegistry::with_engine_components());
+        self.game.get_world_mut().add_unique(PrefabLibrary::new());
+
+        let mut schedule = Schedule::new();
+        for plugin in &mut self.plugins {
+            plugin.build(self.game.get_world_mut(), &mut schedule, &self.config);
+        }
+
+        self.game.on_init();
+
+        let autoexec = Path::new(&self.config.console.autoexec);
+        if autoexec.exists() {
+            let mut cvars = self
+                .game
+                .get_world_mut()
+                .get_unique::<&mut CVarRegistry>()
+                .unwrap();
+            if let Err(e) = cvars.exec_file(autoexec) {
+                warn!("{e}");
+            }
+        }
+
+        self.game.build_schedule(&mut schedule);
+        schedule
+            .add_to_world(self.game.get_world())
+            .unwrap_or_else(|e| panic!("Invalid system schedule: {e:?}"));
+
+        let config = &self.config;
+        let plugins = &mut self.plugins;
+        self.states
+            .apply_pending(self.game.get_world_mut(), |world| {
+                reset_level(world, config, plugins)
+            })
+            .entered
+    }
+
+    /// Runs the `Startup` stage, the startup systems of the `entered` states and the plugins'
+    /// startup hooks.
+    pub(crate) fn startup(&mut self, entered: &[&'static str]) {
+        Schedule::run(self.game.get_world(), Stage::Startup);
+        self.run_state_startups(entered);
+
+        for plugin in &mut self.plugins {
+            plugin.startup(self.game.get_world_mut());
+        }
+    }
+
+    /// Advances the game by one frame of `dt` seconds with no input: the update stages, as many
+    /// fixed physics steps as fit, the state changes and quit requests of the frame.
+    pub fn step(&mut self, dt: f32) {
+        self.init();
+
+        self.begin_frame(&[]);
+        self.apply_cvars();
+        self.update(dt);
+
+        let entered = self.apply_state_transitions();
+        self.run_state_startups(&entered);
+        self.confirm_quit();
+        self.end_frame();
+    }
+
+    /// Runs `ticks` frames of `dt` seconds each, as fast as possible, stopping early if the
+    /// game quits. Returns the number of frames run.
+    pub fn run_ticks(&mut self, ticks: u32, dt: f32) -> u32 {
+        self.init();
+
+        for tick in 0..ticks {
+            if self.is_quit_requested() {
+                return tick;
+            }
+            self.step(dt);
+        }
+        ticks
+    }
+
+    /// Runs against the real clock until the game quits, then shuts down. The frame rate is
+    /// paced like the application's, so a dedicated server sets `config.frame.max_fps` to its
+    /// tick rate instead of spinning.
+    pub fn run(mut self) {
+        self.init();
+
+        let mut frame_pacer = FramePacer::new(&self.config.frame);
+        while !self.is_quit_requested() {
+            let dt = frame_pacer.next_frame();
+            self.step(dt);
+        }
+
+        self.shutdown();
+    }
+
+    /// Applies the frame's input to the [`InputManager`], sends it as events and runs the
+    /// console commands bound to the pressed keys.
+    pub(crate) fn begin_frame(&mut self, input: &[InputEvent]) {
+        {
+            profile_scope!("input");
+            // Absent when the game disabled `InputPlugin`
+            if let Ok(mut input_manager) =
+                self.game.get_world_mut().get_unique::<&mut InputManager>()
+            {
+                input_manager.begin_frame();
+                for event in input {
+                    input_manager.apply(event);
+                }
+            }
+            if let Ok(mut events) = self
+                .game
+                .get_world_mut()
+                .get_unique::<&mut Events<InputEvent>>()
+            {
+                events.send_batch(input.iter().copied());
+            }
+        }
+
+        let mut cvars = self
+            .game
+            .get_world_mut()
+            .get_unique::<&mut CVarRegistry>()
+            .unwrap();
+        for event in input {
+            if let InputEvent::KeyDown(keycode) = event
+                && let Some(command) = cvars.binding(*keycode).map(str::to_string)
+            {
+                match cvars.execute(&command) {
+                    Ok(output) if !output.is_empty() => info!("{output}"),
+                    Ok(_) => {}
+                    Err(e) => error!("{command}: {e}"),
+                }
+            }
+        }
+    }
+
+    /// Runs the update stages for a frame of `dt` seconds, the fixed physics steps that fit in
+    /// it and the game's and the states' update hooks, up to and including `PreRender`.
+    pub(crate) fn update(&mut self, dt: f32) {
+        profile_scope!("update");
+
+        //adds deltatime component
+        self.game.get_world().add_unique(DeltaTime(dt));
+
+        for plugin in &mut self.plugins {
+            plugin.update(self.game.get_world_mut(), dt);
+        }
+
+        self.run_stage(Stage::PreUpdate);
+
+        self.physics_accumulator += dt;
+
+        let physics_dt = self.config.physics.fixed_dt;
+        while self.physics_accumulator >= physics_dt {
+            self.run_stage(Stage::FixedUpdate);
+            self.physics_accumulator -= physics_dt;
+        }
+        self.game.get_world().add_unique(FixedTime {
+            step: physics_dt,
+            alpha: self.physics_accumulator / physics_dt,
+        });
+
+        {
+            profile_scope!("game::on_update");
+            self.game.on_update(dt);
+            self.states.update(self.game.get_world_mut(), dt);
+        }
+        self.run_stage(Stage::Update);
+        self.run_stage(Stage::PostUpdate);
+
+        {
+            profile_scope!("game::on_render");
+            self.game.on_render();
+        }
+        self.run_stage(Stage::PreRender);
+    }
+
+    /// Runs the engine's and the game's systems for `stage`, then the active state's.
+    fn run_stage(&self, stage: Stage) {
+        let world = self.game.get_world();
+        Schedule::run(world, stage);
+        self.states.run_stage(world, stage);
+    }
+
+    /// Carries out the state changes requested this frame and returns the states that were
+    /// entered. Their startup systems run in [`Simulation::run_state_startups`].
+    pub(crate) fn apply_state_transitions(&mut self) -> Vec<&'static str> {
+        let config = &self.config;
+        let plugins = &mut self.plugins;
+        let changes = self
+            .states
+            .apply_pending(self.game.get_world_mut(), |world| {
+                reset_level(world, config, plugins)
+            });
+
+        if changes.reset {
+            self.physics_accumulator = 0.0;
+        }
+        changes.entered
+    }
+
+    /// Runs the `Startup` systems of the entered states that are still on the stack.
+    pub(crate) fn run_state_startups(&self, entered: &[&'static str]) {
+        let active = self.states.names();
+        for &name in entered.iter().filter(|name| active.contains(name)) {
+            Schedule::run_scoped(self.game.get_world(), name, Stage::Startup);
+        }
+    }
+
+    /// Ends the frame of the event queues.
+    pub(crate) fn end_frame(&self) {
+        events::update_events(self.game.get_world());
+    }
+
+    /// Passes a window event to the plugins, the game and the states.
+    pub(crate) fn dispatch_event(&mut self, event: &Event) {
+        for plugin in &mut self.plugins {
+            plugin.on_event(self.game.get_world_mut(), event);
+        }
+        self.game.on_event(event);
+        self.states.event(self.game.get_world_mut(), event);
+    }
+
+    /// The game's states, bottom first.
+    pub fn states(&self) -> &StateStack {
+        &self.states
+    }
+
+    /// Whether [`AppControl::quit`] was called and not cancelled. [`Simulation::run`] and
+    /// [`Simulation::run_ticks`] stop after the frame it was called in.
+    pub fn is_quit_requested(&self) -> bool {
+        self.game
+            .get_world()
+            .get_unique::<&AppControl>()
+            .is_ok_and(|control| control.is_quit_requested())
+    }
+
+    /// Asks the game and the states whether a requested quit may go ahead, and withdraws the
+    /// request if one of them refuses.
+    pub(crate) fn confirm_quit(&mut self) {
+        if !self.is_quit_requested() {
+            return;
+        }
+
+        let confirmed =
+            self.game.on_quit_requested() && self.states.confirm_quit(self.game.get_world_mut());
+        if !confirmed {
+            self.game
+                .get_world()
+                .get_unique::<&mut AppControl>()
+                .unwrap()
+                .cancel_quit();
+        }
+    }
+
+    /// Runs console commands against the [`CVarRegistry`] and returns their output. Only
+    /// available after initialisation.
+    pub fn execute(&mut self, commands: &str) -> Result<String, String> {
+        let output = self
+            .game
+            .get_world_mut()
+            .get_unique::<&mut CVarRegistry>()
+            .map_err(|_| "the console is not available before initialisation".to_string())?
+            .execute(commands)?;
+        self.apply_cvars();
+        Ok(output)
+    }
+
+    /// Copies the `physics.rate` cvar to the physics step when it has changed.
+    pub(crate) fn apply_cvars(&mut self) {
+        let world = self.game.get_world();
+        let cvars = world.get_unique::<&CVarRegistry>().unwrap();
+
+        let rate = cvars.float("physics.rate");
+        if rate == self.physics_rate {
+            return;
+        }
+        self.physics_rate = rate;
+
+        let fixed_dt = 1.0 / rate;
+        self.config.physics.fixed_dt = fixed_dt;
+        if let Ok(mut physics) = world.get_unique::<&mut PhysicsEngine>() {
+            physics.integration_parameters.dt = fixed_dt;
+        }
+    }
+
+    /// How the engine's and the game's systems were split into parallel batches per stage.
+    /// Only available once the simulation has started.
+    pub fn batch_plan(&self) -> String {
+        Schedule::batch_plan(self.game.get_world())
+    }
+
+    /// Exits every state and calls `Game::on_shutdown`. Runs when [`Simulation::run`] returns
+    /// or the simulation is dropped; later calls do nothing.
+    pub fn shutdown(&mut self) {
+        if self.shut_down {
+            return;
+        }
+        self.shut_down = true;
+
+        // Only a started simulation has states and a game to shut down
+        if self.started {
+            self.states.exit_all(self.game.get_world_mut());
+            self.game.on_shutdown();
+        }
+    }
+}
+
+impl<G: Game> Drop for Simulation<G> {
+    fn drop(&mut self) {
+        // Don't run game code while unwinding
+        if !std::thread::panicking() {
+            self.shutdown();
+        }
+    }
+}
+
+/// Deletes every entity and lets the plugins start their per-level uniques over, for a state
+/// switch. Engine-wide uniques such as the input, the cvars and [`AppControl`] are kept.
+fn reset_level(world: &mut World, config: &EngineConfig, plugins: &mut [Box<dyn Plugin>]) {
+    world.clear();
+    for plugin in plugins {
+        plugin.reset(world, config);
+    }
+}
@@ -0,0 +1,237 @@
+//! Game states: menus, levels, pause screens and the like, kept on a stack.
+//!
+//! Only the state on top of the stack is active. It receives `on_update` / `on_event` and its
+//! systems (see [`GameState::build_schedule`]) run after the engine's in every stage; the
+//! states below it are paused until it is popped. States are changed through the
+//! [`AppControl`] unique, from game code or from systems, and the change happens at the end of
+//! the frame.
+
+use std::collections::HashSet;
+
+use sdl3::event::Event;
+use shipyard::{Unique, World};
+
+use crate::core::schedule::{Schedule, Stage};
+
+pub trait GameState: Send + Sync + 'static {
+    /// Identifies the state's systems; states of the same type share one set of workloads.
+    fn name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
+
+    /// Called when the state is pushed, before the frame it first runs in. Models loaded here
+    /// are uploaded to the GPU before that frame.
+    fn on_enter(&mut self, _world: &mut World) {}
+    /// Called when the state is popped, replaced or switched away from.
+    fn on_exit(&mut self, _world: &mut World) {}
+    /// Called when another state is pushed on top of this one.
+    fn on_pause(&mut self, _world: &mut World) {}
+    /// Called when the state above this one is popped.
+    fn on_resume(&mut self, _world: &mut World) {}
+    /// Called every frame while the state is on top, after `Game::on_update`.
+    fn on_update(&mut self, _world: &mut World, _delta_time: f32) {}
+    /// Called for window events while the state is on top, after `Game::on_event`.
+    fn on_event(&mut self, _world: &mut World, _event: &Event) {}
+    /// Called at the end of a frame in which quitting was requested. Return `false` to keep
+    /// running, e.g. to ask about unsaved changes first.
+    fn on_quit_requested(&mut self, _world: &mut World) -> bool {
+        true
+    }
+    /// Registers systems that only run while this state is on top. Called the first time a
+    /// state of this type is entered. `Startup` systems run every time the state is entered.
+    fn build_schedule(&mut self, _schedule: &mut Schedule) {}
+}
+
+enum Transition {
+    Push(Box<dyn GameState>),
+    Pop,
+    Replace(Box<dyn GameState>),
+    Switch(Box<dyn GameState>),
+}
+
+/// Requests state changes and quitting. Inserted by the application before `Game::on_init`;
+/// requests are carried out at the end of the current frame, in the order they were made.
+#[derive(Unique, Default)]
+pub struct AppControl {
+    transitions: Vec<Transition>,
+    quit: bool,
+}
+
+impl AppControl {
+    /// Pauses the current state and enters `state` on top of it, e.g. a pause menu.
+    pub fn push(&mut self, state: impl GameState) {
+        self.transitions.push(Transition::Push(Box::new(state)));
+    }
+
+    /// Exits the current state and resumes the one below it. Popping the last state quits.
+    pub fn pop(&mut self) {
+        self.transitions.push(Transition::Pop);
+    }
+
+    /// Exits the current state and enters `state` in its place.
+    pub fn replace(&mut self, state: impl GameState) {
+        self.transitions.push(Transition::Replace(Box::new(state)));
+    }
+
+    /// Exits every state, resets the world and enters `state`: a level change. All entities
+    /// are deleted and the physics world, the loaded models and the sun start over.
+    pub fn switch_to(&mut self, state: impl GameState) {
+        self.transitions.push(Transition::Switch(Box::new(state)));
+    }
+
+    /// Exits the application after this frame, unless `Game::on_quit_requested` or a state's
+    /// [`GameState::on_quit_requested`] cancels it. Closing the window requests it too.
+    pub fn quit(&mut self) {
+        self.quit = true;
+    }
+
+    /// Withdraws a quit request made earlier in the frame.
+    pub fn cancel_quit(&mut self) {
+        self.quit = false;
+    }
+
+    pub fn is_quit_requested(&self) -> bool {
+        self.quit
+    }
+}
+
+/// What [`StateStack::apply_pending`] changed.
+#[derive(Debug, Default)]
+pub struct StateChanges {
+    /// Names of the states that were entered, in order.
+    pub entered: Vec<&'static str>,
+    /// Whether the world was reset by a switch.
+    pub reset: bool,
+}
+
+/// The game's states, bottom first. Owned by the application; exposed for inspection and for
+/// driving states without a renderer.
+#[derive(Default)]
+pub struct StateStack {
+    states: Vec<Box<dyn GameState>>,
+    scheduled: HashSet<&'static str>,
+}
+
+impl StateStack {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.states.is_empty()
+    }
+
+    /// Names of the states on the stack, bottom first.
+    pub fn names(&self) -> Vec<&'static str> {
+        self.states.iter().map(|state| state.name()).collect()
+    }
+
+    /// The active state's name.
+    pub fn current(&self) -> Option<&'static str> {
+        self.states.last().map(|state| state.name())
+    }
+
+    /// Carries out the transitions requested through [`AppControl`], including any requested
+    /// by the hooks they call. `reset_world` is called between exiting and entering the states
+    /// of a switch.
+    pub fn apply_pending(
+        &mut self,
+        world: &mut World,
+        mut reset_world: impl FnMut(&mut World),
+    ) -> StateChanges {
+        let mut changes = StateChanges::default();
+
+        loop {
+            let pending = std::mem::take(
+                &mut world
+                    .get_unique::<&mut AppControl>()
+                    .expect("AppControl unique is missing")
+                    .transitions,
+            );
+            if pending.is_empty() {
+                return changes;
+            }
+
+            for transition in pending {
+                match transition {
+                    Transition::Push(state) => {
+                        if let Some(top) = self.states.last_mut() {
+                            top.on_pause(world);
+                        }
+                        changes.entered.push(self.enter(world, state));
+                    }
+                    Transition::Pop => {
+                        if let Some(mut state) = self.states.pop() {
+                            state.on_exit(world);
+                        }
+                        match self.states.last_mut() {
+                            Some(top) => top.on_resume(world),
+                            None => world.get_unique::<&mut AppControl>().unwrap().quit(),
+                        }
+                    }
+                    Transition::Replace(state) => {
+                        if let Some(mut top) = self.states.pop() {
+                            top.on_exit(world);
+                        }
+                        changes.entered.push(self.enter(world, state));
+                    }
+                    Transition::Switch(state) => {
+                        self.exit_all(world);
+                        reset_world(world);
+                        changes.reset = true;
+                        changes.entered.push(self.enter(world, state));
+                    }
+                }
+            }
+        }
+    }
+
+    fn enter(&mut self, world: &mut World, mut state: Box<dyn GameState>) -> &'static str {
+        let name = state.name();
+        if self.scheduled.insert(name) {
+            let mut schedule = Schedule::scoped(name);
+            state.build_schedule(&mut schedule);
+            schedule
+                .add_to_world(world)
+                .unwrap_or_else(|e| panic!("Invalid system schedule for {name}: {e:?}"));
+        }
+        state.on_enter(world);
+        self.states.push(state);
+        name
+    }
+
+    /// Exits every state, top first.
+    pub fn exit_all(&mut self, world: &mut World) {
+        while let Some(mut state) = self.states.pop() {
+            state.on_exit(world);
+        }
+    }
+
+    /// Asks every state, top first, whether the application may quit. Stops at the first one
+    /// that refuses.
+    pub fn confirm_quit(&mut self, world: &mut World) -> bool {
+        self.states
+            .iter_mut()
+            .rev()
+            .all(|state| state.on_quit_requested(world))
+    }
+
+    /// Runs the active state's systems for `stage`.
+    pub fn run_stage(&self, world: &World, stage: Stage) {
+        if let Some(name) = self.current() {
+            Schedule::run_scoped(world, name, stage);
+        }
+    }
+
+    pub fn update(&mut self, world: &mut World, delta_time: f32) {
+        if let Some(state) = self.states.last_mut() {
+            state.on_update(world, delta_time);
+        }
+    }
+
+    pub fn event(&mut self, world: &mut World, event: &Event) {
+        if let Some(state) = self.states.last_mut() {
+            state.on_event(world, event);
+        }
+    }
+}
@@ -0,0 +1,130 @@
+//! Window and display settings that can be changed while the game runs.
+
+use sdl3::video::Window;
+use shipyard::Unique;
+use vulkano::swapchain::PresentMode;
+
+use crate::core::config::EngineConfig;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WindowMode {
+    Windowed,
+    /// A borderless window covering the display at the desktop resolution.
+    Borderless,
+    /// Exclusive fullscreen at the display mode closest to the requested size.
+    Fullscreen,
+}
+
+/// The window settings the game asks for. Change the fields from `Game::on_init`, a state or a
+/// system; the application applies the changes at the end of the frame and reports the result
+/// in [`WindowInfo`].
+#[derive(Unique, Clone, Debug, PartialEq)]
+pub struct WindowSettings {
+    pub mode: WindowMode,
+    /// Window size in windowed mode and the resolution in exclusive fullscreen, in window
+    /// coordinates. Borderless fullscreen always uses the desktop resolution.
+    pub width: u32,
+    pub height: u32,
+    /// Falls back to a mode the surface supports, see [`choose_present_mode`].
+    pub present_mode: PresentMode,
+}
+
+/// What the window and the swapchain ended up with. Kept up to date by the application.
+#[derive(Unique, Clone, Debug, PartialEq)]
+pub struct WindowInfo {
+    pub mode: WindowMode,
+    /// Size in window coordinates.
+    pub size: [u32; 2],
+    /// Size of the rendered images in pixels. Larger than `size` on high-DPI displays.
+    pub pixel_size: [u32; 2],
+    /// Content scale of the display the window is on, e.g. 1.5 at 150%. UI should be scaled by
+    /// this.
+    pub display_scale: f32,
+    pub present_mode: PresentMode,
+}
+
+impl WindowSettings {
+    pub fn from_config(config: &EngineConfig) -> Self {
+        Self {
+            mode: config.window.mode,
+            width: config.window.width,
+            height: config.window.height,
+            present_mode: config.renderer.present_mode,
+        }
+    }
+}
+
+impl WindowInfo {
+    pub(crate) fn of(window: &Window, mode: WindowMode, present_mode: PresentMode) -> Self {
+        let (width, height) = window.size();
+        let (pixel_width, pixel_height) = window.size_in_pixels();
+        Self {
+            mode,
+            size: [width, height],
+            pixel_size: [pixel_width, pixel_height],
+            display_scale: window.display_scale(),
+            present_mode,
+        }
+    }
+}
+
+/// Picks `requested` if the surface supports it. Otherwise immediate falls back to mailbox
+/// (both avoid waiting for vertical blank), and everything ends up at FIFO, which every
+/// surface supports.
+pub fn choose_present_mode(requested: PresentMode, supported: &[PresentMode]) -> PresentMode {
+    let fallback: &[PresentMode] = match requested {
+        PresentMode::Immediate => &[PresentMode::Mailbox],
+        _ => &[],
+    };
+    std::iter::once(requested)
+        .chain(fallback.iter().copied())
+        .find(|mode| supported.contains(mode))
+        .unwrap_or(PresentMode::Fifo)
+}
+
+/// Switches `window` from the `previous` settings to `settings`. The present mode is up to the
+/// renderer.
+pub(crate) fn apply_to_window(
+    window: &mut Window,
+    settings: &WindowSettings,
+    previous: &WindowSettings,
+) -> Result<(), String> {
+    let resized = settings.width != previous.width || settings.height != previous.height;
+
+    match settings.mode {
+        WindowMode::Windowed => {
+            if previous.mode != WindowMode::Windowed {
+                window.set_fullscreen(false).map_err(|e| e.to_string())?;
+            }
+            if resized || previous.mode != WindowMode::Windowed {
+                window
+                    .set_size(settings.width, settings.height)
+                    .map_err(|e| e.to_string())?;
+            }
+        }
+        WindowMode::Borderless => {
+            if previous.mode != WindowMode::Borderless {
+                window.set_display_mode(None).map_err(|e| e.to_string())?;
+                window.set_fullscreen(true).map_err(|e| e.to_string())?;
+            }
+        }
+        WindowMode::Fullscreen => {
+            if previous.mode != WindowMode::Fullscreen || resized {
+                let display = window.get_display().map_err(|e| e.to_string())?;
+                let display_mode = display
+                    .get_closest_display_mode(
+                        settings.width as i32,
+                        settings.height as i32,
+                        0.0,
+                        true,
+                    )
+                    .map_err(|e| e.to_string())?;
+                window
+                    .set_display_mode(Some(display_mode))
+                    .map_err(|e| e.to_string())?;
+                window.set_fullscreen(true).map_err(|e| e.to_string())?;
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,108 @@
+//! The interface between the application and what draws its frames. [`Renderer`] draws with
+//! Vulkan; [`RecordingRenderer`] only keeps what every frame submitted, so tests can check what
+//! a game renders without a GPU.
+//!
+//! [`Renderer`]: crate::graphics::renderer::Renderer
+//! [`RecordingRenderer`]: crate::graphics::recording_renderer::RecordingRenderer
+
+use nalgebra_glm::TMat4;
+use vulkano::swapchain::PresentMode;
+
+use crate::assets::asset_manager::AssetManager;
+use crate::core::config::EnvironmentConfig;
+use crate::core::cvars::CVarRegistry;
+use crate::graphics::draw_list::DrawList;
+use crate::graphics::mesh::Mesh;
+use crate::graphics::renderer::CapturedFrame;
+use crate::scene::components::directional_light::DirectionalLight;
+use crate::scene::components::pointlight::Pointlight;
+
+pub trait RenderBackend {
+    /// Prepares a mesh's textures. Called for every mesh in use before the geometry is built.
+    fn upload_mesh(&mut self, mesh: &mut Mesh);
+
+    /// Packs the loaded models for drawing, see [`AssetManager::build_unified_geometry`].
+    /// Called at startup and whenever a state loaded new models.
+    fn build_geometry(&mut self, assets: &mut AssetManager);
+
+    /// Sets the sky and the ambient light. Called once at startup, before the first frame.
+    fn set_environment(&mut self, environment: &EnvironmentConfig);
+
+    /// Applies the `r.*` cvars. Called every frame before rendering.
+    fn apply_settings(&mut self, settings: &RenderSettings);
+
+    /// Draws `frame` and presents it.
+    fn render(&mut self, frame: &RenderFrame);
+
+    /// Releases what finished frames held on to. Called every frame before the game updates.
+    fn cleanup_finished(&mut self) {}
+
+    /// Blocks until everything submitted so far has finished.
+    fn wait_idle(&self) {}
+
+    /// Size in pixels of the images rendered into.
+    fn image_extent(&self) -> [u32; 2];
+
+    /// The window's size in pixels changed.
+    fn resize(&mut self, pixel_size: [u32; 2]);
+
+    fn present_mode(&self) -> PresentMode;
+
+    /// Switches to `present_mode`, or the closest mode supported.
+    fn set_present_mode(&mut self, present_mode: PresentMode);
+
+    /// Captures the next rendered frame for [`RenderBackend::take_capture`], with the HDR scene
+    /// colour if `hdr` is set.
+    fn request_capture(&mut self, _hdr: bool) {}
+
+    /// The frame captured on request, once it is available.
+    fn take_capture(&mut self) -> Option<Result<CapturedFrame, String>> {
+        None
+    }
+
+    /// The last rendered frame as tightly packed RGBA8 rows, if it can be read back.
+    fn read_frame(&self) -> Option<Vec<u8>> {
+        None
+    }
+}
+
+/// Everything a frame is drawn from, collected from the world after the `PreRender` stage.
+pub struct RenderFrame<'a> {
+    /// The loaded models the draws index into.
+    pub assets: &'a AssetManager,
+    pub draw_list: &'a DrawList,
+    pub directional: &'a DirectionalLight,
+    pub pointlights: &'a [Pointlight],
+    /// View matrix of the active camera; `None` without one, which keeps the previous view.
+    pub view: Option<TMat4<f32>>,
+}
+
+/// The renderer tunables exposed as `r.*` cvars.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RenderSettings {
+    pub ao_radius: f32,
+    pub ao_att_scale: f32,
+    pub ao_dist_scale: f32,
+    pub ao_blur_depth_threshold: f32,
+    pub ao_composite_scale: f32,
+    pub ao_composite_bias: f32,
+    pub shadow_softness: f32,
+    pub exposure: f32,
+    pub fxaa_enabled: bool,
+}
+
+impl RenderSettings {
+    pub fn from_cvars(cvars: &CVarRegistry) -> Self {
+        Self {
+            ao_radius: cvars.float("r.ao_radius"),
+            ao_att_scale: cvars.float("r.ao_att_scale"),
+            ao_dist_scale: cvars.float("r.ao_dist_scale"),
+            ao_blur_depth_threshold: cvars.float("r.ao_blur_depth_threshold"),
+            ao_composite_scale: cvars.float("r.ao_composite_scale"),
+            ao_composite_bias: cvars.float("r.ao_composite_bias"),
+            shadow_softness: cvars.float("r.shadow_softness"),
+            exposure: cvars.float("r.exposure"),
+            fxaa_enabled: cvars.bool("r.fxaa"),
+        }
+    }
+}
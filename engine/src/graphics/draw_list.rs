@@ -0,0 +1,55 @@
+use shipyard::{Get, IntoIter, Unique, UniqueView, UniqueViewMut, View};
+
+use crate::assets::asset_manager::{AssetManager, NO_SKIN};
+use crate::scene::components::animator::Animator;
+use crate::scene::components::hierarchy::GlobalTransform;
+use crate::scene::components::object3d::Object3D;
+
+/// Everything the renderer needs from the world to draw a frame, extracted in the `PreRender`
+/// stage so it can run alongside other systems instead of inside the render loop.
+#[derive(Unique, Default)]
+pub struct DrawList {
+    /// `(mesh draw index, world transform, skin offset)` for every visible primitive.
+    pub draws: Vec<(usize, GlobalTransform, u32)>,
+    /// Joint matrices of every animated entity, indexed by the skin offsets in `draws`.
+    pub joint_matrices: Vec<[[f32; 4]; 4]>,
+}
+
+pub fn draw_list_system(
+    mut draw_list: UniqueViewMut<DrawList>,
+    asset_manager: UniqueView<AssetManager>,
+    objects: View<Object3D>,
+    transforms: View<GlobalTransform>,
+    animators: View<Animator>,
+) {
+    let draw_list = &mut *draw_list;
+    draw_list.draws.clear();
+    draw_list.joint_matrices.clear();
+
+    let unified = asset_manager.get_unified_geometry();
+
+    for (entity_id, (object, transform)) in (&objects, &transforms).iter().with_id() {
+        if let Some(model) = asset_manager.get_model(&object.model) {
+            let entity_skin_offset = if let Ok(animator) = animators.get(entity_id) {
+                let offset = draw_list.joint_matrices.len() as u32;
+                draw_list
+                    .joint_matrices
+                    .extend_from_slice(animator.joint_matrices());
+                offset
+            } else {
+                NO_SKIN
+            };
+            for draw_idx in model.draw_range.clone() {
+                // Only pass the skin offset for draws that actually have joint data.
+                // Non-skinned primitives (e.g. rigid helmet attachments) must use
+                // NO_SKIN so they don't incorrectly follow the root bone.
+                let skin_offset = if unified.mesh_draws[draw_idx].is_skinned {
+                    entity_skin_offset
+                } else {
+                    NO_SKIN
+                };
+                draw_list.draws.push((draw_idx, *transform, skin_offset));
+            }
+        }
+    }
+}
@@ -1,12 +1,50 @@
+pub mod backend;
+pub mod draw_list;
 pub mod mesh;
 pub mod model;
+pub mod recording_renderer;
 pub mod renderer;
 pub mod skybox;
 
 use shipyard::World;
 
+use crate::assets::asset_manager::AssetManager;
+use crate::core::config::EngineConfig;
+use crate::core::plugin::Plugin;
+use crate::core::schedule::{Schedule, Stage};
+use crate::scene::components::directional_light::DirectionalLight;
+use draw_list::DrawList;
+
 pub fn run_graphics_systems(world: &mut World) {}
 
+/// What the application's renderer draws from: the loaded models in [`AssetManager`], the sun
+/// and the [`DrawList`] collected every frame. The application can't render without it.
+pub struct RenderPlugin;
+
+impl RenderPlugin {
+    fn add_scene_uniques(world: &mut World, config: &EngineConfig) {
+        world.add_unique(AssetManager::new());
+        world.add_unique(DrawList::default());
+
+        let environment = &config.environment;
+        world.add_unique(DirectionalLight::new(
+            environment.sun_position,
+            environment.sun_color,
+        ));
+    }
+}
+
+impl Plugin for RenderPlugin {
+    fn build(&mut self, world: &mut World, schedule: &mut Schedule, config: &EngineConfig) {
+        Self::add_scene_uniques(world, config);
+        schedule.add_system(Stage::PreRender, draw_list::draw_list_system);
+    }
+
+    fn reset(&mut self, world: &mut World, config: &EngineConfig) {
+        Self::add_scene_uniques(world, config);
+    }
+}
+
 // pub fn update_camera_view(cameras: View<Camera>, transforms: View<Transform>) {
 //     for (camera, transform) in (&cameras, &transforms).iter().filter(|(c, _)| c.active) {
 //         //FPS camera: look from position in the direction we're facing
@@ -6,6 +6,7 @@ use std::cell::Cell;
 use crate::graphics::mesh::Mesh;
 
 use crate::assets::gltf_loader::{ColoredVertex, LoaderGLTF, NormalVertex};
+use crate::core::error::AssetError;
 
 pub struct Model {
     meshes: Vec<Mesh>,
@@ -48,9 +49,9 @@ impl ModelBuilder {
         }
     }
 
-    pub fn build(self) -> Model {
-        let loader = LoaderGLTF::new(self.file_name.as_str(), self.custom_color);
-        Model {
+    pub fn build(self) -> Result<Model, AssetError> {
+        let loader = LoaderGLTF::new(self.file_name.as_str(), self.custom_color)?;
+        Ok(Model {
             meshes: loader.get_meshes(),
             data: loader.as_normal_vertices(),
             translation: identity(),
@@ -62,7 +63,7 @@ impl ModelBuilder {
             cache: Cell::new(None),
             specular_intensity: self.specular_intensity,
             shininess: self.shininess,
-        }
+        })
     }
 
     /// Change the scale of a model.
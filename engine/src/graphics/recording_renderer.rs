@@ -0,0 +1,115 @@
+//! A [`RenderBackend`] that draws nothing and records every frame it is asked to render. Pass
+//! one to [`Application::with_backend`] to test what a game submits without Vulkan:
+//!
+//! ```ignore
+//! let mut app = Application::with_backend(game, RecordingRenderer::new(320, 240), config)?;
+//! app.run_frames(2, 1.0 / 60.0);
+//! assert_eq!(app.renderer.last_frame().unwrap().pointlights.len(), 1);
+//! ```
+//!
+//! [`Application::with_backend`]: crate::core::application::Application::with_backend
+
+use nalgebra_glm::{TMat4, identity};
+use vulkano::swapchain::PresentMode;
+
+use crate::assets::asset_manager::AssetManager;
+use crate::core::config::EnvironmentConfig;
+use crate::graphics::backend::{RenderBackend, RenderFrame, RenderSettings};
+use crate::graphics::mesh::Mesh;
+use crate::scene::components::directional_light::DirectionalLight;
+use crate::scene::components::hierarchy::GlobalTransform;
+use crate::scene::components::pointlight::Pointlight;
+
+/// What one frame submitted.
+#[derive(Clone, Debug)]
+pub struct RecordedFrame {
+    /// `(mesh draw index, transform, skin offset)` for every primitive, as in the draw list.
+    pub draws: Vec<(usize, GlobalTransform, u32)>,
+    pub joint_matrices: Vec<[[f32; 4]; 4]>,
+    pub directional: DirectionalLight,
+    pub pointlights: Vec<Pointlight>,
+    /// The view matrix the frame was drawn with.
+    pub view: TMat4<f32>,
+}
+
+pub struct RecordingRenderer {
+    /// Every rendered frame, oldest first.
+    pub frames: Vec<RecordedFrame>,
+    /// The settings last applied from the cvars.
+    pub settings: Option<RenderSettings>,
+    pub environment: Option<EnvironmentConfig>,
+    /// How many meshes were uploaded and how many times the geometry was built.
+    pub uploaded_meshes: usize,
+    pub geometry_builds: usize,
+    extent: [u32; 2],
+    present_mode: PresentMode,
+    view: TMat4<f32>,
+}
+
+impl RecordingRenderer {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            frames: Vec::new(),
+            settings: None,
+            environment: None,
+            uploaded_meshes: 0,
+            geometry_builds: 0,
+            extent: [width, height],
+            present_mode: PresentMode::Fifo,
+            view: identity(),
+        }
+    }
+
+    pub fn last_frame(&self) -> Option<&RecordedFrame> {
+        self.frames.last()
+    }
+}
+
+impl RenderBackend for RecordingRenderer {
+    fn upload_mesh(&mut self, _mesh: &mut Mesh) {
+        self.uploaded_meshes += 1;
+    }
+
+    fn build_geometry(&mut self, assets: &mut AssetManager) {
+        // Lays out the draw ranges the draw list is built from, without any buffers
+        assets.build_unified_geometry(None);
+        self.geometry_builds += 1;
+    }
+
+    fn set_environment(&mut self, environment: &EnvironmentConfig) {
+        self.environment = Some(environment.clone());
+    }
+
+    fn apply_settings(&mut self, settings: &RenderSettings) {
+        self.settings = Some(settings.clone());
+    }
+
+    fn render(&mut self, frame: &RenderFrame) {
+        if let Some(view) = frame.view {
+            self.view = view;
+        }
+        self.frames.push(RecordedFrame {
+            draws: frame.draw_list.draws.clone(),
+            joint_matrices: frame.draw_list.joint_matrices.clone(),
+            directional: frame.directional.clone(),
+            pointlights: frame.pointlights.to_vec(),
+            view: self.view,
+        });
+    }
+
+    fn image_extent(&self) -> [u32; 2] {
+        self.extent
+    }
+
+    fn resize(&mut self, pixel_size: [u32; 2]) {
+        self.extent = pixel_size;
+    }
+
+    fn present_mode(&self) -> PresentMode {
+        self.present_mode
+    }
+
+    fn set_present_mode(&mut self, present_mode: PresentMode) {
+        self.present_mode = present_mode;
+    }
+}
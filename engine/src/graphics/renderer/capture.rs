@@ -0,0 +1,204 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use image::{DynamicImage, ImageResult, Rgba32FImage, RgbaImage};
+use vulkano::{
+    buffer::{Buffer, BufferCreateInfo, BufferUsage, Subbuffer},
+    command_buffer::{AutoCommandBufferBuilder, CopyImageToBufferInfo, PrimaryAutoCommandBuffer},
+    format::Format,
+    image::Image,
+    memory::allocator::{AllocationCreateInfo, MemoryTypeFilter},
+};
+
+use super::Renderer;
+
+/// A frame copied back from the GPU by [`Renderer::request_capture`].
+pub struct CapturedFrame {
+    /// The composited frame as presented: tone mapped, gamma corrected and anti-aliased.
+    pub image: RgbaImage,
+    /// Linear HDR scene colour before tone mapping, if it was requested.
+    pub hdr: Option<Rgba32FImage>,
+}
+
+impl CapturedFrame {
+    /// Saves the composited frame; the format follows the extension, e.g. `.png`.
+    pub fn save(&self, path: impl AsRef<Path>) -> ImageResult<()> {
+        self.image.save(path)
+    }
+
+    /// Saves the HDR image as floating point, e.g. `.exr` or `.hdr`. Does nothing if the
+    /// capture has no HDR image.
+    pub fn save_hdr(&self, path: impl AsRef<Path>) -> ImageResult<()> {
+        let Some(hdr) = &self.hdr else {
+            return Ok(());
+        };
+        let path = path.as_ref();
+        let image = DynamicImage::ImageRgba32F(hdr.clone());
+        // Radiance HDR has no alpha channel.
+        if path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("hdr"))
+        {
+            DynamicImage::ImageRgb32F(image.to_rgb32f()).save(path)
+        } else {
+            image.save(path)
+        }
+    }
+}
+
+/// Host-visible copies of the frame, filled by the command buffer of the captured frame.
+pub(super) struct CaptureReadback {
+    extent: [u32; 2],
+    format: Format,
+    frame: Subbuffer<[u8]>,
+    hdr: Option<Subbuffer<[u8]>>,
+}
+
+impl Renderer {
+    /// Copies the next frame that is submitted back to the host. Pick it up with
+    /// [`Renderer::take_capture`] once [`Renderer::finish`] returns. With `hdr`, the scene
+    /// colour before tone mapping is copied as well.
+    pub fn request_capture(&mut self, hdr: bool) {
+        self.capture_request = Some(hdr);
+    }
+
+    /// The frame captured after the last [`Renderer::request_capture`], or an error if the
+    /// surface format can't be converted to RGBA8.
+    pub fn take_capture(&mut self) -> Option<Result<CapturedFrame, String>> {
+        self.capture.take()
+    }
+
+    /// Records the copies for a pending capture request. Called after the composite pass.
+    pub(super) fn record_capture(
+        &mut self,
+        commands: &mut AutoCommandBufferBuilder<PrimaryAutoCommandBuffer>,
+    ) -> Option<CaptureReadback> {
+        let hdr = self.capture_request.take()?;
+
+        let target = self.composite_framebuffers[self.image_index as usize].attachments()[0]
+            .image()
+            .clone();
+        let format = target.format();
+        if bytes_per_pixel(format).is_none() {
+            self.capture = Some(Err(format!("can't capture frames in {format:?}")));
+            return None;
+        }
+
+        let extent = self.image_extent();
+        let frame = self.readback_buffer(target.clone(), commands);
+        let hdr = hdr.then(|| {
+            let scene = self.scene_image.image().clone();
+            self.readback_buffer(scene, commands)
+        });
+
+        Some(CaptureReadback {
+            extent,
+            format,
+            frame,
+            hdr,
+        })
+    }
+
+    fn readback_buffer(
+        &self,
+        image: Arc<Image>,
+        commands: &mut AutoCommandBufferBuilder<PrimaryAutoCommandBuffer>,
+    ) -> Subbuffer<[u8]> {
+        let [width, height, _] = image.extent();
+        let size = width as u64 * height as u64 * image.format().block_size();
+
+        let buffer = Buffer::new_slice::<u8>(
+            self.memory_allocator.clone(),
+            BufferCreateInfo {
+                usage: BufferUsage::TRANSFER_DST,
+                ..Default::default()
+            },
+            AllocationCreateInfo {
+                memory_type_filter: MemoryTypeFilter::PREFER_HOST
+                    | MemoryTypeFilter::HOST_RANDOM_ACCESS,
+                ..Default::default()
+            },
+            size,
+        )
+        .unwrap();
+
+        commands
+            .copy_image_to_buffer(CopyImageToBufferInfo::image_buffer(image, buffer.clone()))
+            .unwrap();
+        buffer
+    }
+
+    /// Converts a finished readback. The frame it was recorded in must have completed.
+    pub(super) fn resolve_capture(&mut self, readback: CaptureReadback) {
+        let [width, height] = readback.extent;
+
+        let frame = readback.frame.read().unwrap();
+        let pixels = frame
+            .chunks_exact(bytes_per_pixel(readback.format).unwrap())
+            .flat_map(|texel| to_rgba8(readback.format, texel))
+            .collect();
+        let image = RgbaImage::from_raw(width, height, pixels).unwrap();
+
+        let hdr = readback.hdr.map(|buffer| {
+            let texels = buffer.read().unwrap();
+            let pixels = texels
+                .chunks_exact(2)
+                .map(|half| f16_to_f32(u16::from_le_bytes([half[0], half[1]])))
+                .collect();
+            Rgba32FImage::from_raw(width, height, pixels).unwrap()
+        });
+
+        self.capture = Some(Ok(CapturedFrame { image, hdr }));
+    }
+}
+
+fn bytes_per_pixel(format: Format) -> Option<usize> {
+    match format {
+        Format::R8G8B8A8_UNORM
+        | Format::R8G8B8A8_SRGB
+        | Format::B8G8R8A8_UNORM
+        | Format::B8G8R8A8_SRGB
+        | Format::A2B10G10R10_UNORM_PACK32
+        | Format::A2R10G10B10_UNORM_PACK32 => Some(4),
+        _ => None,
+    }
+}
+
+/// One texel of a supported presentable format as opaque RGBA8. sRGB formats already hold
+/// gamma encoded values, so they are copied as they are.
+fn to_rgba8(format: Format, texel: &[u8]) -> [u8; 4] {
+    let [a, b, c, _]: [u8; 4] = texel.try_into().unwrap();
+    match format {
+        Format::B8G8R8A8_UNORM | Format::B8G8R8A8_SRGB => [c, b, a, 255],
+        Format::A2B10G10R10_UNORM_PACK32 | Format::A2R10G10B10_UNORM_PACK32 => {
+            let packed = u32::from_le_bytes(texel.try_into().unwrap());
+            let channel = |shift: u32| ((packed >> shift) & 0x3ff) as u16 >> 2;
+            let (r, g, b) = if format == Format::A2B10G10R10_UNORM_PACK32 {
+                (channel(0), channel(10), channel(20))
+            } else {
+                (channel(20), channel(10), channel(0))
+            };
+            [r as u8, g as u8, b as u8, 255]
+        }
+        _ => [a, b, c, 255],
+    }
+}
+
+/// IEEE 754 half to single precision, including subnormals, infinities and NaN.
+fn f16_to_f32(bits: u16) -> f32 {
+    let sign = ((bits >> 15) as u32) << 31;
+    let exponent = ((bits >> 10) & 0x1f) as u32;
+    let mantissa = (bits & 0x3ff) as u32;
+
+    let magnitude = match (exponent, mantissa) {
+        (0, 0) => 0,
+        (0, _) => {
+            // Subnormal: mantissa * 2^-24, which is exact in f32.
+            let value = mantissa as f32 * 2f32.powi(-24);
+            return if sign != 0 { -value } else { value };
+        }
+        (0x1f, _) => 0x7f80_0000 | (mantissa << 13),
+        _ => ((exponent + 127 - 15) << 23) | (mantissa << 13),
+    };
+    f32::from_bits(sign | magnitude)
+}
@@ -0,0 +1,132 @@
+use vulkano::swapchain::PresentMode;
+use vulkano::sync::{self, GpuFuture};
+
+use super::{CapturedFrame, Renderer};
+use crate::assets::asset_manager::AssetManager;
+use crate::core::config::EnvironmentConfig;
+use crate::graphics::backend::{RenderBackend, RenderFrame, RenderSettings};
+use crate::graphics::mesh::Mesh;
+use crate::profile_scope;
+
+impl RenderBackend for Renderer {
+    fn upload_mesh(&mut self, mesh: &mut Mesh) {
+        self.upload_texture_to_gpu(mesh);
+    }
+
+    fn build_geometry(&mut self, assets: &mut AssetManager) {
+        assets.build_unified_geometry(Some(self.memory_allocator.clone()));
+        self.build_bindless_material_set(assets.get_unified_geometry());
+    }
+
+    fn set_environment(&mut self, environment: &EnvironmentConfig) {
+        self.bake_environment(environment);
+    }
+
+    fn apply_settings(&mut self, settings: &RenderSettings) {
+        self.ao_radius = settings.ao_radius;
+        self.ao_att_scale = settings.ao_att_scale;
+        self.ao_dist_scale = settings.ao_dist_scale;
+        self.ao_blur_depth_threshold = settings.ao_blur_depth_threshold;
+        self.ao_composite_scale = settings.ao_composite_scale;
+        self.ao_composite_bias = settings.ao_composite_bias;
+        self.shadow_softness = settings.shadow_softness;
+        self.exposure = settings.exposure;
+        self.fxaa_enabled = settings.fxaa_enabled;
+    }
+
+    fn render(&mut self, frame: &RenderFrame) {
+        profile_scope!("render");
+
+        if let Some(view) = &frame.view {
+            self.set_view(view);
+        }
+
+        self.start();
+        let unified = frame.assets.get_unified_geometry();
+        let culled = {
+            profile_scope!("render::cull");
+            self.cull_pass(
+                unified,
+                &frame.draw_list.draws,
+                &frame.draw_list.joint_matrices,
+            )
+        };
+        {
+            profile_scope!("render::shadows");
+            self.shadow_pass(frame.directional, unified, culled.as_ref());
+        }
+        {
+            profile_scope!("render::geometry");
+            self.geometry(unified, culled.as_ref());
+        }
+        {
+            profile_scope!("render::lighting");
+            let mut environment = self
+                .environment
+                .take()
+                .expect("the environment must be set before the first frame");
+            self.ambient(
+                &environment.irradiance,
+                &environment.prefiltered,
+                &environment.brdf_lut,
+            );
+            self.directional(frame.directional);
+            // Timed as one pass; a scope per light would use up the frame's timestamp queries
+            self.gpu_scope_begin("point lights");
+            for light in frame.pointlights {
+                self.pointlight(light);
+            }
+            self.gpu_scope_end();
+            self.skybox(&mut environment.skybox);
+            self.environment = Some(environment);
+        }
+        {
+            profile_scope!("render::submit");
+            let mut previous_frame_end = Some(
+                self.previous_frame_end
+                    .take()
+                    .unwrap_or_else(|| sync::now(self.device.clone()).boxed()),
+            );
+            self.finish(&mut previous_frame_end);
+            self.previous_frame_end = previous_frame_end;
+        }
+    }
+
+    fn cleanup_finished(&mut self) {
+        if let Some(previous_frame_end) = &mut self.previous_frame_end {
+            previous_frame_end.cleanup_finished();
+        }
+    }
+
+    fn wait_idle(&self) {
+        Renderer::wait_idle(self);
+    }
+
+    fn image_extent(&self) -> [u32; 2] {
+        Renderer::image_extent(self)
+    }
+
+    fn resize(&mut self, pixel_size: [u32; 2]) {
+        Renderer::resize(self, pixel_size);
+    }
+
+    fn present_mode(&self) -> PresentMode {
+        Renderer::present_mode(self)
+    }
+
+    fn set_present_mode(&mut self, present_mode: PresentMode) {
+        Renderer::set_present_mode(self, present_mode);
+    }
+
+    fn request_capture(&mut self, hdr: bool) {
+        Renderer::request_capture(self, hdr);
+    }
+
+    fn take_capture(&mut self) -> Option<Result<CapturedFrame, String>> {
+        Renderer::take_capture(self)
+    }
+
+    fn read_frame(&self) -> Option<Vec<u8>> {
+        Renderer::read_frame(self)
+    }
+}
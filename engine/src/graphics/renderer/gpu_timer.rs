@@ -0,0 +1,212 @@
+//! GPU pass timings for the `profiler` feature. Without the feature [`GpuTimer`] is empty and
+//! every call compiles to nothing.
+
+use std::sync::Arc;
+
+use vulkano::command_buffer::{AutoCommandBufferBuilder, PrimaryAutoCommandBuffer};
+use vulkano::device::{Device, Queue};
+
+use super::Renderer;
+
+type Commands = AutoCommandBufferBuilder<PrimaryAutoCommandBuffer>;
+
+impl Renderer {
+    /// Starts timing a pass recorded into `self.commands`.
+    pub(super) fn gpu_scope_begin(&mut self, name: &'static str) {
+        if let Some(commands) = self.commands.as_mut() {
+            self.gpu_timer.begin(commands, name);
+        }
+    }
+
+    pub(super) fn gpu_scope_end(&mut self) {
+        if let Some(commands) = self.commands.as_mut() {
+            self.gpu_timer.end(commands);
+        }
+    }
+}
+
+#[cfg(not(feature = "profiler"))]
+pub(super) struct GpuTimer;
+
+#[cfg(not(feature = "profiler"))]
+impl GpuTimer {
+    pub(super) fn new(_device: Arc<Device>, _queue: &Queue) -> Self {
+        GpuTimer
+    }
+
+    #[inline(always)]
+    pub(super) fn begin_frame(&mut self, _commands: &mut Commands) {}
+
+    #[inline(always)]
+    pub(super) fn end_frame(&mut self) {}
+
+    #[inline(always)]
+    pub(super) fn begin(&mut self, _commands: &mut Commands, _name: &'static str) {}
+
+    #[inline(always)]
+    pub(super) fn end(&mut self, _commands: &mut Commands) {}
+}
+
+#[cfg(feature = "profiler")]
+pub(super) use enabled::GpuTimer;
+
+#[cfg(feature = "profiler")]
+mod enabled {
+    use std::time::Duration;
+
+    use vulkano::query::{QueryPool, QueryPoolCreateInfo, QueryResultFlags, QueryType};
+    use vulkano::sync::PipelineStage;
+
+    use super::{Arc, Commands, Device, Queue};
+    use crate::core::profiler;
+
+    /// Timestamp queries available per frame, two per pass.
+    const QUERIES_PER_FRAME: u32 = 128;
+
+    /// Frames whose queries may still be in flight. Results are read back this many frames
+    /// later so the CPU never waits on the GPU.
+    const FRAMES_IN_FLIGHT: usize = 3;
+
+    struct FrameQueries {
+        pool: Arc<QueryPool>,
+        /// `(name, first query)`; each pass uses that query and the next one.
+        passes: Vec<(&'static str, u32)>,
+        submitted_us: Option<f64>,
+    }
+
+    pub(in crate::graphics::renderer) struct GpuTimer {
+        frames: Vec<FrameQueries>,
+        current: usize,
+        open: Option<u32>,
+        nanoseconds_per_tick: f64,
+        valid_mask: u64,
+    }
+
+    impl GpuTimer {
+        pub(in crate::graphics::renderer) fn new(device: Arc<Device>, queue: &Queue) -> Self {
+            let valid_bits = device.physical_device().queue_family_properties()
+                [queue.queue_family_index() as usize]
+                .timestamp_valid_bits;
+
+            // Queues without timestamp support get a timer that never records anything.
+            let frames = match valid_bits {
+                Some(_) => (0..FRAMES_IN_FLIGHT)
+                    .map(|_| FrameQueries {
+                        pool: QueryPool::new(
+                            device.clone(),
+                            QueryPoolCreateInfo {
+                                query_count: QUERIES_PER_FRAME,
+                                ..QueryPoolCreateInfo::query_type(QueryType::Timestamp)
+                            },
+                        )
+                        .unwrap(),
+                        passes: Vec::new(),
+                        submitted_us: None,
+                    })
+                    .collect(),
+                None => Vec::new(),
+            };
+
+            let valid_mask = match valid_bits {
+                Some(bits) if bits < 64 => (1u64 << bits) - 1,
+                _ => u64::MAX,
+            };
+
+            Self {
+                frames,
+                current: 0,
+                open: None,
+                nanoseconds_per_tick: device.physical_device().properties().timestamp_period as f64,
+                valid_mask,
+            }
+        }
+
+        /// Reads back the results of the frame that last used this frame's queries, then
+        /// resets them. Must be recorded outside of a render pass.
+        pub(in crate::graphics::renderer) fn begin_frame(&mut self, commands: &mut Commands) {
+            let Some(frame) = self.frames.get_mut(self.current) else {
+                return;
+            };
+
+            if let Some(submitted_us) = frame.submitted_us.take()
+                && !frame.passes.is_empty()
+            {
+                let count = frame.passes.len() as u32 * 2;
+                let mut ticks = vec![0u64; count as usize];
+                // Not ready yet means the GPU is more than FRAMES_IN_FLIGHT behind; the sample
+                // is dropped rather than stalling.
+                if let Ok(true) =
+                    frame
+                        .pool
+                        .get_results(0..count, &mut ticks, QueryResultFlags::empty())
+                {
+                    let first = ticks[0];
+                    for &(name, query) in &frame.passes {
+                        let start = ticks[query as usize];
+                        let end = ticks[query as usize + 1];
+                        let to_us = |delta: u64| {
+                            (delta & self.valid_mask) as f64 * self.nanoseconds_per_tick / 1e3
+                        };
+                        profiler::record_gpu(
+                            name,
+                            submitted_us + to_us(start.wrapping_sub(first)),
+                            Duration::from_secs_f64(to_us(end.wrapping_sub(start)) / 1e6),
+                        );
+                    }
+                }
+            }
+
+            frame.passes.clear();
+            self.open = None;
+            unsafe {
+                commands
+                    .reset_query_pool(frame.pool.clone(), 0..QUERIES_PER_FRAME)
+                    .unwrap();
+            }
+        }
+
+        /// Marks the frame as submitted and moves on to the next set of queries.
+        pub(in crate::graphics::renderer) fn end_frame(&mut self) {
+            let Some(frame) = self.frames.get_mut(self.current) else {
+                return;
+            };
+            frame.submitted_us = Some(profiler::now_us());
+            self.current = (self.current + 1) % self.frames.len();
+        }
+
+        pub(in crate::graphics::renderer) fn begin(
+            &mut self,
+            commands: &mut Commands,
+            name: &'static str,
+        ) {
+            let Some(frame) = self.frames.get_mut(self.current) else {
+                return;
+            };
+            let query = frame.passes.len() as u32 * 2;
+            if query + 2 > QUERIES_PER_FRAME {
+                return;
+            }
+
+            unsafe {
+                commands
+                    .write_timestamp(frame.pool.clone(), query, PipelineStage::AllCommands)
+                    .unwrap();
+            }
+            frame.passes.push((name, query));
+            self.open = Some(query);
+        }
+
+        pub(in crate::graphics::renderer) fn end(&mut self, commands: &mut Commands) {
+            let (Some(query), Some(frame)) = (self.open.take(), self.frames.get(self.current))
+            else {
+                return;
+            };
+
+            unsafe {
+                commands
+                    .write_timestamp(frame.pool.clone(), query + 1, PipelineStage::AllCommands)
+                    .unwrap();
+            }
+        }
+    }
+}
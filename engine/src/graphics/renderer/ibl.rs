@@ -18,9 +18,44 @@ use vulkano::{
 };
 
 use super::{Renderer, brdf_lut_comp, irradiance_comp, prefilter_comp};
-use crate::graphics::skybox::Skybox;
+use crate::core::config::EnvironmentConfig;
+use crate::graphics::skybox::{HdrSkyboxImages, Skybox};
+use crate::warn;
+
+/// Image-based lighting inputs baked from the sky.
+pub(super) struct Environment {
+    pub(super) skybox: Skybox,
+    pub(super) irradiance: Skybox,
+    pub(super) prefiltered: Skybox,
+    pub(super) brdf_lut: Arc<ImageView>,
+}
 
 impl Renderer {
+    /// Loads the sky from `environment`, falling back to a uniform sky in the ambient colour,
+    /// bakes the image-based lighting from it and sets the ambient light.
+    pub fn bake_environment(&mut self, environment: &EnvironmentConfig) {
+        let hdr = HdrSkyboxImages::from_equirect(&environment.skybox, environment.skybox_face_size)
+            .unwrap_or_else(|e| {
+                warn!("{e}, using a plain sky");
+                let sky = environment
+                    .ambient_color
+                    .map(|c| c * environment.ambient_intensity);
+                HdrSkyboxImages::uniform(sky, environment.skybox_face_size)
+            });
+        let skybox = self.upload_hdr_skybox(hdr);
+        let irradiance = self.bake_irradiance_map(&skybox);
+        let prefiltered = self.bake_prefiltered_env(&skybox);
+        let brdf_lut = self.bake_brdf_lut();
+        self.environment = Some(Environment {
+            skybox,
+            irradiance,
+            prefiltered,
+            brdf_lut,
+        });
+
+        self.set_ambient(environment.ambient_color, environment.ambient_intensity);
+    }
+
     pub fn bake_irradiance_map(&self, env: &Skybox) -> Skybox {
         const IRR_SIZE: u32 = 32;
 
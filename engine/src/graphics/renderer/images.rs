@@ -1,7 +1,6 @@
 use std::sync::Arc;
 
 use nalgebra_glm::{half_pi, perspective};
-use sdl3::video::Window;
 use vulkano::{
     Validated, VulkanError,
     buffer::{Buffer, BufferCreateInfo, BufferUsage},
@@ -11,30 +10,89 @@ use vulkano::{
     memory::allocator::{AllocationCreateInfo, MemoryTypeFilter, StandardMemoryAllocator},
     pipeline::{Pipeline, graphics::viewport::Viewport},
     render_pass::{Framebuffer, FramebufferCreateInfo, RenderPass},
-    swapchain::SwapchainCreateInfo,
+    swapchain::{PresentMode, SwapchainCreateInfo},
 };
 
 use super::{Renderer, deferred_vert};
+use crate::core::window::choose_present_mode;
 
 impl Renderer {
+    /// Recreates the swapchain and the window-sized images after the window's pixel size
+    /// changed.
+    pub fn resize(&mut self, pixel_size: [u32; 2]) {
+        self.window_extent = pixel_size;
+        self.recreate_swapchain();
+    }
+
+    /// The present mode the swapchain uses. For headless renderers, the one from the config.
+    pub fn present_mode(&self) -> PresentMode {
+        match &self.swapchain {
+            Some(swapchain) => swapchain.create_info().present_mode,
+            None => self.requested_present_mode,
+        }
+    }
+
+    /// The present modes the window surface supports. Empty when headless.
+    pub fn supported_present_modes(&self) -> Vec<PresentMode> {
+        let Some(surface) = &self.surface else {
+            return Vec::new();
+        };
+        self.device
+            .physical_device()
+            .surface_present_modes(surface, Default::default())
+            .unwrap_or_default()
+    }
+
+    /// Switches the swapchain to `requested`, or the closest supported mode (see
+    /// [`choose_present_mode`]). Returns the mode in use afterwards.
+    pub fn set_present_mode(&mut self, requested: PresentMode) -> PresentMode {
+        let Some(swapchain) = self.swapchain.clone() else {
+            self.requested_present_mode = requested;
+            return requested;
+        };
+
+        let present_mode = choose_present_mode(requested, &self.supported_present_modes());
+        if present_mode != swapchain.create_info().present_mode {
+            self.recreate_swapchain_with(present_mode);
+        }
+        self.present_mode()
+    }
+
     pub fn recreate_swapchain(&mut self) {
+        let present_mode = self.present_mode();
+        self.recreate_swapchain_with(present_mode);
+    }
+
+    fn recreate_swapchain_with(&mut self, present_mode: PresentMode) {
+        // Headless renderers have a fixed-size offscreen target and nothing to recreate.
+        let (Some(surface), Some(swapchain)) = (self.surface.clone(), self.swapchain.clone())
+        else {
+            return;
+        };
+
         self.render_stage = super::RenderStage::NeedsRedraw;
         self.commands = None;
 
-        let window = self
-            .surface
-            .object()
-            .unwrap()
-            .downcast_ref::<Window>()
-            .unwrap();
-        let image_extent: [u32; 2] = window.size().into();
+        // Most surfaces dictate the extent; the others (e.g. Wayland) take the window's.
+        let image_extent = self
+            .device
+            .physical_device()
+            .surface_capabilities(&surface, Default::default())
+            .ok()
+            .and_then(|caps| caps.current_extent)
+            .unwrap_or(self.window_extent);
+        // Minimized: keep the old swapchain until the window is visible again
+        if image_extent.contains(&0) {
+            return;
+        }
 
         let aspect_ratio = image_extent[0] as f32 / image_extent[1] as f32;
         self.vp.projection = perspective(aspect_ratio, half_pi(), 0.01, 1000.0);
 
-        let (new_swapchain, new_images) = match self.swapchain.recreate(SwapchainCreateInfo {
+        let (new_swapchain, new_images) = match swapchain.recreate(SwapchainCreateInfo {
             image_extent,
-            ..self.swapchain.create_info()
+            present_mode,
+            ..swapchain.create_info()
         }) {
             Ok(r) => r,
             Err(Validated::ValidationError(_)) => return,
@@ -58,11 +116,11 @@ impl Renderer {
             &new_images,
             self.render_pass.clone(),
             self.composite_render_pass.clone(),
-            self.swapchain.image_format(),
+            new_swapchain.image_format(),
             &mut self.viewport,
         );
 
-        self.swapchain = new_swapchain;
+        self.swapchain = Some(new_swapchain);
         self.framebuffers = new_framebuffers;
         self.composite_framebuffers = new_composite_framebuffers;
         self.scene_image = new_scene_image;
@@ -300,7 +358,9 @@ impl Renderer {
                     image_type: vulkano::image::ImageType::Dim2d,
                     format: Format::R16G16B16A16_SFLOAT,
                     extent: [dimensions[0], dimensions[1], 1],
-                    usage: ImageUsage::COLOR_ATTACHMENT | ImageUsage::SAMPLED,
+                    usage: ImageUsage::COLOR_ATTACHMENT
+                        | ImageUsage::SAMPLED
+                        | ImageUsage::TRANSFER_SRC,
                     ..Default::default()
                 },
                 AllocationCreateInfo {
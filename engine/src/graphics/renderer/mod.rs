@@ -1,4 +1,7 @@
+mod capture;
 mod compute;
+mod frame;
+mod gpu_timer;
 mod ibl;
 mod images;
 mod passes;
@@ -17,7 +20,7 @@ use vulkano::{
     buffer::allocator::{SubbufferAllocator, SubbufferAllocatorCreateInfo},
     buffer::{Buffer, BufferCreateInfo, BufferUsage, Subbuffer},
     command_buffer::{
-        AutoCommandBufferBuilder, CommandBufferUsage, CopyBufferToImageInfo,
+        AutoCommandBufferBuilder, CommandBufferUsage, CopyBufferToImageInfo, CopyImageToBufferInfo,
         PrimaryAutoCommandBuffer, RenderPassBeginInfo, SubpassBeginInfo, SubpassContents,
         SubpassEndInfo, allocator::StandardCommandBufferAllocator,
     },
@@ -29,7 +32,7 @@ use vulkano::{
         Device, DeviceCreateInfo, DeviceExtensions, DeviceFeatures, Queue, QueueCreateInfo,
         QueueFlags, physical::PhysicalDeviceType,
     },
-    format::Format,
+    format::{Format, NumericFormat},
     image::{
         Image, ImageCreateInfo, ImageUsage,
         sampler::{
@@ -59,8 +62,16 @@ use nalgebra_glm::TVec3 as Vec3;
 
 use crate::assets::asset_manager::{self, UnifiedGeometry};
 use crate::assets::gltf_loader::DummyVertex;
+use crate::core::config::{RendererConfig, SurfaceFormatPreference};
+use crate::core::error::GraphicsError;
+use crate::core::window::choose_present_mode;
 use crate::scene::components::pointlight::Pointlight;
 use crate::scene::components::transform::Transform;
+use crate::{error, warn};
+
+pub use capture::CapturedFrame;
+use gpu_timer::GpuTimer;
+use ibl::Environment;
 
 // ── Shader modules ─────────────────────────────────────────────────────────
 
@@ -205,7 +216,9 @@ mod brdf_lut_comp {
 
 // ── Public re-exports (was in old mod.rs) ──────────────────────────────────
 
-const SHADOW_MAP_SIZE: u32 = 4096;
+/// Environment variable that, when set to `cpu`, makes the renderer prefer a CPU Vulkan
+/// implementation over any hardware device.
+pub const DEVICE_PREFERENCE_VAR: &str = "VULKANO_ENGINE_DEVICE";
 
 #[derive(Debug, Clone)]
 enum RenderStage {
@@ -245,11 +258,13 @@ pub struct CulledDrawBuffers {
 
 pub struct Renderer {
     pub(super) instance: Arc<Instance>,
-    pub(super) surface: Arc<Surface>,
+    pub(super) surface: Option<Arc<Surface>>,
     pub device: Arc<Device>,
     pub queue: Arc<Queue>,
     pub(super) vp: VP,
-    pub(super) swapchain: Arc<Swapchain>,
+    pub(super) swapchain: Option<Arc<Swapchain>>,
+    /// Composite target used instead of the swapchain by headless renderers.
+    pub(super) offscreen_image: Option<Arc<Image>>,
     pub memory_allocator: Arc<StandardMemoryAllocator>,
     pub(super) descriptor_set_allocator: Arc<StandardDescriptorSetAllocator>,
     pub command_buffer_allocator: Arc<StandardCommandBufferAllocator>,
@@ -299,6 +314,10 @@ pub struct Renderer {
     pub commands: Option<AutoCommandBufferBuilder<PrimaryAutoCommandBuffer>>,
     pub(super) image_index: u32,
     pub(super) acquire_future: Option<SwapchainAcquireFuture>,
+    gpu_timer: GpuTimer,
+    /// `Some(hdr)` while a capture of the next frame is pending.
+    capture_request: Option<bool>,
+    capture: Option<Result<CapturedFrame, String>>,
     pub ao_radius: f32,
     pub ao_att_scale: f32,
     pub ao_dist_scale: f32,
@@ -308,31 +327,34 @@ pub struct Renderer {
     pub exposure: f32,
     pub fxaa_enabled: bool,
     pub shadow_softness: f32,
+    /// Pixel size of the window the swapchain presents to, for surfaces that leave the extent
+    /// up to the application.
+    window_extent: [u32; 2],
+    /// The present mode asked for, reported by headless renderers.
+    requested_present_mode: PresentMode,
+    /// Set by [`Renderer::bake_environment`]; frames are lit without it until then.
+    environment: Option<Environment>,
+    /// Completes when the last submitted frame has finished on the GPU.
+    previous_frame_end: Option<Box<dyn GpuFuture>>,
 }
 
 // ── Core impl ─────────────────────────────────────────────────────────────
 
 impl Renderer {
-    pub fn new(window: &Window) -> Renderer {
-        let instance = {
-            let library = VulkanLibrary::new().unwrap();
-            let sdl_extensions = window.vulkan_instance_extensions().unwrap();
-            let extensions: InstanceExtensions =
-                sdl_extensions.iter().map(|s| s.as_str()).collect();
-            Instance::new(
-                library,
-                InstanceCreateInfo {
-                    enabled_extensions: extensions,
-                    max_api_version: Some(Version::V1_3),
-                    ..Default::default()
-                },
-            )
-            .unwrap()
-        };
+    /// Creates a renderer presenting to `window`. Fails if Vulkan isn't available or no device
+    /// can render to the window.
+    pub fn new(window: &Window, config: &RendererConfig) -> Result<Renderer, GraphicsError> {
+        let sdl_extensions = window
+            .vulkan_instance_extensions()
+            .map_err(|e| GraphicsError::Surface(e.to_string()))?;
+        let instance =
+            Renderer::create_instance(sdl_extensions.iter().map(|s| s.as_str()).collect())?;
 
         let raw_instance: vk::Instance = instance.handle();
         let raw_instance_ptr = raw_instance.as_raw() as *mut vk::Instance;
-        let raw_surface_ptr = window.vulkan_create_surface(raw_instance_ptr as _).unwrap();
+        let raw_surface_ptr = window
+            .vulkan_create_surface(raw_instance_ptr as _)
+            .map_err(|e| GraphicsError::Surface(e.to_string()))?;
         let raw_surface = vk::SurfaceKHR::from_raw(raw_surface_ptr as u64);
 
         let surface = unsafe {
@@ -346,10 +368,131 @@ impl Renderer {
 
         let device_extensions = DeviceExtensions {
             khr_swapchain: true,
+            ..Renderer::required_device_extensions()
+        };
+        let (device, queue) =
+            Renderer::create_device(&instance, device_extensions, Some(&surface))?;
+
+        let (swapchain, images) = {
+            let caps = device
+                .physical_device()
+                .surface_capabilities(&surface, Default::default())
+                .map_err(GraphicsError::vulkan("query surface capabilities"))?;
+            let usage = caps.supported_usage_flags;
+            let alpha = caps.supported_composite_alpha.into_iter().next().unwrap();
+            let surface_formats = device
+                .physical_device()
+                .surface_formats(&surface, Default::default())
+                .map_err(GraphicsError::vulkan("query surface formats"))?;
+            let fallback_format = surface_formats
+                .first()
+                .map(|(format, _)| *format)
+                .ok_or(GraphicsError::NoSurfaceFormat)?;
+            let image_format = surface_formats
+                .iter()
+                .map(|(format, _)| *format)
+                .find(|format| match config.surface_format {
+                    SurfaceFormatPreference::First => true,
+                    SurfaceFormatPreference::Unorm => {
+                        format.numeric_format_color() == Some(NumericFormat::UNORM)
+                    }
+                    SurfaceFormatPreference::Srgb => {
+                        format.numeric_format_color() == Some(NumericFormat::SRGB)
+                    }
+                })
+                .unwrap_or(fallback_format);
+            let supported_present_modes = device
+                .physical_device()
+                .surface_present_modes(&surface, Default::default())
+                .map_err(GraphicsError::vulkan("query present modes"))?;
+            let present_mode = choose_present_mode(config.present_mode, &supported_present_modes);
+            if present_mode != config.present_mode {
+                warn!(
+                    "Present mode {:?} isn't supported, using {:?}",
+                    config.present_mode, present_mode
+                );
+            }
+            // In pixels, which is more than the window size on high-DPI displays
+            let image_extent: [u32; 2] = window.size_in_pixels().into();
+
+            Swapchain::new(
+                device.clone(),
+                surface.clone(),
+                SwapchainCreateInfo {
+                    min_image_count: caps.min_image_count,
+                    image_format,
+                    image_extent,
+                    image_usage: usage,
+                    composite_alpha: alpha,
+                    present_mode,
+                    ..Default::default()
+                },
+            )
+            .map_err(GraphicsError::vulkan("create the swapchain"))?
+        };
+
+        let extent = swapchain.image_extent();
+        Ok(Renderer::build(
+            instance,
+            Some(surface),
+            device,
+            queue,
+            Some((swapchain, images)),
+            extent,
+            config,
+        ))
+    }
+
+    /// Creates a renderer that draws into an offscreen `width` x `height` image instead of a
+    /// window swapchain. Nothing is presented; use [`Renderer::read_frame`] to fetch the result.
+    pub fn new_headless(
+        width: u32,
+        height: u32,
+        config: &RendererConfig,
+    ) -> Result<Renderer, GraphicsError> {
+        let instance = Renderer::create_instance(InstanceExtensions::empty())?;
+        let (device, queue) =
+            Renderer::create_device(&instance, Renderer::required_device_extensions(), None)?;
+
+        Ok(Renderer::build(
+            instance,
+            None,
+            device,
+            queue,
+            None,
+            [width, height],
+            config,
+        ))
+    }
+
+    fn create_instance(extensions: InstanceExtensions) -> Result<Arc<Instance>, GraphicsError> {
+        let library = VulkanLibrary::new().map_err(GraphicsError::Library)?;
+        Instance::new(
+            library,
+            InstanceCreateInfo {
+                enabled_extensions: extensions,
+                max_api_version: Some(Version::V1_3),
+                ..Default::default()
+            },
+        )
+        .map_err(GraphicsError::vulkan("create the Vulkan instance"))
+    }
+
+    fn required_device_extensions() -> DeviceExtensions {
+        DeviceExtensions {
             ext_descriptor_indexing: true,
             ..DeviceExtensions::empty()
-        };
+        }
+    }
 
+    /// Picks the best physical device that supports `device_extensions` and has a graphics
+    /// queue (which must also be able to present to `surface`, if one is given).
+    /// See [`DEVICE_PREFERENCE_VAR`] for forcing a CPU implementation.
+    fn create_device(
+        instance: &Arc<Instance>,
+        device_extensions: DeviceExtensions,
+        surface: Option<&Arc<Surface>>,
+    ) -> Result<(Arc<Device>, Arc<Queue>), GraphicsError> {
         let device_features = DeviceFeatures {
             descriptor_binding_partially_bound: true,
             runtime_descriptor_array: true,
@@ -361,9 +504,15 @@ impl Renderer {
             ..DeviceFeatures::empty()
         };
 
+        let prefer_cpu = std::env::var(DEVICE_PREFERENCE_VAR)
+            .is_ok_and(|device| device.eq_ignore_ascii_case("cpu"));
+
         let (physical_device, queue_family_index) = instance
             .enumerate_physical_devices()
-            .unwrap()
+            .map_err(|e| GraphicsError::Vulkan {
+                operation: "enumerate devices",
+                source: e.into(),
+            })?
             .filter(|p| p.supported_extensions().contains(&device_extensions))
             .filter_map(|p| {
                 p.queue_family_properties()
@@ -371,11 +520,16 @@ impl Renderer {
                     .enumerate()
                     .position(|(i, q)| {
                         q.queue_flags.intersects(QueueFlags::GRAPHICS)
-                            && p.surface_support(i as u32, &surface).unwrap_or(false)
+                            && surface.map_or(true, |surface| {
+                                p.surface_support(i as u32, surface).unwrap_or(false)
+                            })
                     })
                     .map(|i| (p, i as u32))
             })
             .min_by_key(|(p, _)| match p.properties().device_type {
+                // Software rasterizers (lavapipe, SwiftShader) give reproducible output, so
+                // regression tests can ask for them explicitly.
+                PhysicalDeviceType::Cpu if prefer_cpu => -1,
                 PhysicalDeviceType::DiscreteGpu => 0,
                 PhysicalDeviceType::IntegratedGpu => 1,
                 PhysicalDeviceType::VirtualGpu => 2,
@@ -383,7 +537,7 @@ impl Renderer {
                 PhysicalDeviceType::Other => 4,
                 _ => 5,
             })
-            .expect("No suitable physical device found");
+            .ok_or(GraphicsError::NoSuitableDevice)?;
 
         let (device, mut queues) = Device::new(
             physical_device,
@@ -397,46 +551,24 @@ impl Renderer {
                 ..Default::default()
             },
         )
-        .unwrap();
+        .map_err(GraphicsError::vulkan("create the device"))?;
 
         let queue = queues.next().unwrap();
+        Ok((device, queue))
+    }
 
+    fn build(
+        instance: Arc<Instance>,
+        surface: Option<Arc<Surface>>,
+        device: Arc<Device>,
+        queue: Arc<Queue>,
+        swapchain: Option<(Arc<Swapchain>, Vec<Arc<Image>>)>,
+        extent: [u32; 2],
+        config: &RendererConfig,
+    ) -> Renderer {
         let mut vp = VP::new();
-
-        let (swapchain, images) = {
-            let caps = device
-                .physical_device()
-                .surface_capabilities(&surface, Default::default())
-                .unwrap();
-            let usage = caps.supported_usage_flags;
-            let alpha = caps.supported_composite_alpha.into_iter().next().unwrap();
-            let image_format = Some(
-                device
-                    .physical_device()
-                    .surface_formats(&surface, Default::default())
-                    .unwrap()[0]
-                    .0,
-            );
-            let image_extent: [u32; 2] = window.size().into();
-            let aspect_ratio = image_extent[0] as f32 / image_extent[1] as f32;
-            vp.projection = perspective(aspect_ratio, half_pi(), 0.01, 1000.0);
-
-            Swapchain::new(
-                device.clone(),
-                surface.clone(),
-                SwapchainCreateInfo {
-                    min_image_count: caps.min_image_count,
-                    image_format: image_format.unwrap(),
-                    image_extent,
-                    image_usage: usage,
-                    composite_alpha: alpha,
-                    present_mode: PresentMode::Immediate,
-                    ..Default::default()
-                },
-            )
-            .unwrap()
-        };
-
+        let aspect_ratio = extent[0] as f32 / extent[1] as f32;
+        vp.projection = perspective(aspect_ratio, half_pi(), 0.01, 1000.0);
         let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
         let descriptor_set_allocator = Arc::new(StandardDescriptorSetAllocator::new(
             device.clone(),
@@ -451,6 +583,16 @@ impl Renderer {
             Default::default(),
         ));
 
+        // Headless renderers composite into a single offscreen image instead of swapchain images.
+        let (swapchain, images, offscreen_image) = match swapchain {
+            Some((swapchain, images)) => (Some(swapchain), images, None),
+            None => {
+                let image = Renderer::create_offscreen_target(memory_allocator.clone(), extent);
+                (None, vec![image.clone()], Some(image))
+            }
+        };
+        let target_format = images[0].format();
+
         // ── Render passes ──────────────────────────────────────────────────
 
         let shadow_render_pass = vulkano::single_pass_renderpass!(
@@ -526,10 +668,7 @@ impl Renderer {
 
         let mut viewport = Viewport {
             offset: [0.0, 0.0],
-            extent: [
-                swapchain.image_extent()[0] as f32,
-                swapchain.image_extent()[1] as f32,
-            ],
+            extent: [extent[0] as f32, extent[1] as f32],
             depth_range: 0.0..=1.0,
         };
 
@@ -540,7 +679,7 @@ impl Renderer {
             device.clone(),
             attachments: {
                 color: {
-                    format: swapchain.image_format(),
+                    format: target_format,
                     samples: 1,
                     load_op: DontCare,
                     store_op: Store,
@@ -570,7 +709,8 @@ impl Renderer {
             pipelines::create_light_obj(device.clone(), viewport.clone(), lighting_pass.clone());
         let skybox_pipeline =
             pipelines::create_skybox(device.clone(), viewport.clone(), lighting_pass.clone());
-        let shadow_pipeline = pipelines::create_shadow(device.clone(), shadow_pass);
+        let shadow_pipeline =
+            pipelines::create_shadow(device.clone(), shadow_pass, config.shadow_map_size);
         let ao_pipeline = pipelines::create_ao(device.clone());
         let blur_pipeline = pipelines::create_blur(device.clone());
         let fxaa_pipeline = pipelines::create_fxaa(device.clone());
@@ -663,7 +803,7 @@ impl Renderer {
             &images,
             render_pass.clone(),
             composite_render_pass.clone(),
-            swapchain.image_format(),
+            target_format,
             &mut viewport,
         );
 
@@ -674,7 +814,7 @@ impl Renderer {
             ImageCreateInfo {
                 image_type: vulkano::image::ImageType::Dim2d,
                 format: Format::D32_SFLOAT,
-                extent: [SHADOW_MAP_SIZE, SHADOW_MAP_SIZE, 1],
+                extent: [config.shadow_map_size, config.shadow_map_size, 1],
                 usage: ImageUsage::DEPTH_STENCIL_ATTACHMENT | ImageUsage::SAMPLED,
                 ..Default::default()
             },
@@ -838,6 +978,8 @@ impl Renderer {
         )
         .unwrap();
 
+        let gpu_timer = GpuTimer::new(device.clone(), &queue);
+
         Renderer {
             instance,
             surface,
@@ -845,6 +987,7 @@ impl Renderer {
             queue,
             vp,
             swapchain,
+            offscreen_image,
             memory_allocator,
             descriptor_set_allocator,
             command_buffer_allocator,
@@ -894,15 +1037,22 @@ impl Renderer {
             commands: None,
             image_index: 0,
             acquire_future: None,
-            ao_radius: 0.05,
-            ao_att_scale: 0.95,
-            ao_dist_scale: 1.7,
-            ao_blur_depth_threshold: 100.0,
-            ao_composite_scale: 1.0,
-            ao_composite_bias: 0.0,
-            exposure: 1.0,
-            fxaa_enabled: true,
-            shadow_softness: 2.0,
+            gpu_timer,
+            capture_request: None,
+            capture: None,
+            ao_radius: config.ao_radius,
+            ao_att_scale: config.ao_att_scale,
+            ao_dist_scale: config.ao_dist_scale,
+            ao_blur_depth_threshold: config.ao_blur_depth_threshold,
+            ao_composite_scale: config.ao_composite_scale,
+            ao_composite_bias: config.ao_composite_bias,
+            exposure: config.exposure,
+            fxaa_enabled: config.fxaa_enabled,
+            shadow_softness: config.shadow_softness,
+            window_extent: extent,
+            requested_present_mode: config.present_mode,
+            environment: None,
+            previous_frame_end: None,
         }
     }
 
@@ -924,27 +1074,33 @@ impl Renderer {
             }
         }
 
-        let (image_index, suboptimal, acquire_future) =
-            match acquire_next_image(self.swapchain.clone(), None) {
-                Ok(r) => r,
-                Err(Validated::Error(VulkanError::OutOfDate)) => {
-                    self.recreate_swapchain();
-                    return;
-                }
-                Err(err) => panic!("{:?}", err),
-            };
-
-        if suboptimal {
-            self.recreate_swapchain();
-            return;
-        }
-
-        let commands = AutoCommandBufferBuilder::primary(
+        let mut commands = AutoCommandBufferBuilder::primary(
             self.command_buffer_allocator.clone(),
             self.queue.queue_family_index(),
             CommandBufferUsage::OneTimeSubmit,
         )
         .unwrap();
+        self.gpu_timer.begin_frame(&mut commands);
+
+        let Some(swapchain) = self.swapchain.clone() else {
+            self.commands = Some(commands);
+            self.image_index = 0;
+            return;
+        };
+
+        let (image_index, suboptimal, acquire_future) = match acquire_next_image(swapchain, None) {
+            Ok(r) => r,
+            Err(Validated::Error(VulkanError::OutOfDate)) => {
+                self.recreate_swapchain();
+                return;
+            }
+            Err(err) => panic!("{:?}", err),
+        };
+
+        if suboptimal {
+            self.recreate_swapchain();
+            return;
+        }
 
         self.commands = Some(commands);
         self.image_index = image_index;
@@ -971,18 +1127,49 @@ impl Renderer {
         let mut commands = self.commands.take().unwrap();
         commands.end_render_pass(SubpassEndInfo::default()).unwrap();
 
+        self.gpu_timer.begin(&mut commands, "ao");
         self.dispatch_ao(&mut commands);
+        self.gpu_timer.end(&mut commands);
+        self.gpu_timer.begin(&mut commands, "blur");
         self.dispatch_blur(&mut commands);
+        self.gpu_timer.end(&mut commands);
+        self.gpu_timer.begin(&mut commands, "fxaa");
         self.dispatch_fxaa(&mut commands);
+        self.gpu_timer.end(&mut commands);
+        self.gpu_timer.begin(&mut commands, "composite");
         self.composite(&mut commands);
+        self.gpu_timer.end(&mut commands);
+        let capture = self.record_capture(&mut commands);
 
         let command_buffer = commands.build().unwrap();
-        let af = self.acquire_future.take().unwrap();
+        self.gpu_timer.end_frame();
 
         let mut local_future: Option<Box<dyn GpuFuture>> =
             Some(Box::new(sync::now(self.device.clone())) as Box<dyn GpuFuture>);
         mem::swap(&mut local_future, previous_frame_end);
 
+        let Some(swapchain) = self.swapchain.clone() else {
+            // Headless: nothing to present, so wait for the frame to make it readable.
+            local_future
+                .take()
+                .unwrap()
+                .then_execute(self.queue.clone(), command_buffer)
+                .unwrap()
+                .then_signal_fence_and_flush()
+                .unwrap()
+                .wait(None)
+                .unwrap();
+            if let Some(capture) = capture {
+                self.resolve_capture(capture);
+            }
+            *previous_frame_end = Some(Box::new(sync::now(self.device.clone())) as Box<_>);
+            self.commands = None;
+            self.render_stage = RenderStage::Stopped;
+            return;
+        };
+
+        let af = self.acquire_future.take().unwrap();
+
         let future = local_future
             .take()
             .unwrap()
@@ -991,15 +1178,17 @@ impl Renderer {
             .unwrap()
             .then_swapchain_present(
                 self.queue.clone(),
-                SwapchainPresentInfo::swapchain_image_index(
-                    self.swapchain.clone(),
-                    self.image_index,
-                ),
+                SwapchainPresentInfo::swapchain_image_index(swapchain, self.image_index),
             )
             .then_signal_fence_and_flush();
 
         match future {
             Ok(future) => {
+                if let Some(capture) = capture {
+                    // Screenshots are rare enough to simply wait for the frame.
+                    future.wait(None).unwrap();
+                    self.resolve_capture(capture);
+                }
                 *previous_frame_end = Some(Box::new(future) as Box<_>);
             }
             Err(Validated::Error(VulkanError::OutOfDate)) => {
@@ -1007,7 +1196,7 @@ impl Renderer {
                 *previous_frame_end = Some(Box::new(sync::now(self.device.clone())) as Box<_>);
             }
             Err(e) => {
-                println!("Failed to flush future: {:?}", e);
+                error!("Failed to flush future: {:?}", e);
                 *previous_frame_end = Some(Box::new(sync::now(self.device.clone())) as Box<_>);
             }
         }
@@ -1016,6 +1205,95 @@ impl Renderer {
         self.render_stage = RenderStage::Stopped;
     }
 
+    /// Size in pixels of the images the renderer draws into.
+    pub fn image_extent(&self) -> [u32; 2] {
+        match (&self.swapchain, &self.offscreen_image) {
+            (Some(swapchain), _) => swapchain.image_extent(),
+            (None, Some(image)) => [image.extent()[0], image.extent()[1]],
+            (None, None) => {
+                unreachable!("renderer has neither a swapchain nor an offscreen target")
+            }
+        }
+    }
+
+    pub fn is_headless(&self) -> bool {
+        self.swapchain.is_none()
+    }
+
+    /// Blocks until the GPU has finished everything submitted so far.
+    pub fn wait_idle(&self) {
+        // SAFETY: the renderer is borrowed, so nothing is submitted to its queue meanwhile
+        if let Err(e) = unsafe { self.device.wait_idle() } {
+            error!("Failed to wait for the GPU: {e}");
+        }
+    }
+
+    /// Copies the last composited frame of a headless renderer back to the host as tightly
+    /// packed RGBA8 rows. Returns `None` for windowed renderers.
+    pub fn read_frame(&self) -> Option<Vec<u8>> {
+        let image = self.offscreen_image.as_ref()?;
+        let [width, height] = self.image_extent();
+
+        let readback = Buffer::new_slice::<u8>(
+            self.memory_allocator.clone(),
+            BufferCreateInfo {
+                usage: BufferUsage::TRANSFER_DST,
+                ..Default::default()
+            },
+            AllocationCreateInfo {
+                memory_type_filter: MemoryTypeFilter::PREFER_HOST
+                    | MemoryTypeFilter::HOST_RANDOM_ACCESS,
+                ..Default::default()
+            },
+            (width * height * 4) as u64,
+        )
+        .unwrap();
+
+        let mut cmd = AutoCommandBufferBuilder::primary(
+            self.command_buffer_allocator.clone(),
+            self.queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap();
+        cmd.copy_image_to_buffer(CopyImageToBufferInfo::image_buffer(
+            image.clone(),
+            readback.clone(),
+        ))
+        .unwrap();
+        cmd.build()
+            .unwrap()
+            .execute(self.queue.clone())
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap()
+            .wait(None)
+            .unwrap();
+
+        let pixels = readback.read().unwrap().to_vec();
+        Some(pixels)
+    }
+
+    fn create_offscreen_target(
+        allocator: Arc<StandardMemoryAllocator>,
+        extent: [u32; 2],
+    ) -> Arc<Image> {
+        Image::new(
+            allocator,
+            ImageCreateInfo {
+                image_type: vulkano::image::ImageType::Dim2d,
+                format: Format::R8G8B8A8_UNORM,
+                extent: [extent[0], extent[1], 1],
+                usage: ImageUsage::COLOR_ATTACHMENT | ImageUsage::TRANSFER_SRC,
+                ..Default::default()
+            },
+            AllocationCreateInfo {
+                memory_type_filter: MemoryTypeFilter::PREFER_DEVICE,
+                ..Default::default()
+            },
+        )
+        .unwrap()
+    }
+
     fn begin_main_render_pass(&mut self) {
         let clear_values = vec![
             Some([0.0, 0.0, 0.0, 1.0].into()),
@@ -1145,3 +1423,19 @@ impl Renderer {
         ]
     }
 }
+
+impl Drop for Renderer {
+    fn drop(&mut self) {
+        // The last frames may still be using any of the resources below
+        self.wait_idle();
+
+        // Per-frame state first, then the swapchain and its surface. The remaining pipelines,
+        // images and allocators follow in field order, the device last once nothing holds it.
+        self.commands = None;
+        self.acquire_future = None;
+        self.previous_frame_end = None;
+        self.capture = None;
+        self.swapchain = None;
+        self.surface = None;
+    }
+}
@@ -20,7 +20,7 @@ use crate::{
     assets::asset_manager::{self, UnifiedGeometry},
     graphics::skybox::Skybox,
     scene::components::{
-        directional_light::DirectionalLight, pointlight::Pointlight, transform::Transform,
+        directional_light::DirectionalLight, hierarchy::GlobalTransform, pointlight::Pointlight,
     },
 };
 
@@ -94,6 +94,7 @@ impl Renderer {
         )
         .unwrap();
 
+        self.gpu_scope_begin("shadow");
         self.commands
             .as_mut()
             .unwrap()
@@ -139,6 +140,7 @@ impl Renderer {
             .unwrap()
             .end_render_pass(SubpassEndInfo::default())
             .unwrap();
+        self.gpu_scope_end();
 
         self.begin_main_render_pass();
     }
@@ -186,6 +188,7 @@ impl Renderer {
         )
         .unwrap();
 
+        self.gpu_scope_begin("geometry");
         let builder = self.commands.as_mut().unwrap();
 
         builder
@@ -212,6 +215,7 @@ impl Renderer {
                 .draw_indexed_indirect(culled.indirect.clone())
                 .unwrap();
         }
+        self.gpu_scope_end();
     }
 
     pub fn ambient(
@@ -289,6 +293,7 @@ impl Renderer {
         )
         .unwrap();
 
+        self.gpu_scope_begin("ambient");
         self.commands
             .as_mut()
             .unwrap()
@@ -319,6 +324,7 @@ impl Renderer {
                 .draw(self.dummy_verts.len() as u32, 1, 0, 0)
                 .unwrap();
         }
+        self.gpu_scope_end();
     }
 
     pub fn directional(&mut self, directional_light: &DirectionalLight) {
@@ -406,6 +412,7 @@ impl Renderer {
         )
         .unwrap();
 
+        self.gpu_scope_begin("directional");
         self.commands
             .as_mut()
             .unwrap()
@@ -436,6 +443,7 @@ impl Renderer {
                 .draw(self.dummy_verts.len() as u32, 1, 0, 0)
                 .unwrap();
         }
+        self.gpu_scope_end();
     }
 
     pub fn pointlight(&mut self, light: &Pointlight) {
@@ -592,6 +600,7 @@ impl Renderer {
         )
         .unwrap();
 
+        self.gpu_scope_begin("skybox");
         self.commands
             .as_mut()
             .unwrap()
@@ -614,6 +623,7 @@ impl Renderer {
                 .draw(self.dummy_verts.len() as u32, 1, 0, 0)
                 .unwrap();
         }
+        self.gpu_scope_end();
     }
 
     pub fn light_object(&mut self, _directional_light: &DirectionalLight) {
@@ -653,7 +663,7 @@ impl Renderer {
     pub fn cull_pass(
         &mut self,
         unified: &UnifiedGeometry,
-        objects: &[(usize, Transform, u32)],
+        objects: &[(usize, GlobalTransform, u32)],
         joint_matrices: &[[[f32; 4]; 4]],
     ) -> Option<CulledDrawBuffers> {
         if objects.is_empty() {
@@ -788,6 +798,7 @@ impl Renderer {
             num_draws: objects.len() as u32,
         };
 
+        self.gpu_scope_begin("cull");
         let commands = self.commands.as_mut().unwrap();
         commands
             .bind_pipeline_compute(self.cull_pipeline.clone())
@@ -807,6 +818,7 @@ impl Renderer {
                 .dispatch([(objects.len() as u32 + 63) / 64, 1, 1])
                 .unwrap();
         }
+        self.gpu_scope_end();
 
         Some(CulledDrawBuffers {
             indirect: indirect_buffer,
@@ -25,7 +25,6 @@ use vulkano::{
 use vulkano::pipeline::graphics::rasterization::DepthBiasState;
 
 use super::{
-    SHADOW_MAP_SIZE,
     ambient_frag, ambient_vert,
     ao_comp, blur_comp, composite_frag, composite_vert, cull_comp,
     deferred_frag, deferred_vert,
@@ -384,6 +383,7 @@ pub(super) fn create_skybox(
 pub(super) fn create_shadow(
     device: Arc<Device>,
     shadow_pass: Subpass,
+    shadow_map_size: u32,
 ) -> Arc<GraphicsPipeline> {
     let vs = shadows_vert::load(device.clone()).unwrap();
     let fs = shadows_frag::load(device.clone()).unwrap();
@@ -413,7 +413,7 @@ pub(super) fn create_shadow(
             viewport_state: Some(ViewportState {
                 viewports: [Viewport {
                     offset: [0.0, 0.0],
-                    extent: [SHADOW_MAP_SIZE as f32, SHADOW_MAP_SIZE as f32],
+                    extent: [shadow_map_size as f32, shadow_map_size as f32],
                     depth_range: 0.0..=1.0,
                 }]
                 .into_iter()
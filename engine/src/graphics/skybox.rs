@@ -1,4 +1,7 @@
+use crate::core::error::AssetError;
+use image::{Rgb, Rgb32FImage};
 use std::f32::consts::PI;
+use std::path::Path;
 use std::sync::Arc;
 use vulkano::image::view::ImageView;
 
@@ -7,9 +10,12 @@ pub struct SkyboxImages {
 }
 
 impl SkyboxImages {
-    pub fn new(images: [&str; 6]) -> SkyboxImages {
-        let faces = images.map(|x| image::open(x).unwrap().to_rgba8().into_raw());
-        SkyboxImages { faces }
+    pub fn new(images: [&str; 6]) -> Result<SkyboxImages, AssetError> {
+        let mut faces: [Vec<u8>; 6] = Default::default();
+        for (face, path) in faces.iter_mut().zip(images) {
+            *face = open_image(path)?.to_rgba8().into_raw();
+        }
+        Ok(SkyboxImages { faces })
     }
 }
 
@@ -24,10 +30,17 @@ pub struct HdrSkyboxImages {
 impl HdrSkyboxImages {
     /// Load an equirectangular HDR image and convert to cubemap faces.
     /// `face_size` controls the resolution of each face (e.g. 512).
-    pub fn from_equirect(path: &str, face_size: u32) -> Self {
-        let img = image::open(path)
-            .unwrap_or_else(|e| panic!("Failed to load HDR: {path}: {e}"))
-            .into_rgb32f();
+    pub fn from_equirect(path: &str, face_size: u32) -> Result<Self, AssetError> {
+        let img = open_image(path)?.into_rgb32f();
+        Ok(Self::from_equirect_image(&img, face_size))
+    }
+
+    /// A sky of a single colour, e.g. in place of an environment map that failed to load.
+    pub fn uniform(color: [f32; 3], face_size: u32) -> Self {
+        Self::from_equirect_image(&Rgb32FImage::from_pixel(1, 1, Rgb(color)), face_size)
+    }
+
+    fn from_equirect_image(img: &Rgb32FImage, face_size: u32) -> Self {
         let (eq_w, eq_h) = img.dimensions();
 
         // For each face, given pixel (u, v) in [-1, 1], return the 3D sampling direction.
@@ -74,6 +87,13 @@ impl HdrSkyboxImages {
     }
 }
 
+fn open_image(path: &str) -> Result<image::DynamicImage, AssetError> {
+    image::open(path).map_err(|source| AssetError::Image {
+        path: Path::new(path).to_path_buf(),
+        source,
+    })
+}
+
 #[derive(Clone)]
 pub struct Skybox {
     pub cubemap: Arc<ImageView>,
@@ -2,9 +2,13 @@ use std::collections::HashSet;
 
 use shipyard::{Component, Unique};
 
+use crate::input::recording::InputEvent;
+
 #[derive(Component, Unique)]
 pub struct InputManager {
     pub pressed_keys: HashSet<sdl3::keyboard::Keycode>,
+    /// Keys that went down this frame. Cleared at the start of every frame.
+    pub just_pressed_keys: HashSet<sdl3::keyboard::Keycode>,
     pub pressed_mouse_buttons: HashSet<sdl3::mouse::MouseButton>,
     pub released_keys: HashSet<sdl3::keyboard::Keycode>,
     pub released_mouse_buttons: HashSet<sdl3::mouse::MouseButton>,
@@ -15,10 +19,43 @@ impl InputManager {
     pub fn new() -> InputManager {
         InputManager {
             pressed_keys: HashSet::new(),
+            just_pressed_keys: HashSet::new(),
             pressed_mouse_buttons: HashSet::new(),
             released_keys: HashSet::new(),
             released_mouse_buttons: HashSet::new(),
             mouse_motion: (0.0, 0.0),
         }
     }
+
+    /// Forgets the per-frame state before the next frame's events are applied.
+    pub fn begin_frame(&mut self) {
+        self.just_pressed_keys.clear();
+    }
+
+    pub fn apply(&mut self, event: &InputEvent) {
+        match *event {
+            InputEvent::KeyDown(keycode) => {
+                if self.pressed_keys.insert(keycode) {
+                    self.just_pressed_keys.insert(keycode);
+                }
+                self.released_keys.remove(&keycode);
+            }
+            InputEvent::KeyUp(keycode) => {
+                self.pressed_keys.remove(&keycode);
+                self.released_keys.insert(keycode);
+            }
+            InputEvent::MouseButtonDown(button) => {
+                self.pressed_mouse_buttons.insert(button);
+                self.released_mouse_buttons.remove(&button);
+            }
+            InputEvent::MouseButtonUp(button) => {
+                self.pressed_mouse_buttons.remove(&button);
+                self.released_mouse_buttons.insert(button);
+            }
+            InputEvent::MouseMotion { dx, dy } => {
+                self.mouse_motion.0 += dx;
+                self.mouse_motion.1 += dy;
+            }
+        }
+    }
 }
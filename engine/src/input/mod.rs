@@ -1 +1,22 @@
 pub mod input_manager;
+pub mod recording;
+
+use shipyard::World;
+
+use crate::core::config::EngineConfig;
+use crate::core::events;
+use crate::core::plugin::Plugin;
+use crate::core::schedule::Schedule;
+use input_manager::InputManager;
+use recording::InputEvent;
+
+/// Keyboard and mouse state in the [`InputManager`] unique, fed by the application every frame
+/// from live or replayed input. The same input is sent as [`InputEvent`] events.
+pub struct InputPlugin;
+
+impl Plugin for InputPlugin {
+    fn build(&mut self, world: &mut World, _schedule: &mut Schedule, _config: &EngineConfig) {
+        world.add_unique(InputManager::new());
+        events::add_event::<InputEvent>(world);
+    }
+}
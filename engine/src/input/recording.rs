@@ -0,0 +1,251 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use sdl3::event::Event;
+use sdl3::keyboard::{Keycode, Mod};
+use sdl3::mouse::{MouseButton, MouseState};
+
+const HEADER: &str = "# vulkano-engine input recording v1";
+
+/// The part of an SDL event that affects [`InputManager`](super::input_manager::InputManager).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum InputEvent {
+    KeyDown(Keycode),
+    KeyUp(Keycode),
+    MouseButtonDown(MouseButton),
+    MouseButtonUp(MouseButton),
+    MouseMotion { dx: f32, dy: f32 },
+}
+
+impl InputEvent {
+    pub fn from_sdl(event: &Event) -> Option<InputEvent> {
+        match *event {
+            Event::KeyDown {
+                keycode: Some(keycode),
+                ..
+            } => Some(InputEvent::KeyDown(keycode)),
+            Event::KeyUp {
+                keycode: Some(keycode),
+                ..
+            } => Some(InputEvent::KeyUp(keycode)),
+            Event::MouseButtonDown { mouse_btn, .. } => {
+                Some(InputEvent::MouseButtonDown(mouse_btn))
+            }
+            Event::MouseButtonUp { mouse_btn, .. } => Some(InputEvent::MouseButtonUp(mouse_btn)),
+            Event::MouseMotion { xrel, yrel, .. } => Some(InputEvent::MouseMotion {
+                dx: xrel as f32,
+                dy: yrel as f32,
+            }),
+            _ => None,
+        }
+    }
+
+    /// The SDL event the `on_event` hooks receive for this input. Live and replayed input both
+    /// go through here, so what isn't recorded (timestamps, window, modifiers, cursor position,
+    /// key repeat) is left zero in either case.
+    pub fn to_sdl(&self) -> Event {
+        match *self {
+            InputEvent::KeyDown(keycode) => Event::KeyDown {
+                timestamp: 0,
+                window_id: 0,
+                keycode: Some(keycode),
+                scancode: None,
+                keymod: Mod::NOMOD,
+                repeat: false,
+                which: 0,
+                raw: 0,
+            },
+            InputEvent::KeyUp(keycode) => Event::KeyUp {
+                timestamp: 0,
+                window_id: 0,
+                keycode: Some(keycode),
+                scancode: None,
+                keymod: Mod::NOMOD,
+                repeat: false,
+                which: 0,
+                raw: 0,
+            },
+            InputEvent::MouseButtonDown(mouse_btn) => Event::MouseButtonDown {
+                timestamp: 0,
+                window_id: 0,
+                which: 0,
+                mouse_btn,
+                clicks: 1,
+                x: 0.0,
+                y: 0.0,
+            },
+            InputEvent::MouseButtonUp(mouse_btn) => Event::MouseButtonUp {
+                timestamp: 0,
+                window_id: 0,
+                which: 0,
+                mouse_btn,
+                clicks: 1,
+                x: 0.0,
+                y: 0.0,
+            },
+            InputEvent::MouseMotion { dx, dy } => Event::MouseMotion {
+                timestamp: 0,
+                window_id: 0,
+                which: 0,
+                mousestate: MouseState::from_sdl_state(0),
+                x: 0.0,
+                y: 0.0,
+                xrel: dx,
+                yrel: dy,
+            },
+        }
+    }
+
+    fn parse(line: &str) -> Result<InputEvent, String> {
+        let (kind, rest) = line.split_once(' ').unwrap_or((line, ""));
+        match kind {
+            "key_down" => parse_keycode(rest).map(InputEvent::KeyDown),
+            "key_up" => parse_keycode(rest).map(InputEvent::KeyUp),
+            "mouse_down" => parse_mouse_button(rest).map(InputEvent::MouseButtonDown),
+            "mouse_up" => parse_mouse_button(rest).map(InputEvent::MouseButtonUp),
+            "mouse_motion" => {
+                let mut values = rest.split_whitespace().map(str::parse::<f32>);
+                match (values.next(), values.next(), values.next()) {
+                    (Some(Ok(dx)), Some(Ok(dy)), None) => Ok(InputEvent::MouseMotion { dx, dy }),
+                    _ => Err(format!("invalid mouse motion `{rest}`")),
+                }
+            }
+            _ => Err(format!("unknown input event `{kind}`")),
+        }
+    }
+}
+
+impl fmt::Display for InputEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputEvent::KeyDown(keycode) => write!(f, "key_down {}", keycode.name()),
+            InputEvent::KeyUp(keycode) => write!(f, "key_up {}", keycode.name()),
+            InputEvent::MouseButtonDown(button) => {
+                write!(f, "mouse_down {}", mouse_button_name(*button))
+            }
+            InputEvent::MouseButtonUp(button) => {
+                write!(f, "mouse_up {}", mouse_button_name(*button))
+            }
+            // `{}` on f32 prints the shortest representation that parses back to the same value.
+            InputEvent::MouseMotion { dx, dy } => write!(f, "mouse_motion {dx} {dy}"),
+        }
+    }
+}
+
+fn parse_keycode(name: &str) -> Result<Keycode, String> {
+    Keycode::from_name(name).ok_or_else(|| format!("unknown key `{name}`"))
+}
+
+fn mouse_button_name(button: MouseButton) -> &'static str {
+    match button {
+        MouseButton::Left => "left",
+        MouseButton::Middle => "middle",
+        MouseButton::Right => "right",
+        MouseButton::X1 => "x1",
+        MouseButton::X2 => "x2",
+        _ => "unknown",
+    }
+}
+
+fn parse_mouse_button(name: &str) -> Result<MouseButton, String> {
+    match name {
+        "left" => Ok(MouseButton::Left),
+        "middle" => Ok(MouseButton::Middle),
+        "right" => Ok(MouseButton::Right),
+        "x1" => Ok(MouseButton::X1),
+        "x2" => Ok(MouseButton::X2),
+        "unknown" => Ok(MouseButton::Unknown),
+        _ => Err(format!("unknown mouse button `{name}`")),
+    }
+}
+
+/// Input and frame time of one recorded frame.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecordedFrame {
+    pub dt: f32,
+    pub events: Vec<InputEvent>,
+}
+
+/// A recorded session: one [`RecordedFrame`] per frame, in order.
+///
+/// Stored as text, one line per entry:
+///
+/// ```text
+/// frame 0.016666668
+/// key_down W
+/// mouse_motion 3 -1.5
+/// frame 0.016
+/// key_up W
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InputRecording {
+    pub frames: VecDeque<RecordedFrame>,
+}
+
+impl InputRecording {
+    pub fn load(path: impl AsRef<Path>) -> io::Result<InputRecording> {
+        let text = std::fs::read_to_string(path)?;
+        InputRecording::parse(&text).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    pub fn parse(text: &str) -> Result<InputRecording, String> {
+        let mut frames = VecDeque::new();
+
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(dt) = line.strip_prefix("frame ") {
+                let dt = dt
+                    .trim()
+                    .parse()
+                    .map_err(|_| format!("line {}: invalid frame time `{dt}`", index + 1))?;
+                frames.push_back(RecordedFrame {
+                    dt,
+                    events: Vec::new(),
+                });
+                continue;
+            }
+
+            let event = InputEvent::parse(line).map_err(|e| format!("line {}: {e}", index + 1))?;
+            frames
+                .back_mut()
+                .ok_or_else(|| format!("line {}: input event before the first frame", index + 1))?
+                .events
+                .push(event);
+        }
+
+        Ok(InputRecording { frames })
+    }
+
+    pub fn next_frame(&mut self) -> Option<RecordedFrame> {
+        self.frames.pop_front()
+    }
+}
+
+/// Streams frames to a recording file as they happen, so a crash still leaves every frame up to
+/// the last one on disk.
+pub struct InputRecorder {
+    writer: BufWriter<File>,
+}
+
+impl InputRecorder {
+    pub fn create(path: impl AsRef<Path>) -> io::Result<InputRecorder> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writeln!(writer, "{HEADER}")?;
+        Ok(InputRecorder { writer })
+    }
+
+    pub fn record_frame(&mut self, dt: f32, events: &[InputEvent]) -> io::Result<()> {
+        writeln!(self.writer, "frame {dt}")?;
+        for event in events {
+            writeln!(self.writer, "{event}")?;
+        }
+        self.writer.flush()
+    }
+}
@@ -0,0 +1,78 @@
+use std::sync::Mutex;
+
+use rapier3d::prelude::{
+    ColliderSet, CollisionEvent, ContactPair, EventHandler, Real, RigidBodySet,
+};
+use shipyard::{EntityId, UniqueViewMut};
+
+use crate::core::events::Events;
+use crate::physics::physics_engine::PhysicsEngine;
+
+/// Engine event sent when two colliders start or stop touching.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Collision {
+    pub entities: [EntityId; 2],
+    /// `true` when the colliders started touching, `false` when they separated.
+    pub started: bool,
+    /// One of the colliders is a sensor.
+    pub sensor: bool,
+}
+
+/// Collects rapier's collision events during a step. Rapier hands them over through `&self`,
+/// possibly from several threads, hence the lock.
+#[derive(Default)]
+pub struct CollisionCollector {
+    events: Mutex<Vec<CollisionEvent>>,
+}
+
+impl CollisionCollector {
+    pub fn drain(&mut self) -> Vec<CollisionEvent> {
+        std::mem::take(self.events.get_mut().unwrap())
+    }
+}
+
+impl EventHandler for CollisionCollector {
+    fn handle_collision_event(
+        &self,
+        _bodies: &RigidBodySet,
+        _colliders: &ColliderSet,
+        event: CollisionEvent,
+        _contact_pair: Option<&ContactPair>,
+    ) {
+        self.events.lock().unwrap().push(event);
+    }
+
+    fn handle_contact_force_event(
+        &self,
+        _dt: Real,
+        _bodies: &RigidBodySet,
+        _colliders: &ColliderSet,
+        _contact_pair: &ContactPair,
+        _total_force_magnitude: Real,
+    ) {
+    }
+}
+
+/// Publishes the collisions of the last step as [`Collision`] events. Colliders are mapped back
+/// to their entities through the user data set when they were created.
+pub fn collision_events_system(
+    mut physics: UniqueViewMut<PhysicsEngine>,
+    mut collisions: UniqueViewMut<Events<Collision>>,
+) {
+    let physics = &mut *physics;
+    for event in physics.event_handler.drain() {
+        let entity = |handle| {
+            physics
+                .collider_set
+                .get(handle)
+                .and_then(|collider| EntityId::from_inner(collider.user_data as u64))
+        };
+        if let (Some(a), Some(b)) = (entity(event.collider1()), entity(event.collider2())) {
+            collisions.send(Collision {
+                entities: [a, b],
+                started: event.started(),
+                sensor: event.sensor(),
+            });
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use nalgebra::UnitQuaternion;
+use shipyard::{Component, Get, IntoIter, UniqueView, View, ViewMut};
+
+use crate::scene::components::fixed_time::FixedTime;
+use crate::scene::components::hierarchy::{Parent, local_position, local_rotation};
+use crate::scene::components::transform::Transform;
+
+/// Render-space world positions and rotations of a simulated body after the last two fixed
+/// steps. The rendered `Transform` is blended between them so motion stays smooth when the
+/// frame rate and the physics rate don't line up.
+///
+/// Added automatically to dynamic and velocity-based kinematic bodies.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct PhysicsPose {
+    pub previous: [f32; 3],
+    pub current: [f32; 3],
+    pub previous_rotation: UnitQuaternion<f32>,
+    pub current_rotation: UnitQuaternion<f32>,
+}
+
+impl PhysicsPose {
+    pub fn new(position: [f32; 3], rotation: UnitQuaternion<f32>) -> Self {
+        Self {
+            previous: position,
+            current: position,
+            previous_rotation: rotation,
+            current_rotation: rotation,
+        }
+    }
+
+    /// Records the position and rotation after a new fixed step.
+    pub fn push(&mut self, position: [f32; 3], rotation: UnitQuaternion<f32>) {
+        self.previous = self.current;
+        self.current = position;
+        self.previous_rotation = self.current_rotation;
+        self.current_rotation = rotation;
+    }
+
+    pub fn interpolate(&self, alpha: f32) -> [f32; 3] {
+        let [px, py, pz] = self.previous;
+        let [cx, cy, cz] = self.current;
+        [
+            px + (cx - px) * alpha,
+            py + (cy - py) * alpha,
+            pz + (cz - pz) * alpha,
+        ]
+    }
+
+    /// Spherical blend between the last two rotations. Opposite rotations have no single
+    /// shortest path, so they snap to the latest one.
+    pub fn interpolate_rotation(&self, alpha: f32) -> UnitQuaternion<f32> {
+        self.previous_rotation
+            .try_slerp(&self.current_rotation, alpha, 1.0e-6)
+            .unwrap_or(self.current_rotation)
+    }
+}
+
+/// Opt-out marker: the entity's `Transform` snaps to the latest physics pose instead of
+/// being interpolated. Useful for objects that teleport or that gameplay code reads back
+/// every fixed step.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct NoInterpolation;
+
+/// Writes the interpolated physics position and rotation into every interpolated body's
+/// `Transform`, relative to its parent if it has one.
+pub fn physics_interpolation_system(
+    fixed_time: UniqueView<FixedTime>,
+    poses: View<PhysicsPose>,
+    no_interpolation: View<NoInterpolation>,
+    parents: View<Parent>,
+    mut transforms: ViewMut<Transform>,
+) {
+    for (id, pose) in poses.iter().with_id() {
+        if no_interpolation.contains(id) {
+            continue;
+        }
+
+        let position = pose.interpolate(fixed_time.alpha);
+        let [x, y, z] = local_position(id, position, &transforms, &parents);
+        let rotation = local_rotation(
+            id,
+            pose.interpolate_rotation(fixed_time.alpha),
+            &transforms,
+            &parents,
+        );
+        if let Ok(mut transform) = (&mut transforms).get(id) {
+            transform.set_position(x, y, z);
+            transform.rotation = rotation;
+        }
+    }
+}
@@ -1,9 +1,66 @@
+pub mod collision;
+pub mod interpolation;
 pub mod physics_engine;
 
-use shipyard::World;
+use shipyard::{IntoWorkload, Workload, World};
 
-pub fn run_physics_systems(world: &mut World) {
-    physics_engine::physics_sync_in(world);
-    physics_engine::physics_step(world);
-    physics_engine::physics_sync_out(world);
+use crate::core::config::EngineConfig;
+use crate::core::events;
+use crate::core::plugin::Plugin;
+use crate::core::schedule::{Schedule, Stage};
+use physics_engine::PhysicsEngine;
+
+/// One fixed physics step: kinematic characters, then the simulation, then the results are
+/// copied back into `Transform`s and the step's collisions are sent as events.
+pub fn physics_workload() -> Workload {
+    (
+        physics_engine::physics_kinematic,
+        physics_engine::physics_kinematic_impulses,
+        physics_engine::physics_step,
+        physics_engine::physics_sync_out,
+        collision::collision_events_system,
+    )
+        .into_workload()
+}
+
+/// Rapier physics: the [`PhysicsEngine`] unique, body creation, the fixed step, the
+/// [`Collision`](collision::Collision) events and the interpolation of rendered transforms
+/// between steps.
+pub struct PhysicsPlugin;
+
+impl PhysicsPlugin {
+    fn physics_engine(config: &EngineConfig) -> PhysicsEngine {
+        let mut physics_engine = PhysicsEngine::new();
+        let [gx, gy, gz] = config.physics.gravity;
+        physics_engine.gravity = rapier3d::math::Vec3::new(gx, gy, gz);
+        physics_engine.integration_parameters.dt = config.physics.fixed_dt;
+        physics_engine
+    }
+}
+
+impl Plugin for PhysicsPlugin {
+    fn build(&mut self, world: &mut World, schedule: &mut Schedule, config: &EngineConfig) {
+        world.add_unique(Self::physics_engine(config));
+        events::add_event::<collision::Collision>(world);
+
+        // Bodies are created at startup and for entities spawned later, before the next step
+        schedule
+            .add_system(
+                Stage::Startup,
+                physics_engine::physics_bodies_creation_system,
+            )
+            .add_system(
+                Stage::PreUpdate,
+                physics_engine::physics_bodies_creation_system,
+            )
+            .add_workload(Stage::FixedUpdate, physics_workload())
+            .add_system(
+                Stage::PreRender,
+                interpolation::physics_interpolation_system,
+            );
+    }
+
+    fn reset(&mut self, world: &mut World, config: &EngineConfig) {
+        world.add_unique(Self::physics_engine(config));
+    }
 }
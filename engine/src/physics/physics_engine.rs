@@ -1,9 +1,18 @@
-use crate::prelude::delta_time::DeltaTime;
+use crate::physics::collision::CollisionCollector;
+use crate::physics::interpolation::{NoInterpolation, PhysicsPose};
+use crate::prelude::fixed_time::FixedTime;
+use crate::prelude::hierarchy::{Parent, local_position, local_rotation, world_matrix};
 use crate::prelude::transform::Transform;
+use crate::{debug, warn};
+use nalgebra::{Quaternion, UnitQuaternion};
+use nalgebra_glm::vec3;
 use rapier3d::control::CharacterCollision;
 use rapier3d::control::{CharacterAutostep, CharacterLength};
 use rapier3d::{control::KinematicCharacterController, prelude::*};
-use shipyard::{Component, IntoIter, Unique, UniqueView, View, ViewMut, World};
+use shipyard::{
+    Component, EntitiesViewMut, EntityId, Get, IntoIter, Unique, UniqueView, UniqueViewMut, View,
+    ViewMut,
+};
 
 #[derive(Component, Unique)]
 pub struct PhysicsEngine {
@@ -18,9 +27,9 @@ pub struct PhysicsEngine {
     pub impulse_joint_set: ImpulseJointSet,
     pub multibody_joint_set: MultibodyJointSet,
     pub ccd_solver: CCDSolver,
-    // Optional: physics hooks and event handler
+    // Optional: physics hooks
     pub physics_hooks: (),
-    pub event_handler: (),
+    pub event_handler: CollisionCollector,
 }
 
 impl PhysicsEngine {
@@ -38,7 +47,7 @@ impl PhysicsEngine {
             multibody_joint_set: MultibodyJointSet::new(),
             ccd_solver: CCDSolver::new(),
             physics_hooks: (),
-            event_handler: (),
+            event_handler: CollisionCollector::default(),
         }
     }
 
@@ -162,197 +171,204 @@ impl KinematicCharacterComponent {
     }
 }
 
-pub fn physics_bodies_creation_system(world: &mut World) {
-    let mut physics = world.get_unique::<&mut PhysicsEngine>().unwrap();
-
-    let mut rigid_body_set = RigidBodySet::new();
-
-    world.run(
-        |transforms: View<Transform>,
-         mut bodies: ViewMut<RigidBodyComponent>,
-         mut colliders: ViewMut<ColliderComponent>| {
-            // Create rigid bodies for entities that have RigidBodyComponent but no handle yet
-            for (id, (transform, body)) in (&transforms, &mut bodies).iter().with_id() {
-                if body.handle.is_none() {
-                    let pos = transform.get_position_vector();
-                    let rot = transform.get_rotation_vector();
-
-                    // Create rigid body with position and rotation from transform
-                    // inverting y axis because vulkano uses a right-handed coordinate system
-                    let rigid_body = RigidBodyBuilder::new(body.body_type)
-                        .translation(Vector::new(pos[0], -pos[1], pos[2]))
-                        .rotation(Vector::new(rot[0], rot[1], rot[2]))
-                        .linear_damping(1.0) // Add damping to slow down falling (0.0 = no damping, 1.0 = lots)
-                        .build();
-
-                    let handle = rigid_body_set.insert(rigid_body);
-                    body.handle = Some(handle);
-
-                    println!("Created rigid body for entity {:?} with handle {:?}", id, handle);
-                }
+pub fn physics_bodies_creation_system(
+    mut physics: UniqueViewMut<PhysicsEngine>,
+    entities: EntitiesViewMut,
+    transforms: View<Transform>,
+    parents: View<Parent>,
+    no_interpolation: View<NoInterpolation>,
+    mut bodies: ViewMut<RigidBodyComponent>,
+    mut colliders: ViewMut<ColliderComponent>,
+    mut poses: ViewMut<PhysicsPose>,
+) {
+    let physics = &mut *physics;
+
+    // Create rigid bodies for entities that have RigidBodyComponent but no handle yet
+    for (id, (_, body)) in (&transforms, &mut bodies).iter().with_id() {
+        if body.handle.is_none() {
+            let transform = world_transform(id, &transforms, &parents);
+            let pos = transform.get_position_vector();
+            let pose = to_physics_pose(&transform);
+
+            // Create rigid body at the entity's world position and rotation
+            let rigid_body = RigidBodyBuilder::new(body.body_type)
+                .translation(pose.translation)
+                .rotation(pose.rotation.to_scaled_axis())
+                .linear_damping(1.0) // Add damping to slow down falling (0.0 = no damping, 1.0 = lots)
+                .build();
+
+            let handle = physics.rigid_body_set.insert(rigid_body);
+            body.handle = Some(handle);
+
+            if is_simulated(body.body_type) && !no_interpolation.contains(id) {
+                entities.add_component(id, &mut poses, PhysicsPose::new(pos, transform.rotation));
             }
 
-            // Create colliders for entities that have ColliderComponent but no handle yet
-            for (id, (body, collider)) in (&bodies, &mut colliders).iter().with_id() {
-                if collider.handle.is_none() {
-                    if let Some(body_handle) = body.handle {
-                        // Create collider attached to the rigid body
-                        let collider_builder = ColliderBuilder::new(collider.shape.clone())
-                            .collision_groups(InteractionGroups::new(
-                                Group::GROUP_1,
-                                Group::GROUP_1 | Group::GROUP_2,
-                                InteractionTestMode::And));
-
-                        let handle = physics.collider_set.insert(collider_builder);
-
-                        physics.collider_set.set_parent(handle, Some(body_handle), &mut rigid_body_set);
-                        collider.handle = Some(handle);
-
-                        println!("Created collider for entity {:?} with handle {:?}", id, handle);
-                    } else {
-                        println!(
-                            "Warning: Entity {:?} has ColliderComponent but no RigidBodyComponent handle",
-                            id
-                        );
-                    }
-                }
+            debug!(
+                "Created rigid body for entity {:?} with handle {:?}",
+                id, handle
+            );
+        }
+    }
+
+    // Create colliders for entities that have ColliderComponent but no handle yet
+    for (id, (body, collider)) in (&bodies, &mut colliders).iter().with_id() {
+        if collider.handle.is_none() {
+            if let Some(body_handle) = body.handle {
+                // Create collider attached to the rigid body
+                let collider_builder = ColliderBuilder::new(collider.shape.clone())
+                    .collision_groups(InteractionGroups::new(
+                        Group::GROUP_1,
+                        Group::GROUP_1 | Group::GROUP_2,
+                        InteractionTestMode::And,
+                    ))
+                    // Reported as `Collision` events, mapped back to the entity by its id
+                    .active_events(ActiveEvents::COLLISION_EVENTS)
+                    .user_data(id.inner() as u128);
+
+                let handle = physics.collider_set.insert(collider_builder);
+
+                physics.collider_set.set_parent(
+                    handle,
+                    Some(body_handle),
+                    &mut physics.rigid_body_set,
+                );
+                collider.handle = Some(handle);
+
+                debug!(
+                    "Created collider for entity {:?} with handle {:?}",
+                    id, handle
+                );
+            } else {
+                warn!(
+                    "Entity {:?} has ColliderComponent but no RigidBodyComponent handle",
+                    id
+                );
             }
-            physics.rigid_body_set = rigid_body_set;
-        },
-    )
+        }
+    }
 }
 
-pub fn physics_sync_in(world: &mut World) {
-    let mut physics = world.get_unique::<&mut PhysicsEngine>().unwrap();
-
-    world.run(
-        |transforms: View<Transform>, bodies: View<RigidBodyComponent>| {
-            // Update physics bodies from Transform components
-            // Convert rendering Y (down is positive) to physics Y (up is positive)
-            for (transform, body) in (&transforms, &bodies).iter() {
-                if let Some(handle) = body.handle
-                    && (body.body_type == RigidBodyType::KinematicPositionBased
-                        || body.body_type == RigidBodyType::KinematicVelocityBased)
-                {
-                    if let Some(rigid_body) = physics.rigid_body_set.get_mut(handle) {
-                        let pos = transform.get_position_vector();
-
-                        // Flip Y axis: rendering -Y up -> physics +Y up
-                        rigid_body.set_translation(Vector::new(pos[0], -pos[1], pos[2]), true);
-                    }
-                }
+pub fn physics_sync_in(
+    mut physics: UniqueViewMut<PhysicsEngine>,
+    transforms: View<Transform>,
+    parents: View<Parent>,
+    bodies: View<RigidBodyComponent>,
+) {
+    // Update physics bodies from Transform components
+    for (id, (_, body)) in (&transforms, &bodies).iter().with_id() {
+        if let Some(handle) = body.handle
+            && (body.body_type == RigidBodyType::KinematicPositionBased
+                || body.body_type == RigidBodyType::KinematicVelocityBased)
+        {
+            if let Some(rigid_body) = physics.rigid_body_set.get_mut(handle) {
+                let pose = to_physics_pose(&world_transform(id, &transforms, &parents));
+                rigid_body.set_translation(pose.translation, true);
+                rigid_body.set_rotation(pose.rotation, true);
             }
-        },
-    );
+        }
+    }
 }
 
-pub fn physics_kinematic(world: &mut World) {
-    let mut physics = world.get_unique::<&mut PhysicsEngine>().unwrap();
-
-    world.run(
-        |mut kinematic_character: ViewMut<KinematicCharacterComponent>,
-         bodies: View<RigidBodyComponent>,
-         delta_time: UniqueView<DeltaTime>| {
-            let dt = delta_time.0;
-
-            for (kinematic_character, body) in (&mut kinematic_character, &bodies).iter() {
-                let body_handle = body.handle.unwrap();
-                let rigid_body = physics.rigid_body_set.get(body_handle).unwrap();
-                let collider = physics.collider_set.get(rigid_body.colliders()[0]).unwrap();
-                kinematic_character.cached_shape = Some(collider.shared_shape().clone());
-
-                let mut collisions = vec![];
-
-                // Immutable borrows: read collider shape, position, and query pipeline
-                // All drop at the end of this block before the mutable borrow below
-                let simulated_movement = {
-                    let desired_translation = kinematic_character.desired_movement;
-
-                    kinematic_character.controller.move_shape(
-                        dt,
-                        &physics.broad_phase.as_query_pipeline(
-                            physics.narrow_phase.query_dispatcher(),
-                            &physics.rigid_body_set,
-                            &physics.collider_set,
-                            QueryFilter::default().exclude_rigid_body(body_handle),
-                        ),
-                        collider.shape(),
-                        rigid_body.position().into(),
-                        desired_translation,
-                        |collision| {
-                            collisions.push(collision);
-                        },
-                    )
-                };
-
-                let jumping = kinematic_character.desired_movement.y > 0.0;
-                kinematic_character.grounded = simulated_movement.grounded;
-                if simulated_movement.grounded && !jumping {
-                    kinematic_character.vertical_velocity = 0.0;
-                }
-                kinematic_character.collisions = collisions;
-
-                // Mutable borrow: apply the result
-                if let Some(rigid_body) = physics.rigid_body_set.get_mut(body_handle) {
-                    let mut linvel = simulated_movement.translation / dt;
-                    // When grounded and not jumping, zero vertical velocity to prevent
-                    // micro-corrections against the floor offset being amplified into jitter.
-                    // When jumping, let the upward linvel through even if grounded is still
-                    // reported (snap_to_ground can keep grounded=true for the first frame).
-                    if simulated_movement.grounded && !jumping {
-                        linvel.y = 0.0;
-                    }
-                    rigid_body.set_linvel(linvel, true);
-                }
+pub fn physics_kinematic(
+    mut physics: UniqueViewMut<PhysicsEngine>,
+    mut kinematic_character: ViewMut<KinematicCharacterComponent>,
+    bodies: View<RigidBodyComponent>,
+    fixed_time: UniqueView<FixedTime>,
+) {
+    let dt = fixed_time.step;
+
+    for (kinematic_character, body) in (&mut kinematic_character, &bodies).iter() {
+        let body_handle = body.handle.unwrap();
+        let rigid_body = physics.rigid_body_set.get(body_handle).unwrap();
+        let collider = physics.collider_set.get(rigid_body.colliders()[0]).unwrap();
+        kinematic_character.cached_shape = Some(collider.shared_shape().clone());
+
+        let mut collisions = vec![];
+
+        // Immutable borrows: read collider shape, position, and query pipeline
+        // All drop at the end of this block before the mutable borrow below
+        let simulated_movement = {
+            let desired_translation = kinematic_character.desired_movement;
+
+            kinematic_character.controller.move_shape(
+                dt,
+                &physics.broad_phase.as_query_pipeline(
+                    physics.narrow_phase.query_dispatcher(),
+                    &physics.rigid_body_set,
+                    &physics.collider_set,
+                    QueryFilter::default().exclude_rigid_body(body_handle),
+                ),
+                collider.shape(),
+                rigid_body.position().into(),
+                desired_translation,
+                |collision| {
+                    collisions.push(collision);
+                },
+            )
+        };
+
+        let jumping = kinematic_character.desired_movement.y > 0.0;
+        kinematic_character.grounded = simulated_movement.grounded;
+        if simulated_movement.grounded && !jumping {
+            kinematic_character.vertical_velocity = 0.0;
+        }
+        kinematic_character.collisions = collisions;
+
+        // Mutable borrow: apply the result
+        if let Some(rigid_body) = physics.rigid_body_set.get_mut(body_handle) {
+            let mut linvel = simulated_movement.translation / dt;
+            // When grounded and not jumping, zero vertical velocity to prevent
+            // micro-corrections against the floor offset being amplified into jitter.
+            // When jumping, let the upward linvel through even if grounded is still
+            // reported (snap_to_ground can keep grounded=true for the first frame).
+            if simulated_movement.grounded && !jumping {
+                linvel.y = 0.0;
             }
-        },
-    );
+            rigid_body.set_linvel(linvel, true);
+        }
+    }
 }
 
-pub fn physics_kinematic_impulses(world: &mut World) {
-    let mut physics = world.get_unique::<&mut PhysicsEngine>().unwrap();
+pub fn physics_kinematic_impulses(
+    mut physics: UniqueViewMut<PhysicsEngine>,
+    mut kinematic_characters: ViewMut<KinematicCharacterComponent>,
+    bodies: View<RigidBodyComponent>,
+    fixed_time: UniqueView<FixedTime>,
+) {
     let physics = &mut *physics;
+    let dt = fixed_time.step;
 
-    world.run(
-        |mut kinematic_characters: ViewMut<KinematicCharacterComponent>,
-         bodies: View<RigidBodyComponent>,
-         delta_time: UniqueView<DeltaTime>| {
-            let dt = delta_time.0;
-
-            for (kinematic_character, body) in (&mut kinematic_characters, &bodies).iter() {
-                if kinematic_character.collisions.is_empty() {
-                    continue;
-                }
+    for (kinematic_character, body) in (&mut kinematic_characters, &bodies).iter() {
+        if kinematic_character.collisions.is_empty() {
+            continue;
+        }
 
-                let body_handle = body.handle.unwrap();
-
-                let dispatcher = physics.narrow_phase.query_dispatcher();
-
-                kinematic_character
-                    .controller
-                    .solve_character_collision_impulses(
-                        dt,
-                        &mut physics.broad_phase.as_query_pipeline_mut(
-                            dispatcher,
-                            &mut physics.rigid_body_set,
-                            &mut physics.collider_set,
-                            QueryFilter::default().exclude_rigid_body(body_handle),
-                        ),
-                        kinematic_character.cached_shape.as_ref().unwrap().as_ref(),
-                        80.0,
-                        &kinematic_character.collisions,
-                    );
-
-                kinematic_character.collisions.clear();
-            }
-        },
-    );
+        let body_handle = body.handle.unwrap();
+
+        let dispatcher = physics.narrow_phase.query_dispatcher();
+
+        kinematic_character
+            .controller
+            .solve_character_collision_impulses(
+                dt,
+                &mut physics.broad_phase.as_query_pipeline_mut(
+                    dispatcher,
+                    &mut physics.rigid_body_set,
+                    &mut physics.collider_set,
+                    QueryFilter::default().exclude_rigid_body(body_handle),
+                ),
+                kinematic_character.cached_shape.as_ref().unwrap().as_ref(),
+                80.0,
+                &kinematic_character.collisions,
+            );
+
+        kinematic_character.collisions.clear();
+    }
 }
 
-pub fn physics_step(world: &mut World) {
+pub fn physics_step(mut physics: UniqueViewMut<PhysicsEngine>) {
     // Run the physics simulation step
-    let mut physics = world.get_unique::<&mut PhysicsEngine>().unwrap();
-
     physics.step();
 
     // Debug: print rigid body positions (optional - remove in production)
@@ -360,24 +376,84 @@ pub fn physics_step(world: &mut World) {
     // rigid_bodies.for_each(|body| println!("rigdbody transliacija: {}", body.1.translation()));
 }
 
-pub fn physics_sync_out(world: &mut World) {
-    let physics = world.get_unique::<&PhysicsEngine>().unwrap();
-
-    world.run(
-        |mut transforms: ViewMut<Transform>, bodies: View<RigidBodyComponent>| {
-            for (transform, body) in (&mut transforms, &bodies).iter() {
-                if let Some(handle) = body.handle
-                    && (body.body_type == RigidBodyType::Dynamic
-                        || body.body_type == RigidBodyType::KinematicVelocityBased)
-                {
-                    if let Some(rigid_body) = physics.rigid_body_set.get(handle) {
-                        let pos = rigid_body.translation();
-
-                        // Flip Y axis back: physics +Y up -> rendering -Y up
-                        transform.set_position(pos.x, -pos.y, pos.z);
+/// Bodies whose position comes from the simulation and is copied back into `Transform`.
+fn is_simulated(body_type: RigidBodyType) -> bool {
+    body_type == RigidBodyType::Dynamic || body_type == RigidBodyType::KinematicVelocityBased
+}
+
+/// The rapier pose (position and rotation) of a render-space transform. Rendering has -Y up
+/// and physics +Y up, so the Y axis is flipped and the rotation mirrored with it. Scale isn't
+/// part of a pose.
+pub fn to_physics_pose(transform: &Transform) -> Pose {
+    let t = transform.translation;
+    let q = transform.rotation;
+    Pose::from_parts(
+        Vector::new(t.x, -t.y, t.z),
+        Rotation::from_xyzw(-q.i, q.j, -q.k, q.w),
+    )
+}
+
+/// The render-space transform of a rapier pose, with unit scale; the inverse of
+/// [`to_physics_pose`].
+pub fn from_physics_pose(pose: &Pose) -> Transform {
+    let (t, q) = (pose.translation, pose.rotation);
+    Transform::from_trs(
+        vec3(t.x, -t.y, t.z),
+        UnitQuaternion::from_quaternion(Quaternion::new(q.w, -q.x, q.y, -q.z)),
+        vec3(1.0, 1.0, 1.0),
+    )
+}
+
+/// The entity's transform in world space, so bodies of child entities start and move where
+/// they're drawn.
+fn world_transform(
+    entity: EntityId,
+    transforms: &View<Transform>,
+    parents: &View<Parent>,
+) -> Transform {
+    world_matrix(entity, transforms, parents)
+        .map(|matrix| Transform::from_matrix(&matrix))
+        .unwrap_or_default()
+}
+
+/// Copies simulated positions and rotations back after a step. Bodies with a [`PhysicsPose`]
+/// only record the new pose; their `Transform` is written by `physics_interpolation_system`
+/// before rendering. Everything else snaps immediately. Poses are in world space and converted
+/// into the parent's space for child entities.
+pub fn physics_sync_out(
+    physics: UniqueView<PhysicsEngine>,
+    mut transforms: ViewMut<Transform>,
+    parents: View<Parent>,
+    mut poses: ViewMut<PhysicsPose>,
+    no_interpolation: View<NoInterpolation>,
+    bodies: View<RigidBodyComponent>,
+) {
+    for (id, body) in bodies.iter().with_id() {
+        if let Some(handle) = body.handle
+            && is_simulated(body.body_type)
+        {
+            if let Some(rigid_body) = physics.rigid_body_set.get(handle) {
+                let simulated = from_physics_pose(rigid_body.position());
+                let position = simulated.get_position_vector();
+
+                let interpolated = match (&mut poses).get(id) {
+                    Ok(mut pose) => {
+                        pose.push(position, simulated.rotation);
+                        !no_interpolation.contains(id)
                     }
+                    Err(_) => false,
+                };
+                if interpolated {
+                    continue;
+                }
+
+                let [x, y, z] = local_position(id, position, &transforms, &parents);
+                let rotation = local_rotation(id, simulated.rotation, &transforms, &parents);
+                if let Ok(mut transform) = (&mut transforms).get(id) {
+                    transform.set_position(x, y, z);
+                    transform.rotation = rotation;
                 }
             }
-        },
-    );
+        }
+    }
 }
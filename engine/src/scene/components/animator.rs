@@ -1,10 +1,14 @@
 use nalgebra::{Quaternion, UnitQuaternion};
 use nalgebra_glm as glm;
-use shipyard::Component;
+use rayon::iter::ParallelIterator;
+use shipyard::{Component, EntityId, IntoIter, UniqueView, UniqueViewMut, View, ViewMut};
 
 use crate::assets::animation::{
     AnimationChannel, AnimationClip, Interpolation, NodeTree, Skin, SamplerOutput, TargetProperty,
 };
+use crate::assets::asset_manager::AssetHandle;
+use crate::core::events::Events;
+use crate::scene::components::delta_time::DeltaTime;
 
 // ── public component ──────────────────────────────────────────────────────────
 
@@ -15,10 +19,22 @@ pub struct Animator {
     pub current_time: f32,
     pub playing: bool,
     pub looping: bool,
+    /// The model `AssetManager::create_animator` made the animator for, which scene files save
+    /// it as. `None` for animators built by hand.
+    pub model: Option<AssetHandle>,
 
     node_tree: NodeTree,
     skin: Skin,
     joint_matrices: Vec<[[f32; 4]; 4]>,
+    /// Set by the update in which a non-looping clip reached its end.
+    finished: bool,
+}
+
+/// Engine event sent when a non-looping clip plays to its end.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AnimationFinished {
+    pub entity: EntityId,
+    pub clip: usize,
 }
 
 impl Animator {
@@ -30,9 +46,11 @@ impl Animator {
             current_time: 0.0,
             playing: false,
             looping: true,
+            model: None,
             node_tree,
             skin,
             joint_matrices: vec![identity_mat4(); joint_count],
+            finished: false,
         };
         animator.recompute_joint_matrices();
         animator
@@ -56,6 +74,7 @@ impl Animator {
 
     /// Advance the animation by `delta` seconds and recompute joint matrices.
     pub fn update(&mut self, delta: f32) {
+        self.finished = false;
         if !self.playing || self.clips.is_empty() {
             return;
         }
@@ -69,6 +88,7 @@ impl Animator {
             self.current_time = self.current_time.min(duration);
             if self.current_time >= duration {
                 self.playing = false;
+                self.finished = true;
             }
         }
 
@@ -113,6 +133,33 @@ impl Animator {
     }
 }
 
+/// Advances every [`Animator`] by the frame's [`DeltaTime`]. Animators are independent of each
+/// other, so they are sampled in parallel.
+pub fn animators_update_system(
+    mut animators: ViewMut<Animator>,
+    delta_time: UniqueView<DeltaTime>,
+) {
+    let dt = delta_time.0;
+    (&mut animators)
+        .par_iter()
+        .for_each(|animator| animator.update(dt));
+}
+
+/// Sends an [`AnimationFinished`] event for every clip that ended in this frame's update.
+pub fn animation_events_system(
+    animators: View<Animator>,
+    mut finished: UniqueViewMut<Events<AnimationFinished>>,
+) {
+    for (entity, animator) in (&animators).iter().with_id() {
+        if animator.finished {
+            finished.send(AnimationFinished {
+                entity,
+                clip: animator.current_clip,
+            });
+        }
+    }
+}
+
 // ── sampling ──────────────────────────────────────────────────────────────────
 
 enum NodeUpdate {
@@ -1,7 +1,7 @@
 use nalgebra_glm::{TVec3, vec3};
 use shipyard::{Component, Unique, track};
 
-#[derive(Component, Unique)]
+#[derive(Component, Unique, Clone, Debug)]
 pub struct DirectionalLight {
     pub position: [f32; 4],
     pub color: [f32; 3],
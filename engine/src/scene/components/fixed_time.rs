@@ -0,0 +1,18 @@
+use shipyard::{Component, Unique};
+
+/// Timing of the fixed physics step.
+#[derive(Component, Unique, Clone, Copy, Debug)]
+pub struct FixedTime {
+    /// Length of one fixed step in seconds. Systems in the `FixedUpdate` stage should use this
+    /// instead of `DeltaTime`.
+    pub step: f32,
+    /// How far the current frame is between the last fixed step and the next one, in `0..1`.
+    /// Used to interpolate rendered transforms.
+    pub alpha: f32,
+}
+
+impl FixedTime {
+    pub fn new(step: f32) -> Self {
+        Self { step, alpha: 0.0 }
+    }
+}
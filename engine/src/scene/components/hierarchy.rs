@@ -0,0 +1,318 @@
+//! Parent/child relationships between entities. An entity with a [`Parent`] has its
+//! [`Transform`] expressed relative to the parent, and [`transform_propagation_system`]
+//! combines the chain into a world-space [`GlobalTransform`] every frame.
+//!
+//! `Parent` is the source of truth: adding it directly (for example in `add_entity`) is
+//! enough, and the propagation system keeps every parent's [`Children`] list in step. Use
+//! [`set_parent`] and [`remove_parent`] to reparent at runtime while keeping the entity where
+//! it is in the world.
+
+use std::collections::HashMap;
+
+use nalgebra::UnitQuaternion;
+use nalgebra_glm::{TMat4, identity, inverse, inverse_transpose, vec4};
+use shipyard::{
+    AllStoragesViewMut, Component, EntitiesView, EntitiesViewMut, EntityId, Get, IntoIter, View,
+    ViewMut, World,
+};
+
+use crate::scene::components::transform::Transform;
+use crate::warn;
+
+/// Deeper chains are assumed to be a cycle made by inserting `Parent` by hand.
+const MAX_DEPTH: usize = 256;
+
+/// The entity this one's `Transform` is relative to.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Parent(pub EntityId);
+
+/// The entities whose [`Parent`] is this one. Maintained by the engine; read it, don't edit it.
+#[derive(Component, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Children(pub Vec<EntityId>);
+
+/// World-space transform of an entity, computed from its own and its ancestors' `Transform`s
+/// by [`transform_propagation_system`] in `PreRender`. Systems in earlier stages see the value
+/// from the previous frame; use [`world_matrix`] when the current one is needed.
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub struct GlobalTransform {
+    pub matrix: TMat4<f32>,
+}
+
+impl GlobalTransform {
+    pub fn model_matrix(&self) -> TMat4<f32> {
+        self.matrix
+    }
+
+    pub fn normal_matrix(&self) -> TMat4<f32> {
+        inverse_transpose(self.matrix)
+    }
+
+    pub fn get_position_vector(&self) -> [f32; 3] {
+        [
+            self.matrix[(0, 3)],
+            self.matrix[(1, 3)],
+            self.matrix[(2, 3)],
+        ]
+    }
+}
+
+impl Default for GlobalTransform {
+    fn default() -> Self {
+        GlobalTransform { matrix: identity() }
+    }
+}
+
+impl From<&Transform> for GlobalTransform {
+    fn from(transform: &Transform) -> Self {
+        GlobalTransform {
+            matrix: transform.model_matrix(),
+        }
+    }
+}
+
+/// The world-space matrix of `entity`, walking up its parents. `None` if it has no
+/// `Transform`. A parent without a `Transform` ends the chain, as does a dead one.
+pub fn world_matrix<'a>(
+    entity: EntityId,
+    transforms: impl Get<Out = &'a Transform> + Copy,
+    parents: impl Get<Out = &'a Parent> + Copy,
+) -> Option<TMat4<f32>> {
+    let mut matrix = transforms.get(entity).ok()?.model_matrix();
+    let mut current = entity;
+    for _ in 0..MAX_DEPTH {
+        let Ok(parent) = parents.get(current) else {
+            break;
+        };
+        let Ok(parent_transform) = transforms.get(parent.0) else {
+            break;
+        };
+        matrix = parent_transform.model_matrix() * matrix;
+        current = parent.0;
+    }
+    Some(matrix)
+}
+
+fn parent_matrix<'a>(
+    entity: EntityId,
+    transforms: impl Get<Out = &'a Transform> + Copy,
+    parents: impl Get<Out = &'a Parent> + Copy,
+) -> Option<TMat4<f32>> {
+    let parent = parents.get(entity).ok()?;
+    world_matrix(parent.0, transforms, parents)
+}
+
+/// Converts a world-space position into the space `entity`'s `Transform` is in, its parent's.
+/// Positions of entities without a parent are returned unchanged.
+pub fn local_position<'a>(
+    entity: EntityId,
+    world_position: [f32; 3],
+    transforms: impl Get<Out = &'a Transform> + Copy,
+    parents: impl Get<Out = &'a Parent> + Copy,
+) -> [f32; 3] {
+    let Some(parent_matrix) = parent_matrix(entity, transforms, parents) else {
+        return world_position;
+    };
+    let [x, y, z] = world_position;
+    let local = inverse(&parent_matrix) * vec4(x, y, z, 1.0);
+    [local.x, local.y, local.z]
+}
+
+/// Converts a world-space rotation into `entity`'s parent's space, like [`local_position`].
+pub fn local_rotation<'a>(
+    entity: EntityId,
+    world_rotation: UnitQuaternion<f32>,
+    transforms: impl Get<Out = &'a Transform> + Copy,
+    parents: impl Get<Out = &'a Parent> + Copy,
+) -> UnitQuaternion<f32> {
+    match parent_matrix(entity, transforms, parents) {
+        Some(matrix) => Transform::from_matrix(&matrix).rotation.inverse() * world_rotation,
+        None => world_rotation,
+    }
+}
+
+/// Whether `ancestor` is `entity` or one of its parents.
+fn is_ancestor<'a>(
+    ancestor: EntityId,
+    entity: EntityId,
+    parents: impl Get<Out = &'a Parent> + Copy,
+) -> bool {
+    let mut current = entity;
+    for _ in 0..MAX_DEPTH {
+        if current == ancestor {
+            return true;
+        }
+        match parents.get(current) {
+            Ok(parent) => current = parent.0,
+            Err(_) => return false,
+        }
+    }
+    true
+}
+
+/// Computes every entity's [`GlobalTransform`], adding it where missing, and rebuilds the
+/// [`Children`] lists from the [`Parent`] components.
+pub fn transform_propagation_system(
+    entities: EntitiesViewMut,
+    transforms: View<Transform>,
+    parents: View<Parent>,
+    mut children: ViewMut<Children>,
+    mut globals: ViewMut<GlobalTransform>,
+) {
+    let mut child_map: HashMap<EntityId, Vec<EntityId>> = HashMap::new();
+    for (child, parent) in parents.iter().with_id() {
+        if entities.is_alive(parent.0) {
+            child_map.entry(parent.0).or_default().push(child);
+        }
+    }
+
+    let stale: Vec<EntityId> = children
+        .iter()
+        .with_id()
+        .filter(|(id, _)| !child_map.contains_key(id))
+        .map(|(id, _)| id)
+        .collect();
+    for id in stale {
+        children.remove(id);
+    }
+    for (&parent, list) in &child_map {
+        match (&mut children).get(parent) {
+            Ok(mut current) => {
+                if current.0 != *list {
+                    current.0.clone_from(list);
+                }
+            }
+            Err(_) => entities.add_component(parent, &mut children, Children(list.clone())),
+        }
+    }
+
+    let roots: Vec<EntityId> = transforms
+        .iter()
+        .with_id()
+        .filter(|(id, _)| match parents.get(*id) {
+            Ok(parent) => !entities.is_alive(parent.0) || !transforms.contains(parent.0),
+            Err(_) => true,
+        })
+        .map(|(id, _)| id)
+        .collect();
+
+    let mut stack: Vec<(EntityId, TMat4<f32>)> =
+        roots.into_iter().map(|id| (id, identity())).collect();
+    while let Some((id, parent_matrix)) = stack.pop() {
+        let Ok(transform) = transforms.get(id) else {
+            continue;
+        };
+        let matrix = parent_matrix * transform.model_matrix();
+        match (&mut globals).get(id) {
+            Ok(mut global) => global.matrix = matrix,
+            Err(_) => entities.add_component(id, &mut globals, GlobalTransform { matrix }),
+        }
+        if let Some(list) = child_map.get(&id) {
+            stack.extend(list.iter().map(|&child| (child, matrix)));
+        }
+    }
+}
+
+/// Makes `parent` the parent of `child`. With `keep_world_transform` the child's `Transform`
+/// is recomputed so it stays where it is in the world; otherwise it's reinterpreted relative
+/// to the new parent. Refused, with a warning, if `parent` is `child` or one of its
+/// descendants.
+pub fn set_parent(world: &World, child: EntityId, parent: EntityId, keep_world_transform: bool) {
+    world.run(
+        |entities: EntitiesViewMut,
+         mut parents: ViewMut<Parent>,
+         mut children: ViewMut<Children>,
+         mut transforms: ViewMut<Transform>| {
+            if is_ancestor(child, parent, &parents) {
+                warn!("Can't make {parent:?} the parent of {child:?}: it's its descendant");
+                return;
+            }
+
+            if keep_world_transform
+                && let Some(child_matrix) = world_matrix(child, &transforms, &parents)
+            {
+                let parent_matrix =
+                    world_matrix(parent, &transforms, &parents).unwrap_or_else(identity);
+                let local = inverse(&parent_matrix) * child_matrix;
+                if let Ok(mut transform) = (&mut transforms).get(child) {
+                    *transform = Transform::from_matrix(&local);
+                }
+            }
+
+            detach(child, &parents, &mut children);
+            entities.add_component(child, &mut parents, Parent(parent));
+            match (&mut children).get(parent) {
+                Ok(mut list) => list.0.push(child),
+                Err(_) => entities.add_component(parent, &mut children, Children(vec![child])),
+            }
+        },
+    );
+}
+
+/// Makes `child` a root again. With `keep_world_transform` its `Transform` becomes its current
+/// world transform, otherwise it keeps its local values.
+pub fn remove_parent(world: &World, child: EntityId, keep_world_transform: bool) {
+    world.run(
+        |mut parents: ViewMut<Parent>,
+         mut children: ViewMut<Children>,
+         mut transforms: ViewMut<Transform>| {
+            if keep_world_transform
+                && let Some(child_matrix) = world_matrix(child, &transforms, &parents)
+                && let Ok(mut transform) = (&mut transforms).get(child)
+            {
+                *transform = Transform::from_matrix(&child_matrix);
+            }
+
+            detach(child, &parents, &mut children);
+            parents.remove(child);
+        },
+    );
+}
+
+/// Removes `child` from its current parent's `Children`.
+fn detach(child: EntityId, parents: &ViewMut<Parent>, children: &mut ViewMut<Children>) {
+    if let Ok(parent) = parents.get(child)
+        && let Ok(mut list) = children.get(parent.0)
+    {
+        list.0.retain(|&id| id != child);
+    }
+}
+
+/// Every descendant of `entity`, children before grandchildren, not including `entity`.
+pub fn descendants(world: &World, entity: EntityId) -> Vec<EntityId> {
+    world.run(|entities: EntitiesView, parents: View<Parent>| {
+        let mut found = vec![entity];
+        let mut index = 0;
+        while index < found.len() {
+            let current = found[index];
+            let next: Vec<EntityId> = parents
+                .iter()
+                .with_id()
+                .filter(|(id, parent)| {
+                    parent.0 == current && entities.is_alive(*id) && !found.contains(id)
+                })
+                .map(|(id, _)| id)
+                .collect();
+            found.extend(next);
+            index += 1;
+        }
+        found.remove(0);
+        found
+    })
+}
+
+/// Deletes `entity` together with all its descendants.
+pub fn delete_recursive(world: &mut World, entity: EntityId) {
+    let descendants = descendants(world, entity);
+    world.run(
+        |mut parents: ViewMut<Parent>, mut children: ViewMut<Children>| {
+            detach(entity, &parents, &mut children);
+            parents.remove(entity);
+        },
+    );
+    world.run(|mut all_storages: AllStoragesViewMut| {
+        for id in descendants.into_iter().rev() {
+            all_storages.delete_entity(id);
+        }
+        all_storages.delete_entity(entity);
+    });
+}
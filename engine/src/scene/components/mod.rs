@@ -2,6 +2,9 @@ pub mod animator;
 pub mod camera;
 pub mod delta_time;
 pub mod directional_light;
+pub mod fixed_time;
+pub mod hierarchy;
+pub mod name;
 pub mod object3d;
 pub mod pointlight;
 pub mod transform;
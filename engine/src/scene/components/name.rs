@@ -0,0 +1,22 @@
+use shipyard::{Component, EntityId, Get, View, World};
+
+use crate::scene::components::hierarchy;
+
+/// A human-readable name, e.g. the node name of an entity spawned from a glTF file.
+#[derive(Component, Clone, Debug, PartialEq, Eq)]
+pub struct Name(pub String);
+
+impl Name {
+    pub fn new(name: impl Into<String>) -> Self {
+        Name(name.into())
+    }
+}
+
+/// The first descendant of `root` called `name`, children before grandchildren.
+pub fn find_by_name(world: &World, root: EntityId, name: &str) -> Option<EntityId> {
+    let descendants = hierarchy::descendants(world, root);
+    let names = world.borrow::<View<Name>>().ok()?;
+    descendants
+        .into_iter()
+        .find(|&entity| names.get(entity).is_ok_and(|n| n.0 == name))
+}
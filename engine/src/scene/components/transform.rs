@@ -1,49 +1,84 @@
-use nalgebra_glm::inverse_transpose;
-use nalgebra_glm::scale;
-use nalgebra_glm::{TMat4, TVec2, TVec3, Vec2, identity, rotate_normalized_axis, translate, vec3};
+use nalgebra::{Rotation3, Unit, UnitQuaternion};
+use nalgebra_glm::{TMat3, TMat4, TVec3, inverse_transpose, scaling, translation, vec3};
 use shipyard::{Component, track};
 
-#[derive(Component, Debug, Clone)]
+/// Position, orientation and scale of an entity, relative to its parent if it has one.
+///
+/// The local axes follow the right-handed convention: +X is right, +Y is up and the entity
+/// looks down -Z.
+#[derive(Component, Debug, Clone, PartialEq)]
 pub struct Transform {
-    pub position: TMat4<f32>,
-    pub rotation: TMat4<f32>,
-    pub uniform_scale: f32,
+    pub translation: TVec3<f32>,
+    pub rotation: UnitQuaternion<f32>,
+    pub scale: TVec3<f32>,
 }
 
 impl Transform {
     pub fn new() -> Transform {
         Transform {
-            position: identity(),
-            rotation: identity(),
-            uniform_scale: 1.0,
+            translation: vec3(0.0, 0.0, 0.0),
+            rotation: UnitQuaternion::identity(),
+            scale: vec3(1.0, 1.0, 1.0),
+        }
+    }
+
+    pub fn from_trs(
+        translation: TVec3<f32>,
+        rotation: UnitQuaternion<f32>,
+        scale: TVec3<f32>,
+    ) -> Self {
+        Transform {
+            translation,
+            rotation,
+            scale,
         }
     }
 
     pub fn with_pos(v: TVec3<f32>) -> Self {
-        let zero_position: TMat4<f32> = identity();
         Transform {
-            position: translate(&zero_position, &v),
-            rotation: identity(),
-            uniform_scale: 1.0,
+            translation: v,
+            ..Transform::new()
         }
     }
 
     pub fn with_pos_scale(v: TVec3<f32>, s: f32) -> Self {
-        let zero_position: TMat4<f32> = identity();
         Transform {
-            position: translate(&zero_position, &v),
-            rotation: identity(),
-            uniform_scale: s,
+            translation: v,
+            scale: vec3(s, s, s),
+            ..Transform::new()
+        }
+    }
+
+    /// Splits a model matrix back into translation, rotation and scale. The matrix must not
+    /// have shear; a mirroring matrix gets a negative X scale.
+    pub fn from_matrix(matrix: &TMat4<f32>) -> Self {
+        let columns = [
+            matrix.column(0).xyz(),
+            matrix.column(1).xyz(),
+            matrix.column(2).xyz(),
+        ];
+        let mut scale = vec3(columns[0].norm(), columns[1].norm(), columns[2].norm());
+        let mut basis = TMat3::from_columns(&[
+            columns[0] / scale.x,
+            columns[1] / scale.y,
+            columns[2] / scale.z,
+        ]);
+        if basis.determinant() < 0.0 {
+            scale.x = -scale.x;
+            let mirrored = -basis.column(0);
+            basis.set_column(0, &mirrored);
+        }
+        Transform {
+            translation: matrix.column(3).xyz(),
+            rotation: UnitQuaternion::from_rotation_matrix(&Rotation3::from_matrix_unchecked(
+                basis,
+            )),
+            scale,
         }
     }
 
     pub fn model_matrix(&self) -> TMat4<f32> {
-        let mut model = self.position * self.rotation;
-        model = scale(
-            &model,
-            &vec3(self.uniform_scale, self.uniform_scale, self.uniform_scale),
-        );
-        return model;
+        translation(&self.translation) * self.rotation.to_homogeneous() * scaling(&self.scale)
     }
 
     pub fn normal_matrix(&self) -> TMat4<f32> {
@@ -51,37 +86,60 @@ impl Transform {
     }
 
     pub fn translate(&mut self, v: TVec3<f32>) {
-        self.position = translate(&self.position, &v);
+        self.translation += v;
     }
 
     pub fn set_position(&mut self, x: f32, y: f32, z: f32) {
-        self.position[(0, 3)] = x;
-        self.position[(1, 3)] = y;
-        self.position[(2, 3)] = z;
+        self.translation = vec3(x, y, z);
     }
 
+    /// Rotates around `v`, an axis in the entity's own space.
     pub fn rotate(&mut self, radians: f32, v: TVec3<f32>) {
-        self.rotation = rotate_normalized_axis(&self.rotation, radians, &v);
+        self.rotation *= UnitQuaternion::from_axis_angle(&Unit::new_normalize(v), radians);
     }
 
-    pub fn get_position_vector(&self) -> [f32; 3] {
-        let x = self.position[(0, 3)];
-        let y = self.position[(1, 3)];
-        let z = self.position[(2, 3)];
-        [x, y, z]
+    /// Turns the entity so it looks at `target`, with its up axis as close to `up` as possible.
+    /// Does nothing when `target` is its own position.
+    pub fn look_at(&mut self, target: TVec3<f32>, up: TVec3<f32>) {
+        let direction = target - self.translation;
+        if direction.norm_squared() > f32::EPSILON {
+            self.rotation = UnitQuaternion::face_towards(&-direction, &up);
+        }
+    }
+
+    /// Rotations around X, Y and Z in radians, applied in the order X, Y, Z.
+    pub fn get_euler_angles(&self) -> TVec3<f32> {
+        let (x, y, z) = self.rotation.euler_angles();
+        vec3(x, y, z)
     }
 
-    pub fn get_rotation_vector(&self) -> [f32; 3] {
-        let x = self.rotation[(0, 3)];
-        let y = self.rotation[(1, 3)];
-        let z = self.rotation[(2, 3)];
-        [x, y, z]
+    pub fn set_euler_angles(&mut self, x: f32, y: f32, z: f32) {
+        self.rotation = UnitQuaternion::from_euler_angles(x, y, z);
+    }
+
+    pub fn get_forward_vector(&self) -> TVec3<f32> {
+        self.rotation * vec3(0.0, 0.0, -1.0)
+    }
+
+    pub fn get_right_vector(&self) -> TVec3<f32> {
+        self.rotation * vec3(1.0, 0.0, 0.0)
+    }
+
+    pub fn get_up_vector(&self) -> TVec3<f32> {
+        self.rotation * vec3(0.0, 1.0, 0.0)
+    }
+
+    pub fn get_position_vector(&self) -> [f32; 3] {
+        self.translation.into()
     }
 
     pub fn get_scale_vector(&self) -> [f32; 3] {
-        let x = self.position[(0, 0)];
-        let y = self.position[(1, 1)];
-        let z = self.position[(2, 2)];
-        [x, y, z]
+        self.scale.into()
+    }
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Transform::new()
     }
 }
@@ -0,0 +1,78 @@
+//! Spawning a glTF file as a tree of entities, one per node, instead of one [`Object3D`] with
+//! every node transform baked into the vertices.
+//!
+//! Each node gets its own [`Transform`] and, if named, a [`Name`]; mesh nodes also get an
+//! `Object3D` with that mesh's model. The nodes hang under a root entity named after the file,
+//! so the whole scene can be moved by moving the root and removed with
+//! [`delete_recursive`](crate::scene::components::hierarchy::delete_recursive).
+//!
+//! Skinned meshes are spawned in their bind pose without an [`Animator`]. Animated characters
+//! are simpler to load baked with [`AssetManager::load_model`] and
+//! [`AssetManager::create_animator`].
+//!
+//! [`Animator`]: crate::scene::components::animator::Animator
+
+use std::path::Path;
+
+use nalgebra::{Quaternion, UnitQuaternion};
+use nalgebra_glm::vec3;
+use shipyard::{EntityId, World};
+
+use crate::assets::animation::NodeData;
+use crate::assets::asset_manager::AssetManager;
+use crate::core::error::AssetError;
+use crate::scene::components::hierarchy::Parent;
+use crate::scene::components::name::Name;
+use crate::scene::components::object3d::Object3D;
+use crate::scene::components::transform::Transform;
+
+/// Spawns the nodes of the glTF file at `path` under a new root entity, which is returned.
+/// The file is loaded through the world's [`AssetManager`] with
+/// [`load_scene`](AssetManager::load_scene), so spawning it again reuses the meshes.
+///
+/// Panics if the world has no `AssetManager`.
+pub fn spawn_gltf_scene(world: &mut World, path: &str) -> Result<EntityId, AssetError> {
+    let scene = world
+        .get_unique::<&mut AssetManager>()
+        .expect("the world has no AssetManager")
+        .load_scene(path)?;
+
+    let file_name = Path::new(path)
+        .file_stem()
+        .map_or_else(|| path.to_string(), |s| s.to_string_lossy().into_owned());
+    let root = world.add_entity((Transform::new(), Name(file_name)));
+
+    let entities: Vec<EntityId> = scene
+        .node_tree
+        .nodes
+        .iter()
+        .map(|node| {
+            let entity = world.add_entity(node_transform(node));
+            if let Some(name) = &node.name {
+                world.add_component(entity, Name(name.clone()));
+            }
+            if let Some(handle) = node.mesh.and_then(|mesh| scene.meshes.get(mesh)) {
+                world.add_component(entity, Object3D::with_model(handle.clone()));
+            }
+            entity
+        })
+        .collect();
+
+    for (node, &entity) in scene.node_tree.nodes.iter().zip(&entities) {
+        let parent = node.parent.map_or(root, |parent| entities[parent]);
+        world.add_component(entity, Parent(parent));
+    }
+
+    Ok(root)
+}
+
+/// The node's transform in render space, whose Y axis points the other way than glTF's.
+fn node_transform(node: &NodeData) -> Transform {
+    let [tx, ty, tz] = node.translation;
+    let [x, y, z, w] = node.rotation;
+    Transform::from_trs(
+        vec3(tx, -ty, tz),
+        UnitQuaternion::from_quaternion(Quaternion::new(w, -x, y, -z)),
+        node.scale.into(),
+    )
+}
@@ -1,3 +1,46 @@
 pub mod components;
+pub mod gltf_scene;
+pub mod prefab;
 pub mod scene;
+pub mod scene_file;
 pub mod systems;
+
+use shipyard::World;
+
+use crate::core::config::EngineConfig;
+use crate::core::events;
+use crate::core::plugin::Plugin;
+use crate::core::schedule::{Schedule, Stage};
+
+/// Advances skeletal [`Animator`](components::animator::Animator)s in `PostUpdate` and sends
+/// [`AnimationFinished`](components::animator::AnimationFinished) events.
+pub struct AnimationPlugin;
+
+impl Plugin for AnimationPlugin {
+    fn build(&mut self, world: &mut World, schedule: &mut Schedule, _config: &EngineConfig) {
+        events::add_event::<components::animator::AnimationFinished>(world);
+        schedule
+            .add_system(
+                Stage::PostUpdate,
+                components::animator::animators_update_system,
+            )
+            .add_system(
+                Stage::PostUpdate,
+                components::animator::animation_events_system,
+            );
+    }
+}
+
+/// Computes every entity's [`GlobalTransform`](components::hierarchy::GlobalTransform) from
+/// the [`Parent`](components::hierarchy::Parent) chain in `PreRender`, after the physics
+/// positions are interpolated and before the draw list is built.
+pub struct TransformPlugin;
+
+impl Plugin for TransformPlugin {
+    fn build(&mut self, _world: &mut World, schedule: &mut Schedule, _config: &EngineConfig) {
+        schedule.add_system(
+            Stage::PreRender,
+            components::hierarchy::transform_propagation_system,
+        );
+    }
+}
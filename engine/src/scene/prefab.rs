@@ -0,0 +1,285 @@
+//! Prefabs: templates of an entity and its children that are spawned many times, e.g. every
+//! soldier of a level, instead of repeating the same `add_entity` call.
+//!
+//! A prefab is written like a [scene file](crate::scene::scene_file) whose first entity is the
+//! root, or built in code with [`PrefabEntity`]. Its components are loaded through the world's
+//! [`SceneRegistry`](scene_file::SceneRegistry) for every instance, so each gets its own components and the assets are
+//! shared through the `AssetManager`. Components that aren't in the registry can be added from
+//! code with [`PrefabEntity::with`].
+//!
+//! ```ignore
+//! let soldier = Prefab::new(
+//!     PrefabEntity::new()
+//!         .add("soldier")
+//!         .set("object3d.model", "data/models/SoldierRe.glb")
+//!         .set("animator.model", "data/models/SoldierRe.glb")
+//!         .child(PrefabEntity::new().set("pointlight.radius", 2.0)),
+//! );
+//! world.get_unique::<&mut PrefabLibrary>().unwrap().insert("soldier", soldier);
+//!
+//! let overrides = PrefabOverrides::new().position(vec3(-9.0, 0.0, 0.0));
+//! let root = prefab::instantiate(&mut world, "soldier", overrides)?;
+//! ```
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::path::Path;
+use std::sync::Arc;
+
+use nalgebra_glm::TVec3;
+use shipyard::{Component, EntityId, Unique, World};
+
+use crate::scene::scene_file::{self, ParsedEntity, SceneError};
+
+/// Adds a component made in code to an instance.
+type InsertFn = Arc<dyn Fn(&mut World, EntityId) + Send + Sync>;
+
+/// One entity of a prefab built in code, together with its children.
+#[derive(Clone, Default)]
+pub struct PrefabEntity {
+    entity: ParsedEntity,
+    inserts: Vec<InsertFn>,
+    children: Vec<PrefabEntity>,
+}
+
+impl PrefabEntity {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a registered component with its default fields, like a scene file line with just
+    /// the component's name.
+    pub fn add(mut self, component: &str) -> Self {
+        self.entity.component(component, 0);
+        self
+    }
+
+    /// Sets a field of a registered component, `key` being `component.field` as in a scene
+    /// file. Fields left out keep their defaults.
+    pub fn set(mut self, key: &str, value: impl Display) -> Self {
+        set_field(&mut self.entity, key, value);
+        self
+    }
+
+    /// Adds a component made by `make` for each instance, for components that can't be written
+    /// in a scene file.
+    pub fn with<T: Component + Send + Sync>(
+        mut self,
+        make: impl Fn() -> T + Send + Sync + 'static,
+    ) -> Self {
+        self.inserts
+            .push(Arc::new(move |world: &mut World, entity| {
+                world.add_component(entity, make())
+            }));
+        self
+    }
+
+    pub fn child(mut self, child: PrefabEntity) -> Self {
+        self.children.push(child);
+        self
+    }
+}
+
+/// Replaces `component.field` of `entity`, adding the component if it's missing.
+fn set_field(entity: &mut ParsedEntity, key: &str, value: impl Display) {
+    let (component, field) = key.split_once('.').unwrap_or((key, ""));
+    let fields = entity.component(component, 0);
+    fields.take_str(field);
+    fields.set(field, value);
+}
+
+/// Changes to the root entity of a single instance.
+#[derive(Default)]
+pub struct PrefabOverrides {
+    fields: Vec<(String, String)>,
+    inserts: Vec<Box<dyn FnOnce(&mut World, EntityId)>>,
+}
+
+impl PrefabOverrides {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Puts the instance at `position`, keeping the prefab's rotation and scale.
+    pub fn position(self, position: TVec3<f32>) -> Self {
+        self.set(
+            "transform.position",
+            format!("{}, {}, {}", position.x, position.y, position.z),
+        )
+    }
+
+    /// Sets `component.field` of a registered component, like [`PrefabEntity::set`].
+    pub fn set(mut self, key: &str, value: impl Display) -> Self {
+        self.fields.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// Adds `component` to the root, replacing the prefab's component of the same type.
+    pub fn with<T: Component + Send + Sync>(mut self, component: T) -> Self {
+        self.inserts
+            .push(Box::new(move |world: &mut World, entity| {
+                world.add_component(entity, component)
+            }));
+        self
+    }
+}
+
+/// A template of an entity and its descendants. See the [module docs](self).
+#[derive(Clone)]
+pub struct Prefab {
+    /// The root first, each child after its parent.
+    entities: Vec<ParsedEntity>,
+    inserts: Vec<Vec<InsertFn>>,
+}
+
+impl Prefab {
+    /// A prefab of `root` and its children.
+    pub fn new(root: PrefabEntity) -> Self {
+        let mut prefab = Prefab {
+            entities: Vec::new(),
+            inserts: Vec::new(),
+        };
+        prefab.push(root, None);
+        prefab
+    }
+
+    fn push(&mut self, entity: PrefabEntity, parent: Option<usize>) {
+        let index = self.entities.len();
+        let mut parsed = entity.entity;
+        parsed.parent = parent.map(|parent| (0, parent));
+        self.entities.push(parsed);
+        self.inserts.push(entity.inserts);
+        for child in entity.children {
+            self.push(child, Some(index));
+        }
+    }
+
+    /// Reads a prefab written like a scene file. The first entity is the root, which the
+    /// others must descend from through `parent = N` lines, so an instance is deleted with its
+    /// root. Components are only checked when the prefab is instantiated.
+    pub fn parse(text: &str) -> Result<Self, SceneError> {
+        let entities = scene_file::parse_entities(text)?;
+        if entities.is_empty() {
+            return Err(SceneError::Parse {
+                line: 1,
+                message: "a prefab needs at least one `[entity]`".to_string(),
+            });
+        }
+        if let Some((line, _)) = entities[0].parent {
+            return Err(SceneError::Parse {
+                line,
+                message: "the prefab's root can't have a parent".to_string(),
+            });
+        }
+        for (position, entity) in entities.iter().enumerate() {
+            if !descends_from_root(&entities, position) {
+                return Err(SceneError::Parse {
+                    line: entity.line,
+                    message: format!("entity {position} doesn't descend from the root, entity 0"),
+                });
+            }
+        }
+        Ok(Prefab {
+            inserts: vec![Vec::new(); entities.len()],
+            entities,
+        })
+    }
+
+    /// Reads a prefab file, see [`Prefab::parse`].
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, SceneError> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).map_err(|source| SceneError::Io {
+            path: path.display().to_string(),
+            source,
+        })?;
+        Self::parse(&text)
+    }
+
+    /// Adds an instance to `world` and returns its root. Nothing is added if a component fails
+    /// to load.
+    pub fn instantiate(
+        &self,
+        world: &mut World,
+        overrides: PrefabOverrides,
+    ) -> Result<EntityId, SceneError> {
+        let mut entities = self.entities.clone();
+        for (key, value) in &overrides.fields {
+            set_field(&mut entities[0], key, value);
+        }
+
+        let added =
+            scene_file::with_registry(world, |registry, world| registry.spawn(world, entities))?;
+        for (inserts, &entity) in self.inserts.iter().zip(&added) {
+            for insert in inserts {
+                insert(world, entity);
+            }
+        }
+        let root = added[0];
+        for insert in overrides.inserts {
+            insert(world, root);
+        }
+        Ok(root)
+    }
+}
+
+/// Whether following `parent = N` lines from the entity at `position` reaches the first one.
+fn descends_from_root(entities: &[ParsedEntity], mut position: usize) -> bool {
+    // A chain longer than the entities has to loop
+    for _ in 0..entities.len() {
+        if position == 0 {
+            return true;
+        }
+        match entities.get(position).and_then(|entity| entity.parent) {
+            Some((_, parent)) => position = parent,
+            None => return false,
+        }
+    }
+    false
+}
+
+/// The game's prefabs by name. The engine adds an empty one to the world.
+#[derive(Unique, Default)]
+pub struct PrefabLibrary {
+    prefabs: HashMap<String, Arc<Prefab>>,
+}
+
+impl PrefabLibrary {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a prefab, replacing one with the same name.
+    pub fn insert(&mut self, name: &str, prefab: Prefab) -> &mut Self {
+        self.prefabs.insert(name.to_string(), Arc::new(prefab));
+        self
+    }
+
+    /// Reads a prefab file and adds it under `name`.
+    pub fn load(&mut self, name: &str, path: impl AsRef<Path>) -> Result<&mut Self, SceneError> {
+        let prefab = Prefab::load(path)?;
+        Ok(self.insert(name, prefab))
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Prefab> {
+        self.prefabs.get(name).map(Arc::as_ref)
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> + '_ {
+        self.prefabs.keys().map(String::as_str)
+    }
+}
+
+/// Adds an instance of the prefab called `name` in the world's [`PrefabLibrary`] and returns
+/// its root.
+pub fn instantiate(
+    world: &mut World,
+    name: &str,
+    overrides: PrefabOverrides,
+) -> Result<EntityId, SceneError> {
+    let prefab = world
+        .get_unique::<&PrefabLibrary>()
+        .ok()
+        .and_then(|library| library.prefabs.get(name).cloned())
+        .ok_or_else(|| SceneError::UnknownPrefab(name.to_string()))?;
+    prefab.instantiate(world, overrides)
+}
@@ -0,0 +1,839 @@
+//! Saving entities to and loading them from text scene files, so levels can be edited without
+//! recompiling.
+//!
+//! A scene file has one `[entity]` section per entity, listing its components as
+//! `component.field = value` lines, or just the name for a component without fields. `#`
+//! starts a comment, and fields left out take their default values:
+//!
+//! ```text
+//! [entity]
+//! transform.position = 0, -1.5, 4
+//! object3d.model = data/models/Soldier.glb
+//! rigid_body.type = dynamic
+//! collider.shape = ball 0.45
+//! no_interpolation
+//! ```
+//!
+//! `parent = N` makes the entity a child of the file's `N`th entity, counting from 0, so its
+//! transform is relative to that one's.
+//!
+//! A value can be written in double quotes, with `\"`, `\\` and `\n` escapes. Saving quotes
+//! values that contain `#`, quotes or line breaks, or start or end with spaces, so they load
+//! back unchanged.
+//!
+//! Only components in the world's [`SceneRegistry`] are saved and loaded. The engine's are
+//! registered out of the box; a game adds its own by implementing [`SceneComponent`] and calling
+//! [`SceneRegistry::register`]. Physics handles aren't saved: bodies are created again from the
+//! loaded components. Animators are saved as the model they were created for and the clip.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{self, Display};
+use std::path::Path;
+use std::str::FromStr;
+
+use nalgebra::{Quaternion, UnitQuaternion};
+use nalgebra_glm::vec3;
+use rapier3d::prelude::{RigidBodyType, SharedShape};
+use shipyard::{Component, EntityId, IntoIter, Unique, View, World};
+
+use crate::assets::asset_manager::AssetManager;
+use crate::core::config;
+use crate::physics::interpolation::NoInterpolation;
+use crate::physics::physics_engine::{
+    ColliderComponent, KinematicCharacterComponent, RigidBodyComponent,
+};
+use crate::scene::components::animator::Animator;
+use crate::scene::components::camera::Camera;
+use crate::scene::components::hierarchy::{self, Parent};
+use crate::scene::components::name::Name;
+use crate::scene::components::object3d::Object3D;
+use crate::scene::components::pointlight::Pointlight;
+use crate::scene::components::transform::Transform;
+use crate::warn;
+
+/// A component that can be written to and read from a scene file.
+///
+/// ```ignore
+/// impl SceneComponent for Soldier {
+///     const NAME: &'static str = "soldier";
+///
+///     fn save(&self, fields: &mut SceneFields) -> Result<(), String> {
+///         fields.set("direction", self.direction);
+///         Ok(())
+///     }
+///
+///     fn load(fields: &mut SceneFields, _world: &World) -> Result<Self, String> {
+///         Ok(Soldier {
+///             direction: fields.take("direction")?.unwrap_or(1.0),
+///             ..Soldier::new()
+///         })
+///     }
+/// }
+/// ```
+pub trait SceneComponent: Component + Send + Sync {
+    /// The name the component is written under, e.g. `transform`.
+    const NAME: &'static str;
+
+    /// Writes the component's fields. An error leaves the component out of the saved scene.
+    fn save(&self, fields: &mut SceneFields) -> Result<(), String>;
+
+    /// Builds the component from the fields read for it. `world` is the world being loaded
+    /// into, e.g. to load assets through its [`AssetManager`].
+    fn load(fields: &mut SceneFields, world: &World) -> Result<Self, String>;
+}
+
+/// The fields of one component in a scene file.
+#[derive(Clone, Debug, Default)]
+pub struct SceneFields {
+    values: Vec<(String, String)>,
+}
+
+impl SceneFields {
+    pub fn set(&mut self, field: &str, value: impl Display) {
+        self.values.push((field.to_string(), value.to_string()));
+    }
+
+    /// Writes numbers as a comma separated list, e.g. a position.
+    pub fn set_array(&mut self, field: &str, values: &[f32]) {
+        let value = values
+            .iter()
+            .map(f32::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+        self.set(field, value);
+    }
+
+    /// Removes and returns a field's text, `None` if the scene leaves it out.
+    pub fn take_str(&mut self, field: &str) -> Option<String> {
+        let index = self.values.iter().position(|(name, _)| name == field)?;
+        Some(self.values.remove(index).1)
+    }
+
+    pub fn take<T: FromStr>(&mut self, field: &str) -> Result<Option<T>, String> {
+        self.take_str(field)
+            .map(|value| config::parse(field, &value))
+            .transpose()
+    }
+
+    pub fn take_array<const N: usize>(&mut self, field: &str) -> Result<Option<[f32; N]>, String> {
+        self.take_str(field)
+            .map(|value| config::parse_array(field, &value))
+            .transpose()
+    }
+}
+
+/// Saved components by entity index, so the order entities are written in is stable.
+type SavedEntities = BTreeMap<u64, Vec<(&'static str, SceneFields)>>;
+
+/// Adds a loaded component to its entity once the whole scene has loaded.
+type InsertComponent = Box<dyn FnOnce(&mut World, EntityId)>;
+
+struct Registration {
+    name: &'static str,
+    save: fn(&World, &mut SavedEntities),
+    load: fn(&World, &mut SceneFields) -> Result<InsertComponent, String>,
+}
+
+/// The components scene files can contain, in the order they are written.
+#[derive(Unique)]
+pub struct SceneRegistry {
+    components: Vec<Registration>,
+}
+
+impl SceneRegistry {
+    /// A registry without any components.
+    pub fn new() -> Self {
+        Self {
+            components: Vec::new(),
+        }
+    }
+
+    /// A registry with the engine's components: names, transforms, models, animators, lights,
+    /// cameras and physics bodies.
+    pub fn with_engine_components() -> Self {
+        let mut registry = Self::new();
+        registry
+            .register::<Name>()
+            .register::<Transform>()
+            .register::<Object3D>()
+            .register::<Animator>()
+            .register::<Pointlight>()
+            .register::<Camera>()
+            .register::<RigidBodyComponent>()
+            .register::<ColliderComponent>()
+            .register::<KinematicCharacterComponent>()
+            .register::<NoInterpolation>();
+        registry
+    }
+
+    /// Adds a component, replacing one registered under the same name.
+    pub fn register<T: SceneComponent>(&mut self) -> &mut Self {
+        self.components.retain(|c| c.name != T::NAME);
+        self.components.push(Registration {
+            name: T::NAME,
+            save: save_component::<T>,
+            load: load_component::<T>,
+        });
+        self
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.components.iter().map(|c| c.name)
+    }
+
+    /// Writes every entity with at least one registered component.
+    pub fn save(&self, world: &World) -> String {
+        let mut entities = BTreeMap::new();
+        for component in &self.components {
+            (component.save)(world, &mut entities);
+        }
+
+        // Parents are written as the position of their section in the file
+        let positions: HashMap<u64, usize> = entities
+            .keys()
+            .enumerate()
+            .map(|(position, &index)| (index, position))
+            .collect();
+        let parents: HashMap<u64, usize> = world
+            .borrow::<View<Parent>>()
+            .map(|parents| {
+                parents
+                    .iter()
+                    .with_id()
+                    .filter_map(|(child, parent)| {
+                        Some((child.index(), *positions.get(&parent.0.index())?))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let mut text = String::new();
+        for (index, components) in &entities {
+            text.push_str("[entity]\n");
+            if let Some(parent) = parents.get(index) {
+                text.push_str(&format!("parent = {parent}\n"));
+            }
+            for (name, fields) in components {
+                if fields.values.is_empty() {
+                    text.push_str(&format!("{name}\n"));
+                }
+                for (field, value) in &fields.values {
+                    text.push_str(&format!("{name}.{field} = {}\n", quote(value)));
+                }
+            }
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Adds the entities in `text` to `world` and returns them. Nothing is added if any of
+    /// them fails to load.
+    pub fn load(&self, world: &mut World, text: &str) -> Result<Vec<EntityId>, SceneError> {
+        self.spawn(world, parse_entities(text)?)
+    }
+
+    /// Adds already parsed entities, e.g. the ones of a [`Prefab`](crate::scene::prefab::Prefab).
+    pub(crate) fn spawn(
+        &self,
+        world: &mut World,
+        parsed: Vec<ParsedEntity>,
+    ) -> Result<Vec<EntityId>, SceneError> {
+        let count = parsed.len();
+        let mut pending = Vec::new();
+        for (position, entity) in parsed.into_iter().enumerate() {
+            if let Some((line, parent)) = entity.parent
+                && (parent >= count || parent == position)
+            {
+                return Err(SceneError::Parse {
+                    line,
+                    message: format!("`parent = {parent}` isn't another entity in the scene"),
+                });
+            }
+            let mut inserts = Vec::new();
+            for mut component in entity.components {
+                let parse_error = |message| SceneError::Parse {
+                    line: component.line,
+                    message,
+                };
+                let registration = self
+                    .components
+                    .iter()
+                    .find(|c| c.name == component.name)
+                    .ok_or_else(|| {
+                        parse_error(format!("unknown component `{}`", component.name))
+                    })?;
+                let insert = (registration.load)(world, &mut component.fields)
+                    .map_err(|e| parse_error(format!("`{}`: {e}", component.name)))?;
+                if let Some((field, _)) = component.fields.values.first() {
+                    return Err(parse_error(format!(
+                        "unknown field `{}.{field}`",
+                        component.name
+                    )));
+                }
+                inserts.push(insert);
+            }
+            pending.push((entity.parent, inserts));
+        }
+
+        let mut parents = Vec::new();
+        let added: Vec<EntityId> = pending
+            .into_iter()
+            .map(|(parent, inserts)| {
+                let entity = world.add_entity(());
+                for insert in inserts {
+                    insert(world, entity);
+                }
+                if let Some((_, parent)) = parent {
+                    parents.push((entity, parent));
+                }
+                entity
+            })
+            .collect();
+        for (child, parent) in parents {
+            hierarchy::set_parent(world, child, added[parent], false);
+        }
+        Ok(added)
+    }
+}
+
+impl Default for SceneRegistry {
+    fn default() -> Self {
+        Self::with_engine_components()
+    }
+}
+
+fn save_component<T: SceneComponent>(world: &World, entities: &mut SavedEntities) {
+    let Ok(components) = world.borrow::<View<T>>() else {
+        return;
+    };
+    for (entity, component) in components.iter().with_id() {
+        let mut fields = SceneFields::default();
+        match component.save(&mut fields) {
+            Ok(()) => entities
+                .entry(entity.index())
+                .or_default()
+                .push((T::NAME, fields)),
+            Err(e) => warn!("Not saving `{}` of {entity:?}: {e}", T::NAME),
+        }
+    }
+}
+
+fn load_component<T: SceneComponent>(
+    world: &World,
+    fields: &mut SceneFields,
+) -> Result<InsertComponent, String> {
+    let component = T::load(fields, world)?;
+    Ok(Box::new(move |world: &mut World, entity| {
+        world.add_component(entity, component);
+    }))
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct ParsedComponent {
+    pub(crate) name: String,
+    pub(crate) line: usize,
+    pub(crate) fields: SceneFields,
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct ParsedEntity {
+    /// The line of the `[entity]` section, 0 for entities built in code.
+    pub(crate) line: usize,
+    /// The line and the position of the parent entity in the file.
+    pub(crate) parent: Option<(usize, usize)>,
+    pub(crate) components: Vec<ParsedComponent>,
+}
+
+impl ParsedEntity {
+    /// The component called `name`, added without fields if the entity doesn't have it.
+    pub(crate) fn component(&mut self, name: &str, line: usize) -> &mut SceneFields {
+        let index = match self.components.iter().position(|c| c.name == name) {
+            Some(index) => index,
+            None => {
+                self.components.push(ParsedComponent {
+                    name: name.to_string(),
+                    line,
+                    fields: SceneFields::default(),
+                });
+                self.components.len() - 1
+            }
+        };
+        &mut self.components[index].fields
+    }
+}
+
+pub(crate) fn parse_entities(text: &str) -> Result<Vec<ParsedEntity>, SceneError> {
+    let mut entities: Vec<ParsedEntity> = Vec::new();
+
+    for (index, raw_line) in text.lines().enumerate() {
+        let line_number = index + 1;
+        let line = strip_comment(raw_line).trim();
+        if line.is_empty() {
+            continue;
+        }
+        let parse_error = |message| SceneError::Parse {
+            line: line_number,
+            message,
+        };
+
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            match name.trim() {
+                "entity" => entities.push(ParsedEntity {
+                    line: line_number,
+                    parent: None,
+                    components: Vec::new(),
+                }),
+                name => return Err(parse_error(format!("unknown section `{name}`"))),
+            }
+            continue;
+        }
+
+        let Some(entity) = entities.last_mut() else {
+            return Err(parse_error(
+                "expected `[entity]` before the first component".to_string(),
+            ));
+        };
+        let (name, field) = match line.split_once('=') {
+            Some((key, value)) if key.trim() == "parent" => {
+                let parent = value.trim().parse().map_err(|_| {
+                    parse_error(format!("expected an entity number, got `{}`", value.trim()))
+                })?;
+                entity.parent = Some((line_number, parent));
+                continue;
+            }
+            Some((key, value)) => {
+                let Some((name, field)) = key.trim().split_once('.') else {
+                    return Err(parse_error(format!(
+                        "expected `component.field = value`, got `{line}`"
+                    )));
+                };
+                (
+                    name,
+                    Some((field.trim(), unquote(value.trim()).map_err(parse_error)?)),
+                )
+            }
+            None => (line, None),
+        };
+
+        let fields = entity.component(name, line_number);
+        if let Some((field, value)) = field {
+            fields.set(field, value);
+        }
+    }
+
+    Ok(entities)
+}
+
+/// The line up to a `#` that isn't inside a quoted value.
+pub(crate) fn strip_comment(line: &str) -> &str {
+    let mut quoted = false;
+    let mut escaped = false;
+    for (index, c) in line.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            '#' if !quoted => return &line[..index],
+            _ => {}
+        }
+    }
+    line
+}
+
+/// Writes a value so that [`unquote`] reads it back unchanged.
+fn quote(value: &str) -> String {
+    let needs_quotes = value.contains(['#', '"', '\n', '\r']) || value.trim() != value;
+    if !needs_quotes {
+        return value.to_string();
+    }
+
+    let mut quoted = String::from('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// The text of a value, with the quotes and escapes of a quoted one removed.
+pub(crate) fn unquote(value: &str) -> Result<String, String> {
+    let Some(inner) = value.strip_prefix('"') else {
+        return Ok(value.to_string());
+    };
+
+    let mut text = String::new();
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if chars.as_str().is_empty() => return Ok(text),
+            '"' => return Err(format!("unexpected text after the quoted value `{value}`")),
+            '\\' => match chars.next() {
+                Some('"') => text.push('"'),
+                Some('\\') => text.push('\\'),
+                Some('n') => text.push('\n'),
+                Some('r') => text.push('\r'),
+                other => {
+                    let escape = other.map(String::from).unwrap_or_default();
+                    return Err(format!("unknown escape `\\{escape}` in `{value}`"));
+                }
+            },
+            c => text.push(c),
+        }
+    }
+    Err(format!("missing closing quote in `{value}`"))
+}
+
+/// Writes the world's entities to a scene file, with the components of its [`SceneRegistry`],
+/// or the engine's if it has none.
+pub fn save_scene(world: &World, path: impl AsRef<Path>) -> Result<(), SceneError> {
+    let path = path.as_ref();
+    let text = match world.get_unique::<&SceneRegistry>() {
+        Ok(registry) => registry.save(world),
+        Err(_) => SceneRegistry::default().save(world),
+    };
+    std::fs::write(path, text).map_err(|source| SceneError::Io {
+        path: path.display().to_string(),
+        source,
+    })
+}
+
+/// Adds the entities in a scene file to the world and returns them, see [`save_scene`].
+pub fn load_scene(world: &mut World, path: impl AsRef<Path>) -> Result<Vec<EntityId>, SceneError> {
+    let path = path.as_ref();
+    let text = std::fs::read_to_string(path).map_err(|source| SceneError::Io {
+        path: path.display().to_string(),
+        source,
+    })?;
+    load_scene_str(world, &text)
+}
+
+/// Adds the entities in scene file text to the world.
+pub fn load_scene_str(world: &mut World, text: &str) -> Result<Vec<EntityId>, SceneError> {
+    with_registry(world, |registry, world| registry.load(world, text))
+}
+
+/// Runs `f` with the world's [`SceneRegistry`], or the engine's if it has none.
+pub(crate) fn with_registry<R>(
+    world: &mut World,
+    f: impl FnOnce(&SceneRegistry, &mut World) -> R,
+) -> R {
+    // Taken out of the world while the entities are added to it
+    match world.remove_unique::<SceneRegistry>() {
+        Ok(registry) => {
+            let result = f(&registry, world);
+            world.add_unique(registry);
+            result
+        }
+        Err(_) => f(&SceneRegistry::default(), world),
+    }
+}
+
+#[derive(Debug)]
+pub enum SceneError {
+    Io {
+        path: String,
+        source: std::io::Error,
+    },
+    Parse {
+        line: usize,
+        message: String,
+    },
+    /// The [`PrefabLibrary`](crate::scene::prefab::PrefabLibrary) has no prefab by this name.
+    UnknownPrefab(String),
+}
+
+impl fmt::Display for SceneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SceneError::Io { path, source } => write!(f, "failed to access scene {path}: {source}"),
+            SceneError::Parse { line, message } => write!(f, "scene line {line}: {message}"),
+            SceneError::UnknownPrefab(name) => write!(f, "no prefab named `{name}`"),
+        }
+    }
+}
+
+impl std::error::Error for SceneError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SceneError::Io { source, .. } => Some(source),
+            SceneError::Parse { .. } | SceneError::UnknownPrefab(_) => None,
+        }
+    }
+}
+
+impl SceneComponent for Transform {
+    const NAME: &'static str = "transform";
+
+    fn save(&self, fields: &mut SceneFields) -> Result<(), String> {
+        fields.set_array("position", &self.get_position_vector());
+        fields.set_array("rotation", self.rotation.coords.as_slice());
+        // A uniform scale is written as one number
+        let [x, y, z] = self.get_scale_vector();
+        if x == y && y == z {
+            fields.set("scale", x);
+        } else {
+            fields.set_array("scale", &[x, y, z]);
+        }
+        Ok(())
+    }
+
+    fn load(fields: &mut SceneFields, _world: &World) -> Result<Self, String> {
+        let position = fields.take_array("position")?.unwrap_or([0.0; 3]);
+        let [x, y, z, w] = fields
+            .take_array("rotation")?
+            .unwrap_or([0.0, 0.0, 0.0, 1.0]);
+        let scale = match fields.take_str("scale") {
+            Some(value) if value.contains(|c: char| c == ',' || c.is_whitespace()) => {
+                config::parse_array("scale", &value)?
+            }
+            Some(value) => [config::parse("scale", &value)?; 3],
+            None => [1.0; 3],
+        };
+        Ok(Transform::from_trs(
+            position.into(),
+            UnitQuaternion::from_quaternion(Quaternion::new(w, x, y, z)),
+            scale.into(),
+        ))
+    }
+}
+
+impl SceneComponent for Name {
+    const NAME: &'static str = "name";
+
+    fn save(&self, fields: &mut SceneFields) -> Result<(), String> {
+        fields.set("value", &self.0);
+        Ok(())
+    }
+
+    fn load(fields: &mut SceneFields, _world: &World) -> Result<Self, String> {
+        fields
+            .take_str("value")
+            .map(Name)
+            .ok_or_else(|| "`value` is missing".to_string())
+    }
+}
+
+impl SceneComponent for Object3D {
+    const NAME: &'static str = "object3d";
+
+    fn save(&self, fields: &mut SceneFields) -> Result<(), String> {
+        fields.set("model", &self.model.id);
+        Ok(())
+    }
+
+    fn load(fields: &mut SceneFields, world: &World) -> Result<Self, String> {
+        let path = fields
+            .take_str("model")
+            .ok_or_else(|| "`model` is missing".to_string())?;
+        let mut assets = world
+            .get_unique::<&mut AssetManager>()
+            .map_err(|_| "the world has no AssetManager to load models with".to_string())?;
+        let handle = assets.load_model(&path).map_err(|e| e.to_string())?;
+        Ok(Object3D::with_model(handle))
+    }
+}
+
+impl SceneComponent for Animator {
+    const NAME: &'static str = "animator";
+
+    fn save(&self, fields: &mut SceneFields) -> Result<(), String> {
+        let model = self
+            .model
+            .as_ref()
+            .ok_or_else(|| "it wasn't created from a model".to_string())?;
+        fields.set("model", &model.id);
+        fields.set("clip", self.current_clip);
+        fields.set("playing", self.playing);
+        fields.set("looping", self.looping);
+        Ok(())
+    }
+
+    fn load(fields: &mut SceneFields, world: &World) -> Result<Self, String> {
+        let path = fields
+            .take_str("model")
+            .ok_or_else(|| "`model` is missing".to_string())?;
+        let mut assets = world
+            .get_unique::<&mut AssetManager>()
+            .map_err(|_| "the world has no AssetManager to load models with".to_string())?;
+        let handle = assets.load_model(&path).map_err(|e| e.to_string())?;
+        let mut animator = assets
+            .create_animator(&handle)
+            .ok_or_else(|| format!("{path} has no skin to animate"))?;
+
+        let clip = fields.take("clip")?.unwrap_or(0);
+        if fields.take("playing")?.unwrap_or(false) {
+            animator.play(clip);
+        } else {
+            animator.current_clip = clip.min(animator.clips.len().saturating_sub(1));
+        }
+        animator.looping = fields.take("looping")?.unwrap_or(animator.looping);
+        Ok(animator)
+    }
+}
+
+impl SceneComponent for Pointlight {
+    const NAME: &'static str = "pointlight";
+
+    fn save(&self, fields: &mut SceneFields) -> Result<(), String> {
+        fields.set_array("position", &self.position);
+        fields.set_array("color", &self.color);
+        fields.set("intensity", self.intensity);
+        fields.set("radius", self.radius);
+        Ok(())
+    }
+
+    fn load(fields: &mut SceneFields, _world: &World) -> Result<Self, String> {
+        Ok(Pointlight::new(
+            fields
+                .take_array("position")?
+                .unwrap_or([0.0, 0.0, 0.0, 1.0]),
+            fields.take_array("color")?.unwrap_or([1.0; 3]),
+            fields.take("intensity")?.unwrap_or(1.0),
+            fields.take("radius")?.unwrap_or(5.0),
+        ))
+    }
+}
+
+impl SceneComponent for Camera {
+    const NAME: &'static str = "camera";
+
+    fn save(&self, fields: &mut SceneFields) -> Result<(), String> {
+        fields.set("fov", self.fov);
+        fields.set("near", self.near);
+        fields.set("far", self.far);
+        fields.set("yaw", self.yaw);
+        fields.set("pitch", self.pitch);
+        fields.set("radius", self.radius);
+        fields.set_array("position", self.position.as_slice());
+        fields.set("active", self.active);
+        Ok(())
+    }
+
+    fn load(fields: &mut SceneFields, _world: &World) -> Result<Self, String> {
+        let position = fields.take_array("position")?.unwrap_or([0.0; 3]);
+        let mut camera = Camera::new(vec3(position[0], position[1], position[2]));
+        camera.fov = fields.take("fov")?.unwrap_or(camera.fov);
+        camera.near = fields.take("near")?.unwrap_or(camera.near);
+        camera.far = fields.take("far")?.unwrap_or(camera.far);
+        camera.yaw = fields.take("yaw")?.unwrap_or(camera.yaw);
+        camera.pitch = fields.take("pitch")?.unwrap_or(camera.pitch);
+        camera.radius = fields.take("radius")?.unwrap_or(camera.radius);
+        camera.active = fields.take("active")?.unwrap_or(camera.active);
+        Ok(camera)
+    }
+}
+
+impl SceneComponent for RigidBodyComponent {
+    const NAME: &'static str = "rigid_body";
+
+    fn save(&self, fields: &mut SceneFields) -> Result<(), String> {
+        let body_type = match self.body_type {
+            RigidBodyType::Dynamic => "dynamic",
+            RigidBodyType::Fixed => "fixed",
+            RigidBodyType::KinematicPositionBased => "kinematic_position_based",
+            RigidBodyType::KinematicVelocityBased => "kinematic_velocity_based",
+        };
+        fields.set("type", body_type);
+        Ok(())
+    }
+
+    fn load(fields: &mut SceneFields, _world: &World) -> Result<Self, String> {
+        let body_type = match fields.take_str("type").as_deref() {
+            None | Some("dynamic") => RigidBodyType::Dynamic,
+            Some("fixed") => RigidBodyType::Fixed,
+            Some("kinematic_position_based") => RigidBodyType::KinematicPositionBased,
+            Some("kinematic_velocity_based") => RigidBodyType::KinematicVelocityBased,
+            Some(other) => return Err(format!("unknown body type `{other}`")),
+        };
+        Ok(RigidBodyComponent::new(body_type))
+    }
+}
+
+impl SceneComponent for ColliderComponent {
+    const NAME: &'static str = "collider";
+
+    /// Balls, cuboids, cylinders, cones and capsules along an axis are supported.
+    fn save(&self, fields: &mut SceneFields) -> Result<(), String> {
+        let shape = &self.shape;
+        let shape = if let Some(ball) = shape.as_ball() {
+            format!("ball {}", ball.radius)
+        } else if let Some(cuboid) = shape.as_cuboid() {
+            let h = cuboid.half_extents;
+            format!("cuboid {} {} {}", h.x, h.y, h.z)
+        } else if let Some(cylinder) = shape.as_cylinder() {
+            format!("cylinder {} {}", cylinder.half_height, cylinder.radius)
+        } else if let Some(cone) = shape.as_cone() {
+            format!("cone {} {}", cone.half_height, cone.radius)
+        } else if let Some(capsule) = shape.as_capsule() {
+            let (a, b) = (capsule.segment.a, capsule.segment.b);
+            let axis = match (a.x == -b.x, a.y == -b.y, a.z == -b.z) {
+                (true, true, true) if a.y == 0.0 && a.z == 0.0 => "x",
+                (true, true, true) if a.x == 0.0 && a.z == 0.0 => "y",
+                (true, true, true) if a.x == 0.0 && a.y == 0.0 => "z",
+                _ => return Err("only capsules centred on an axis are supported".to_string()),
+            };
+            format!(
+                "capsule_{axis} {} {}",
+                capsule.half_height(),
+                capsule.radius
+            )
+        } else {
+            return Err(format!("unsupported shape {:?}", shape.shape_type()));
+        };
+        fields.set("shape", shape);
+        Ok(())
+    }
+
+    fn load(fields: &mut SceneFields, _world: &World) -> Result<Self, String> {
+        let shape = fields
+            .take_str("shape")
+            .ok_or_else(|| "`shape` is missing".to_string())?;
+        let mut words = shape.split_whitespace();
+        let kind = words.next().unwrap_or_default();
+        let numbers = words
+            .map(|w| config::parse::<f32>("shape", w))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let shape = match (kind, numbers.as_slice()) {
+            ("ball", &[radius]) => SharedShape::ball(radius),
+            ("cuboid", &[hx, hy, hz]) => SharedShape::cuboid(hx, hy, hz),
+            ("cylinder", &[half_height, radius]) => SharedShape::cylinder(half_height, radius),
+            ("cone", &[half_height, radius]) => SharedShape::cone(half_height, radius),
+            ("capsule_x", &[half_height, radius]) => SharedShape::capsule_x(half_height, radius),
+            ("capsule_y", &[half_height, radius]) => SharedShape::capsule_y(half_height, radius),
+            ("capsule_z", &[half_height, radius]) => SharedShape::capsule_z(half_height, radius),
+            _ => return Err(format!("invalid shape `{shape}`")),
+        };
+        Ok(ColliderComponent::new(shape))
+    }
+}
+
+impl SceneComponent for KinematicCharacterComponent {
+    const NAME: &'static str = "kinematic_character";
+
+    fn save(&self, _fields: &mut SceneFields) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn load(_fields: &mut SceneFields, _world: &World) -> Result<Self, String> {
+        Ok(KinematicCharacterComponent::new())
+    }
+}
+
+impl SceneComponent for NoInterpolation {
+    const NAME: &'static str = "no_interpolation";
+
+    fn save(&self, _fields: &mut SceneFields) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn load(_fields: &mut SceneFields, _world: &World) -> Result<Self, String> {
+        Ok(NoInterpolation)
+    }
+}
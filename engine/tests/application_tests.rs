@@ -1,6 +1,13 @@
+use std::sync::{Arc, Mutex};
+
 use sdl3::event::Event;
+use sdl3::keyboard::Keycode;
 use shipyard::World;
 use vulkano_engine::core::application::{Application, Game};
+use vulkano_engine::core::config::EngineConfig;
+use vulkano_engine::core::state::AppControl;
+use vulkano_engine::graphics::renderer::Renderer;
+use vulkano_engine::input::recording::{InputEvent, InputRecording};
 
 struct EmptyGame {
     world: World,
@@ -24,7 +31,143 @@ fn test_startup_without_models() {
         world: World::new(),
     };
 
-    let app = Application::new(game, "Test Window", 800, 600);
+    let mut config = EngineConfig::default();
+    config.window.title = "Test Window".to_string();
+    config.window.width = 800;
+    config.window.height = 600;
+
+    let app = Application::new(game, config).unwrap();
+
+    assert_eq!(app.simulation.physics_accumulator, 0.0);
+}
+
+#[test]
+fn test_headless_renderer_without_window() {
+    let renderer = Renderer::new_headless(64, 48, &EngineConfig::default().renderer).unwrap();
+
+    assert!(renderer.is_headless());
+    assert_eq!(renderer.image_extent(), [64, 48]);
+}
+
+/// Asks to quit every frame but refuses the first request.
+struct QuittingGame {
+    world: World,
+    frames: u32,
+    /// Quit requests seen and shutdowns, shared so they can be checked after the drop.
+    counts: Arc<Mutex<(u32, u32)>>,
+}
+impl Game for QuittingGame {
+    fn on_init(&mut self) {}
+    fn on_update(&mut self, _dt: f32) {
+        self.frames += 1;
+        self.world.get_unique::<&mut AppControl>().unwrap().quit();
+    }
+    fn on_render(&mut self) {}
+    fn on_event(&mut self, _event: &Event) {}
+    fn on_quit_requested(&mut self) -> bool {
+        let mut counts = self.counts.lock().unwrap();
+        counts.0 += 1;
+        counts.0 > 1
+    }
+    fn on_shutdown(&mut self) {
+        self.counts.lock().unwrap().1 += 1;
+    }
+    fn get_world(&self) -> &World {
+        &self.world
+    }
+    fn get_world_mut(&mut self) -> &mut World {
+        &mut self.world
+    }
+}
+
+#[test]
+fn test_quit_can_be_cancelled_and_shuts_down_once() {
+    let counts = Arc::new(Mutex::new((0, 0)));
+    let game = QuittingGame {
+        world: World::new(),
+        frames: 0,
+        counts: counts.clone(),
+    };
+
+    let mut config = EngineConfig::default();
+    config.window.width = 32;
+    config.window.height = 32;
+
+    let mut app = Application::new_headless(game, config).unwrap();
+    app.run_frames(10, 1.0 / 60.0);
+
+    assert_eq!(
+        app.simulation.game.frames, 2,
+        "Pirmas išėjimo prašymas turėjo būti atšauktas"
+    );
+    assert_eq!(*counts.lock().unwrap(), (2, 0));
+
+    app.shutdown();
+    drop(app);
+    assert_eq!(
+        counts.lock().unwrap().1,
+        1,
+        "on_shutdown turi būti kviečiamas kartą"
+    );
+}
+
+/// Keeps the keys its `on_event` saw pressed.
+struct KeyLoggingGame {
+    world: World,
+    pressed: Vec<Keycode>,
+}
+impl Game for KeyLoggingGame {
+    fn on_init(&mut self) {}
+    fn on_update(&mut self, _dt: f32) {}
+    fn on_render(&mut self) {}
+    fn on_event(&mut self, event: &Event) {
+        if let Event::KeyDown {
+            keycode: Some(keycode),
+            ..
+        } = event
+        {
+            self.pressed.push(*keycode);
+        }
+    }
+    fn get_world(&self) -> &World {
+        &self.world
+    }
+    fn get_world_mut(&mut self) -> &mut World {
+        &mut self.world
+    }
+}
+
+fn key_logging_app() -> Application<KeyLoggingGame> {
+    let game = KeyLoggingGame {
+        world: World::new(),
+        pressed: Vec::new(),
+    };
+
+    let mut config = EngineConfig::default();
+    config.window.width = 32;
+    config.window.height = 32;
+
+    Application::new_headless(game, config).unwrap()
+}
+
+#[test]
+fn test_replayed_keys_reach_on_event() {
+    let path = std::env::temp_dir().join("vulkano_engine_on_event_replay.txt");
+
+    let mut app = key_logging_app();
+    app.record_input(&path).unwrap();
+    app.run_frame_with_input(1.0 / 60.0, vec![InputEvent::KeyDown(Keycode::G)]);
+    app.run_frame_with_input(1.0 / 60.0, vec![InputEvent::KeyUp(Keycode::G)]);
+    app.shutdown();
+    assert_eq!(app.simulation.game.pressed, [Keycode::G]);
+    drop(app);
 
-    assert_eq!(app.physics_accumulator, 0.0);
+    let mut app = key_logging_app();
+    app.replay_input(InputRecording::load(&path).unwrap());
+    app.run_frames(2, 1.0 / 60.0);
+    assert_eq!(
+        app.simulation.game.pressed,
+        [Keycode::G],
+        "Atkurtas klavišo paspaudimas turi pasiekti on_event"
+    );
 }
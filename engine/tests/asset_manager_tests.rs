@@ -1,5 +1,8 @@
 use std::path::PathBuf;
-use vulkano_engine::assets::asset_manager::AssetManager;
+use vulkano_engine::assets::asset_manager::{AssetHandle, AssetManager};
+use vulkano_engine::assets::gltf_loader::LoaderGLTF;
+use vulkano_engine::core::error::{AssetError, EngineError};
+use vulkano_engine::graphics::skybox::HdrSkyboxImages;
 
 #[test]
 fn test_load_3d_model_success() {
@@ -7,7 +10,7 @@ fn test_load_3d_model_success() {
 
     let test_model_path = "../engine/data/models/suzanne_2_material.glb";
 
-    let model_id = asset_manager.load_model(test_model_path);
+    let model_id = asset_manager.load_model(test_model_path).unwrap();
 
     assert!(
         model_id.id != "0",
@@ -20,3 +23,67 @@ fn test_load_3d_model_success() {
         "Modelis nebuvo išsaugotas AssetManager'yje!"
     );
 }
+
+#[test]
+fn test_missing_model_returns_error() {
+    let mut asset_manager = AssetManager::new();
+
+    let result = asset_manager.load_model("../engine/data/models/does_not_exist.glb");
+
+    match result {
+        Err(AssetError::Gltf { path, .. }) => {
+            assert_eq!(
+                path,
+                PathBuf::from("../engine/data/models/does_not_exist.glb")
+            )
+        }
+        Err(e) => panic!("Netikėta klaida: {e}"),
+        Ok(_) => panic!("Neegzistuojantis modelis neturėjo būti įkeltas"),
+    }
+    assert!(
+        asset_manager
+            .get_model(&AssetHandle {
+                id: "../engine/data/models/does_not_exist.glb".to_string()
+            })
+            .is_none(),
+        "Nepavykęs įkėlimas neturi palikti modelio"
+    );
+}
+
+#[test]
+fn test_malformed_model_returns_error() {
+    let path = std::env::temp_dir().join("vulkano_engine_malformed.glb");
+    std::fs::write(&path, b"not a glTF file").unwrap();
+
+    let error = LoaderGLTF::new(path.to_str().unwrap(), [0.0, 0.0, 0.0])
+        .err()
+        .expect("Sugadintas failas neturėjo būti įkeltas");
+
+    assert_eq!(error.path(), path);
+    let engine_error = EngineError::from(error);
+    assert!(matches!(engine_error, EngineError::Asset(_)));
+    assert!(
+        engine_error
+            .to_string()
+            .contains("vulkano_engine_malformed.glb"),
+        "Klaidos pranešime turi būti failo kelias"
+    );
+}
+
+#[test]
+fn test_missing_skybox_returns_error() {
+    let result = HdrSkyboxImages::from_equirect("../engine/data/does_not_exist.hdr", 4);
+
+    assert!(matches!(result, Err(AssetError::Image { .. })));
+}
+
+#[test]
+fn test_uniform_skybox_has_the_given_colour() {
+    let sky = HdrSkyboxImages::uniform([0.25, 0.5, 1.0], 4);
+
+    assert_eq!(sky.face_size, 4);
+    for face in &sky.faces {
+        assert_eq!(face.len(), 4 * 4 * 4);
+        assert!(face.chunks_exact(4).all(|p| p == [0.25f32, 0.5, 1.0, 1.0]));
+    }
+}
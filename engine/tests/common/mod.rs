@@ -0,0 +1,162 @@
+//! Helpers shared by the integration tests: worlds with the engine's uniques, approximate
+//! comparisons and the golden-image comparison of the render regression tests.
+//!
+//! Golden references live in `tests/golden/<name>.png`. A missing reference fails the test;
+//! set `UPDATE_GOLDEN=1` to record missing references or overwrite existing ones after an
+//! intended change. On mismatch the actual frame and a diff image are written to
+//! `target/golden-diffs/`.
+
+// Every test binary includes this module but only uses some of it
+#![allow(dead_code)]
+
+use image::{Rgba, RgbaImage};
+use nalgebra::UnitQuaternion;
+use nalgebra_glm::{TVec3, vec3};
+use shipyard::World;
+use std::path::PathBuf;
+use vulkano_engine::assets::asset_manager::AssetManager;
+
+/// A world with an `AssetManager`, for loading models and scene files.
+pub fn world_with_assets() -> World {
+    let world = World::new();
+    world.add_unique(AssetManager::new());
+    world
+}
+
+pub fn assert_close(actual: [f32; 3], expected: [f32; 3]) {
+    assert!(
+        actual
+            .iter()
+            .zip(expected)
+            .all(|(a, e)| (a - e).abs() < 1e-5),
+        "{actual:?} != {expected:?}"
+    );
+}
+
+pub fn assert_vec_close(actual: TVec3<f32>, expected: TVec3<f32>) {
+    assert!(
+        (actual - expected).abs().max() < 1e-4,
+        "{actual:?} != {expected:?}"
+    );
+}
+
+/// Compares rotations by what they do to a vector, so `q` and `-q` count as equal.
+pub fn assert_rotation_close(actual: &UnitQuaternion<f32>, expected: &UnitQuaternion<f32>) {
+    let v = vec3(1.0, 2.0, 3.0);
+    assert_vec_close(actual * v, expected * v);
+}
+
+/// How far two frames may drift apart before a comparison fails.
+pub struct Tolerance {
+    /// Per-pixel perceptual threshold in `0.0..=1.0` (YIQ colour distance, as in pixelmatch).
+    pub pixel_threshold: f32,
+    /// Fraction of pixels allowed to exceed `pixel_threshold`.
+    pub max_diff_ratio: f32,
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Self {
+            pixel_threshold: 0.1,
+            max_diff_ratio: 0.005,
+        }
+    }
+}
+
+/// Maximum possible YIQ delta between two opaque pixels (black vs white).
+const MAX_YIQ_DELTA: f32 = 35215.0;
+
+pub fn assert_matches_golden(
+    name: &str,
+    width: u32,
+    height: u32,
+    rgba: Vec<u8>,
+    tolerance: Tolerance,
+) {
+    let actual = RgbaImage::from_raw(width, height, rgba).expect("Kadro dydis neatitinka");
+    let reference_path = golden_dir().join(format!("{name}.png"));
+
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        actual.save(&reference_path).unwrap();
+        eprintln!("golden: recorded reference {}", reference_path.display());
+        return;
+    }
+
+    assert!(
+        reference_path.exists(),
+        "golden '{name}': reference {} is missing; run with UPDATE_GOLDEN=1 to record it",
+        reference_path.display(),
+    );
+
+    let reference = image::open(&reference_path).unwrap().to_rgba8();
+    assert_eq!(
+        reference.dimensions(),
+        actual.dimensions(),
+        "golden '{name}': reference size differs from rendered frame"
+    );
+
+    let max_delta = tolerance.pixel_threshold * tolerance.pixel_threshold * MAX_YIQ_DELTA;
+    let mut diff = RgbaImage::new(width, height);
+    let mut differing = 0usize;
+
+    for (x, y, expected) in reference.enumerate_pixels() {
+        let got = actual.get_pixel(x, y);
+        if yiq_delta(expected, got) > max_delta {
+            differing += 1;
+            diff.put_pixel(x, y, Rgba([255, 0, 0, 255]));
+        } else {
+            // Faded greyscale of the reference so the differences stand out.
+            let luma = (yiq_y(expected) * 0.25 + 191.0) as u8;
+            diff.put_pixel(x, y, Rgba([luma, luma, luma, 255]));
+        }
+    }
+
+    let ratio = differing as f32 / (width * height) as f32;
+    if ratio > tolerance.max_diff_ratio {
+        let out_dir = diff_dir();
+        std::fs::create_dir_all(&out_dir).unwrap();
+        let actual_path = out_dir.join(format!("{name}.actual.png"));
+        let diff_path = out_dir.join(format!("{name}.diff.png"));
+        actual.save(&actual_path).unwrap();
+        diff.save(&diff_path).unwrap();
+
+        panic!(
+            "golden '{name}': {differing} pixels ({:.3}%) differ, allowed {:.3}%. \
+             Actual: {}, diff: {}",
+            ratio * 100.0,
+            tolerance.max_diff_ratio * 100.0,
+            actual_path.display(),
+            diff_path.display(),
+        );
+    }
+}
+
+fn golden_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("golden")
+}
+
+fn diff_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join("target")
+        .join("golden-diffs")
+}
+
+fn yiq_y(p: &Rgba<u8>) -> f32 {
+    0.29889531 * p[0] as f32 + 0.58662247 * p[1] as f32 + 0.11448223 * p[2] as f32
+}
+
+fn yiq_delta(a: &Rgba<u8>, b: &Rgba<u8>) -> f32 {
+    let (r1, g1, b1) = (a[0] as f32, a[1] as f32, a[2] as f32);
+    let (r2, g2, b2) = (b[0] as f32, b[1] as f32, b[2] as f32);
+
+    let y = yiq_y(a) - yiq_y(b);
+    let i = (0.59597799 * r1 - 0.27417610 * g1 - 0.32180189 * b1)
+        - (0.59597799 * r2 - 0.27417610 * g2 - 0.32180189 * b2);
+    let q = (0.21147017 * r1 - 0.52261711 * g1 + 0.31114694 * b1)
+        - (0.21147017 * r2 - 0.52261711 * g2 + 0.31114694 * b2);
+
+    0.5053 * y * y + 0.299 * i * i + 0.1957 * q * q
+}
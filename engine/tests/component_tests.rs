@@ -1,5 +1,18 @@
+mod common;
+
+use common::{assert_rotation_close, assert_vec_close};
+use nalgebra::UnitQuaternion;
 use nalgebra_glm::vec3;
-use shipyard::{Get, IntoIter, View, ViewMut, World};
+use rapier3d::math::Vector;
+use shipyard::{Get, IntoIter, UniqueView, View, ViewMut, World};
+use vulkano_engine::physics::interpolation::{
+    NoInterpolation, PhysicsPose, physics_interpolation_system,
+};
+use vulkano_engine::physics::physics_engine::{
+    PhysicsEngine, RigidBodyComponent, from_physics_pose, physics_bodies_creation_system,
+    to_physics_pose,
+};
+use vulkano_engine::scene::components::fixed_time::FixedTime;
 use vulkano_engine::scene::components::pointlight::Pointlight;
 use vulkano_engine::scene::components::transform::Transform;
 
@@ -53,3 +66,117 @@ fn test_pointlight_registration() {
         assert_eq!(light.radius, radius, "Pointlight radius neatitinka");
     });
 }
+
+#[test]
+fn test_physics_pose_interpolation() {
+    let mut world = World::new();
+    world.add_unique(FixedTime {
+        step: 1.0 / 120.0,
+        alpha: 0.25,
+    });
+
+    let spin = UnitQuaternion::from_euler_angles(0.0, 1.0, 0.0);
+    let mut pose = PhysicsPose::new([0.0, 0.0, 0.0], UnitQuaternion::identity());
+    pose.push([4.0, -8.0, 0.0], spin);
+
+    let smooth = world.add_entity((Transform::with_pos(vec3(0.0, 0.0, 0.0)), pose));
+    let mut latest = Transform::with_pos(vec3(4.0, -8.0, 0.0));
+    latest.rotation = spin;
+    let snapped = world.add_entity((latest, pose, NoInterpolation));
+
+    world.run(physics_interpolation_system);
+
+    let transforms = world.borrow::<View<Transform>>().unwrap();
+    assert_eq!(
+        transforms.get(smooth).unwrap().get_position_vector(),
+        [1.0, -2.0, 0.0],
+        "Transform turi būti interpoliuotas tarp fizikos žingsnių"
+    );
+    // Sukimas taip pat interpoliuojamas: ketvirtadalis kelio iki 1 rad apie Y
+    assert_rotation_close(
+        &transforms.get(smooth).unwrap().rotation,
+        &UnitQuaternion::from_euler_angles(0.0, 0.25, 0.0),
+    );
+    assert_eq!(
+        transforms.get(snapped).unwrap().get_position_vector(),
+        [4.0, -8.0, 0.0],
+        "NoInterpolation objektas neturi būti interpoliuojamas"
+    );
+    assert_rotation_close(&transforms.get(snapped).unwrap().rotation, &spin);
+}
+
+#[test]
+fn test_transform_non_uniform_scale() {
+    let mut transform = Transform::with_pos(vec3(1.0, 2.0, 3.0));
+    transform.scale = vec3(2.0, 0.5, 4.0);
+    transform.rotate(std::f32::consts::FRAC_PI_2, vec3(0.0, 0.0, 1.0));
+
+    // Mastelis taikomas prieš sukimą: X ašis ištempiama ir pasukama į Y
+    let point = transform.model_matrix() * nalgebra_glm::vec4(1.0, 0.0, 0.0, 1.0);
+    assert_vec_close(point.xyz(), vec3(1.0, 4.0, 3.0));
+
+    let decomposed = Transform::from_matrix(&transform.model_matrix());
+    assert_vec_close(decomposed.translation, transform.translation);
+    assert_vec_close(decomposed.scale, transform.scale);
+    assert_rotation_close(&decomposed.rotation, &transform.rotation);
+}
+
+#[test]
+fn test_transform_orientation_helpers() {
+    let mut transform = Transform::with_pos(vec3(0.0, 0.0, 5.0));
+    assert_vec_close(transform.get_forward_vector(), vec3(0.0, 0.0, -1.0));
+
+    // Žiūri į taško kryptį: 45° į dešinę nuo -Z
+    transform.look_at(vec3(5.0, 0.0, 0.0), vec3(0.0, 1.0, 0.0));
+    let half = std::f32::consts::FRAC_1_SQRT_2;
+    assert_vec_close(transform.get_forward_vector(), vec3(half, 0.0, -half));
+    assert_vec_close(transform.get_right_vector(), vec3(half, 0.0, half));
+    assert_vec_close(transform.get_up_vector(), vec3(0.0, 1.0, 0.0));
+    assert_vec_close(
+        transform.get_euler_angles(),
+        vec3(0.0, -std::f32::consts::FRAC_PI_4, 0.0),
+    );
+
+    transform.set_euler_angles(0.3, -0.2, 1.1);
+    assert_vec_close(transform.get_euler_angles(), vec3(0.3, -0.2, 1.1));
+}
+
+#[test]
+fn test_physics_pose_conversion() {
+    let mut transform = Transform::with_pos(vec3(1.0, -2.0, 3.0));
+    transform.rotation = UnitQuaternion::from_euler_angles(0.4, 0.7, -0.2);
+
+    let pose = to_physics_pose(&transform);
+    assert_eq!(
+        [pose.translation.x, pose.translation.y, pose.translation.z],
+        [1.0, 2.0, 3.0],
+        "Fizikoje Y ašis apversta"
+    );
+
+    // Taškas pasukamas taip pat, tik su apversta Y ašimi
+    let rendered = transform.rotation * vec3(0.5, 1.0, -2.0);
+    let simulated = pose.rotation * Vector::new(0.5, -1.0, -2.0);
+    assert_vec_close(vec3(simulated.x, -simulated.y, simulated.z), rendered);
+
+    let back = from_physics_pose(&pose);
+    assert_vec_close(back.translation, transform.translation);
+    assert_rotation_close(&back.rotation, &transform.rotation);
+}
+
+#[test]
+fn test_bodies_start_with_transform_rotation() {
+    let mut world = World::new();
+    world.add_unique(PhysicsEngine::new());
+    let mut transform = Transform::with_pos(vec3(0.0, -1.0, 0.0));
+    transform.set_euler_angles(0.0, 0.5, 0.0);
+    world.add_entity((transform.clone(), RigidBodyComponent::fixed()));
+
+    world.run(physics_bodies_creation_system);
+    world.run(|physics: UniqueView<PhysicsEngine>| {
+        let (_, body) = physics.rigid_body_set.iter().next().unwrap();
+        let created = from_physics_pose(body.position());
+        assert_vec_close(created.translation, transform.translation);
+        // Sukimas turi pasiekti rapier kūną
+        assert_rotation_close(&created.rotation, &transform.rotation);
+    });
+}
@@ -0,0 +1,136 @@
+use vulkano::swapchain::PresentMode;
+use vulkano_engine::core::config::{ConfigError, EngineConfig, SurfaceFormatPreference};
+use vulkano_engine::core::window::WindowMode;
+
+#[test]
+fn test_config_file_overrides_defaults() {
+    let mut config = EngineConfig::default();
+    config
+        .apply_str(
+            "
+            # low quality preset
+            [renderer]
+            present_mode = fifo
+            surface_format = unorm
+            shadow_map_size = 1024   # smaller shadows
+            fxaa_enabled = false
+
+            [physics]
+            rate = 60
+            gravity = 0.0, -20.0, 0.0
+
+            [environment]
+            skybox = data/skybox/test.hdr
+            ",
+        )
+        .unwrap();
+
+    assert_eq!(config.renderer.present_mode, PresentMode::Fifo);
+    assert_eq!(
+        config.renderer.surface_format,
+        SurfaceFormatPreference::Unorm
+    );
+    assert_eq!(config.renderer.shadow_map_size, 1024);
+    assert!(!config.renderer.fxaa_enabled);
+    assert_eq!(config.physics.fixed_dt, 1.0 / 60.0);
+    assert_eq!(config.physics.gravity, [0.0, -20.0, 0.0]);
+    assert_eq!(config.environment.skybox, "data/skybox/test.hdr");
+
+    // Neliesti nustatymai turi likti numatytieji
+    assert_eq!(config.window.width, 1920);
+    assert_eq!(config.renderer.exposure, 1.0);
+}
+
+#[test]
+fn test_config_set_from_code() {
+    let mut config = EngineConfig::default();
+
+    config.set("window.width", "1280").unwrap();
+    config.set("environment.sun_color", "1 2 3").unwrap();
+
+    assert_eq!(config.window.width, 1280);
+    assert_eq!(config.environment.sun_color, [1.0, 2.0, 3.0]);
+    assert!(config.set("renderer.shadow_map_size", "huge").is_err());
+    assert!(config.set("renderer.unknown", "1").is_err());
+    assert!(config.set("physics.fixed_dt", "0").is_err());
+    assert!(config.set("physics.rate", "-60").is_err());
+    // Už `physics.rate` cvar ribų esančios reikšmės atmetamos, o ne tyliai apkarpomos
+    assert!(config.set("physics.rate", "2000").is_err());
+    assert!(config.set("physics.fixed_dt", "0.5").is_err());
+    assert_eq!(
+        config.physics.fixed_dt,
+        EngineConfig::default().physics.fixed_dt
+    );
+}
+
+#[test]
+fn test_config_quoted_values_keep_hashes() {
+    let mut config = EngineConfig::default();
+    config
+        .apply_str(
+            "
+            [window]
+            title = \"Build #2 \\\"nightly\\\"\"   # quoted
+            ",
+        )
+        .unwrap();
+
+    assert_eq!(config.window.title, "Build #2 \"nightly\"");
+
+    let err = config
+        .apply_str("[window]\ntitle = \"Build #2\n")
+        .unwrap_err();
+    assert!(matches!(err, ConfigError::Parse { line: 2, .. }));
+}
+
+#[test]
+fn test_config_errors_report_line() {
+    let mut config = EngineConfig::default();
+
+    let err = config
+        .apply_str("[window]\nwidth = 800\nheight\n")
+        .unwrap_err();
+    assert!(
+        matches!(err, ConfigError::Parse { line: 3, .. }),
+        "Klaida turi nurodyti 3 eilutę, gauta: {err}"
+    );
+
+    let err = EngineConfig::load("does/not/exist.cfg").unwrap_err();
+    assert!(matches!(err, ConfigError::Io { .. }));
+}
+
+#[test]
+fn test_shipped_config_matches_defaults() {
+    let config = EngineConfig::load(concat!(env!("CARGO_MANIFEST_DIR"), "/../engine.cfg")).unwrap();
+    let defaults = EngineConfig::default();
+
+    assert_eq!(config.window.title, defaults.window.title);
+    assert_eq!(config.window.mode, defaults.window.mode);
+    assert_eq!(config.renderer.present_mode, defaults.renderer.present_mode);
+    assert_eq!(
+        config.renderer.shadow_map_size,
+        defaults.renderer.shadow_map_size
+    );
+    assert_eq!(config.frame.max_fps, defaults.frame.max_fps);
+    assert_eq!(config.frame.unfocused_fps, defaults.frame.unfocused_fps);
+    assert_eq!(config.frame.max_dt, defaults.frame.max_dt);
+    assert_eq!(config.physics.fixed_dt, defaults.physics.fixed_dt);
+    assert_eq!(config.environment.skybox, defaults.environment.skybox);
+    assert_eq!(config.console.autoexec, defaults.console.autoexec);
+}
+
+#[test]
+fn test_config_window_mode() {
+    let mut config = EngineConfig::default();
+    assert_eq!(config.window.mode, WindowMode::Windowed);
+
+    config
+        .apply_str("[window]\nmode = borderless\nhigh_dpi = false\n")
+        .unwrap();
+    assert_eq!(config.window.mode, WindowMode::Borderless);
+    assert!(!config.window.high_dpi);
+
+    config.set("window.mode", "fullscreen").unwrap();
+    assert_eq!(config.window.mode, WindowMode::Fullscreen);
+    assert!(config.set("window.mode", "maximized").is_err());
+}
@@ -0,0 +1,111 @@
+use sdl3::keyboard::Keycode;
+use vulkano_engine::core::config::EngineConfig;
+use vulkano_engine::core::cvars::{CVarRegistry, CVarValue};
+
+fn registry() -> CVarRegistry {
+    let mut cvars = CVarRegistry::new();
+    cvars.register_float("player.move_speed", 2.0, 0.0, 20.0, "walking speed");
+    cvars.register_int("r.samples", 4, 1, 16, "sample count");
+    cvars.register_bool("r.fxaa", true, "FXAA");
+    cvars
+}
+
+#[test]
+fn test_cvar_commands() {
+    let mut cvars = registry();
+
+    assert_eq!(
+        cvars.execute("player.move_speed 3.5").unwrap(),
+        "player.move_speed = 3.5"
+    );
+    assert_eq!(cvars.float("player.move_speed"), 3.5);
+
+    cvars.execute("add r.samples 2; toggle r.fxaa").unwrap();
+    assert_eq!(cvars.int("r.samples"), 6);
+    assert!(!cvars.bool("r.fxaa"));
+
+    cvars.execute("set r.fxaa on").unwrap();
+    assert!(cvars.bool("r.fxaa"));
+
+    cvars.execute("reset player.move_speed").unwrap();
+    assert_eq!(cvars.float("player.move_speed"), 2.0);
+
+    assert_eq!(cvars.execute("r.samples").unwrap(), "r.samples = 6");
+    assert!(cvars.execute("list").unwrap().contains("walking speed"));
+}
+
+#[test]
+fn test_cvar_values_are_clamped() {
+    let mut cvars = registry();
+
+    assert_eq!(
+        cvars.set("player.move_speed", "100").unwrap(),
+        CVarValue::Float(20.0)
+    );
+    cvars.execute("add r.samples -100").unwrap();
+    assert_eq!(cvars.int("r.samples"), 1, "Reikšmė turi likti rėžiuose");
+}
+
+#[test]
+fn test_cvar_errors() {
+    let mut cvars = registry();
+
+    assert!(cvars.execute("unknown.cvar 1").is_err());
+    assert!(cvars.execute("r.samples many").is_err());
+    assert!(cvars.execute("toggle r.samples").is_err());
+    assert!(cvars.execute("add r.fxaa 1").is_err());
+    assert!(cvars.execute("bind NotAKey list").is_err());
+    assert!(cvars.execute("set r.samples").is_err());
+}
+
+#[test]
+fn test_cvar_binds() {
+    let mut cvars = registry();
+
+    cvars
+        .execute("bind \"Keypad +\" \"add player.move_speed 0.5; add r.samples 1\"")
+        .unwrap();
+    assert_eq!(
+        cvars.binding(Keycode::KpPlus),
+        Some("add player.move_speed 0.5; add r.samples 1")
+    );
+
+    let command = cvars.binding(Keycode::KpPlus).unwrap().to_string();
+    cvars.execute(&command).unwrap();
+    assert_eq!(cvars.float("player.move_speed"), 2.5);
+    assert_eq!(cvars.int("r.samples"), 5);
+
+    cvars.execute("unbind \"Keypad +\"").unwrap();
+    assert_eq!(cvars.binding(Keycode::KpPlus), None);
+}
+
+#[test]
+fn test_cvar_exec_file() {
+    let path = std::env::temp_dir().join("vulkano_engine_cvar_exec.cfg");
+    std::fs::write(
+        &path,
+        "# comment\nplayer.move_speed 4\n\nr.fxaa off # inline\n",
+    )
+    .unwrap();
+
+    let mut cvars = registry();
+    cvars.exec_file(&path).unwrap();
+    assert_eq!(cvars.float("player.move_speed"), 4.0);
+    assert!(!cvars.bool("r.fxaa"));
+
+    std::fs::write(&path, "player.move_speed 4\nr.fxaa maybe\n").unwrap();
+    let err = cvars.exec_file(&path).unwrap_err();
+    assert!(err.contains(":2:"), "Klaida turi nurodyti eilutę: {err}");
+}
+
+#[test]
+fn test_engine_cvars_follow_config() {
+    let mut config = EngineConfig::default();
+    config.renderer.exposure = 2.0;
+    config.physics.fixed_dt = 1.0 / 64.0;
+
+    let cvars = CVarRegistry::with_engine_defaults(&config);
+    assert_eq!(cvars.float("r.exposure"), 2.0);
+    assert_eq!(cvars.float("physics.rate"), 64.0);
+    assert_eq!(cvars.binding(Keycode::F1), Some("toggle r.fxaa"));
+}
@@ -0,0 +1,142 @@
+use shipyard::{Unique, UniqueView, UniqueViewMut, Workload, World};
+use vulkano_engine::core::events::{
+    self, EventReader, EventWriter, Events, send_event, update_events,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Explosion(u32);
+
+#[derive(Unique, Default)]
+struct Heard(Vec<u32>, EventReader<Explosion>);
+
+fn explode(mut explosions: EventWriter<Explosion>) {
+    explosions.send(Explosion(1));
+}
+
+fn listen(explosions: UniqueView<Events<Explosion>>, mut heard: UniqueViewMut<Heard>) {
+    let heard = &mut *heard;
+    heard
+        .0
+        .extend(heard.1.read(&explosions).map(|explosion| explosion.0));
+}
+
+#[test]
+fn test_events_live_for_two_frames() {
+    let mut events = Events::new();
+    let mut reader = EventReader::new();
+
+    events.send(Explosion(1));
+    events.update();
+    events.send(Explosion(2));
+    assert_eq!(events.len(), 2);
+
+    events.update();
+    assert_eq!(events.iter().copied().collect::<Vec<_>>(), [Explosion(2)]);
+
+    events.update();
+    assert!(events.is_empty(), "Įvykiai turi išnykti po dviejų kadrų");
+    assert_eq!(reader.read(&events).count(), 0);
+}
+
+#[test]
+fn test_readers_have_their_own_cursors() {
+    let mut events = Events::new();
+    let mut first = EventReader::new();
+    let mut second = EventReader::new();
+
+    events.send(Explosion(1));
+    assert_eq!(
+        first.read(&events).copied().collect::<Vec<_>>(),
+        [Explosion(1)]
+    );
+
+    events.send(Explosion(2));
+    assert_eq!(
+        first.read(&events).copied().collect::<Vec<_>>(),
+        [Explosion(2)]
+    );
+    assert_eq!(
+        second.read(&events).copied().collect::<Vec<_>>(),
+        [Explosion(1), Explosion(2)]
+    );
+    assert!(!first.has_unread(&events));
+    assert!(!second.has_unread(&events));
+}
+
+#[test]
+fn test_reader_sees_events_sent_after_it_ran() {
+    let mut events = Events::new();
+    let mut reader = EventReader::new();
+
+    // The reader runs first in the frame, the writer later
+    assert_eq!(reader.read(&events).count(), 0);
+    events.send(Explosion(1));
+    events.update();
+
+    assert_eq!(
+        reader.read(&events).copied().collect::<Vec<_>>(),
+        [Explosion(1)]
+    );
+    events.update();
+    assert_eq!(reader.read(&events).count(), 0);
+}
+
+#[test]
+fn test_reader_from_now_skips_old_events() {
+    let mut events = Events::new();
+    events.send(Explosion(1));
+
+    let mut reader = EventReader::from_now(&events);
+    assert_eq!(reader.read(&events).count(), 0);
+
+    events.send(Explosion(2));
+    assert_eq!(
+        reader.read(&events).copied().collect::<Vec<_>>(),
+        [Explosion(2)]
+    );
+}
+
+#[test]
+fn test_systems_communicate_through_events() {
+    let world = World::new();
+    events::add_event::<Explosion>(&world);
+    world.add_unique(Heard::default());
+
+    Workload::new("frame")
+        .with_system(listen)
+        .with_system(explode)
+        .add_to_world(&world)
+        .unwrap();
+
+    for _ in 0..3 {
+        world.run_workload("frame").unwrap();
+        update_events(&world);
+    }
+
+    // Every explosion is heard exactly once, the last one in the frame after it was sent
+    assert_eq!(world.get_unique::<&Heard>().unwrap().0, [1, 1]);
+    world.run_workload("frame").unwrap();
+    assert_eq!(world.get_unique::<&Heard>().unwrap().0, [1, 1, 1]);
+}
+
+#[test]
+fn test_add_event_twice_keeps_queue() {
+    let world = World::new();
+    events::add_event::<Explosion>(&world);
+    send_event(&world, Explosion(7));
+    events::add_event::<Explosion>(&world);
+
+    assert_eq!(world.get_unique::<&Events<Explosion>>().unwrap().len(), 1);
+
+    // Registered once, so a single update keeps it for another frame
+    update_events(&world);
+    assert_eq!(world.get_unique::<&Events<Explosion>>().unwrap().len(), 1);
+}
+
+#[test]
+fn test_send_event_without_queue_is_ignored() {
+    let world = World::new();
+    send_event(&world, Explosion(1));
+    update_events(&world);
+    assert!(world.get_unique::<&Events<Explosion>>().is_err());
+}
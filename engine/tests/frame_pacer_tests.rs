@@ -0,0 +1,72 @@
+use std::time::{Duration, Instant};
+
+use vulkano_engine::core::config::{EngineConfig, FrameConfig};
+use vulkano_engine::core::frame_pacer::FramePacer;
+
+fn pacer(max_fps: f32, unfocused_fps: f32, max_dt: f32, smoothing: f32) -> FramePacer {
+    FramePacer::new(&FrameConfig {
+        max_fps,
+        unfocused_fps,
+        max_dt,
+        smoothing,
+    })
+}
+
+#[test]
+fn test_spikes_are_clamped() {
+    let mut pacer = pacer(0.0, 0.0, 0.1, 0.0);
+    assert_eq!(pacer.filter(0.016), 0.016);
+    assert_eq!(pacer.filter(2.5), 0.1, "Šuolis turi būti apribotas");
+
+    let mut unclamped = pacer(0.0, 0.0, 0.0, 0.0);
+    assert_eq!(unclamped.filter(2.5), 2.5);
+}
+
+#[test]
+fn test_smoothing_averages_frame_times() {
+    let mut pacer = pacer(0.0, 0.0, 0.0, 0.5);
+    assert_eq!(pacer.filter(0.010), 0.010);
+    assert!((pacer.filter(0.020) - 0.015).abs() < 1e-6);
+    assert!((pacer.filter(0.020) - 0.0175).abs() < 1e-6);
+
+    // Without smoothing frame times pass straight through
+    let mut raw = pacer(0.0, 0.0, 0.0, 0.0);
+    raw.filter(0.010);
+    assert_eq!(raw.filter(0.020), 0.020);
+}
+
+#[test]
+fn test_unfocused_cap() {
+    let mut pacer = pacer(0.0, 30.0, 0.1, 0.0);
+    assert_eq!(pacer.target_fps(), None);
+
+    pacer.set_focused(false);
+    assert_eq!(pacer.target_fps(), Some(30.0));
+
+    pacer.unfocused_fps = 0.0;
+    pacer.max_fps = 144.0;
+    assert_eq!(pacer.target_fps(), Some(144.0));
+}
+
+#[test]
+fn test_frame_rate_cap_waits() {
+    let mut pacer = pacer(100.0, 0.0, 0.0, 0.0);
+    pacer.wait();
+
+    let start = Instant::now();
+    let mut total = 0.0;
+    for _ in 0..5 {
+        total += pacer.wait();
+    }
+
+    // Five frames at 100 fps take at least 50 ms
+    assert!(start.elapsed() >= Duration::from_millis(49));
+    assert!(total >= 0.049, "Kadrų laikas per trumpas: {total}");
+}
+
+#[test]
+fn test_default_config_is_uncapped_with_spike_clamp() {
+    let pacer = FramePacer::new(&EngineConfig::default().frame);
+    assert_eq!(pacer.target_fps(), None);
+    assert!(pacer.max_dt > 0.0);
+}
@@ -0,0 +1,180 @@
+mod common;
+
+use common::{assert_close, world_with_assets};
+use nalgebra_glm::vec4;
+use shipyard::{EntityId, Get, IntoIter, UniqueView, View, World};
+use vulkano_engine::assets::asset_manager::{AssetHandle, AssetManager};
+use vulkano_engine::core::error::AssetError;
+use vulkano_engine::scene::components::hierarchy::{
+    GlobalTransform, Parent, transform_propagation_system,
+};
+use vulkano_engine::scene::components::name::{Name, find_by_name};
+use vulkano_engine::scene::components::object3d::Object3D;
+use vulkano_engine::scene::components::transform::Transform;
+use vulkano_engine::scene::gltf_scene::spawn_gltf_scene;
+use vulkano_engine::scene::scene_file::SceneRegistry;
+
+/// Vienas trikampis: mazgas „Base“ su vaiku „Arm“, kuris piešia tinklą.
+const TRIANGLE_GLTF: &str = r#"{
+    "asset": { "version": "2.0" },
+    "scene": 0,
+    "scenes": [{ "nodes": [0] }],
+    "nodes": [
+        { "name": "Base", "translation": [0, 2, 0], "scale": [2, 1, 1], "children": [1] },
+        { "name": "Arm", "translation": [1, 0, 0], "rotation": [0, 0, 0.70710677, 0.70710677], "mesh": 0 }
+    ],
+    "meshes": [{ "primitives": [{ "attributes": { "POSITION": 0 }, "indices": 1 }] }],
+    "buffers": [{
+        "byteLength": 44,
+        "uri": "data:application/octet-stream;base64,AAAAAAAAAAAAAAAAAACAPwAAAAAAAAAAAAAAAAAAgD8AAAAAAAABAAIAAAA="
+    }],
+    "bufferViews": [
+        { "buffer": 0, "byteOffset": 0, "byteLength": 36 },
+        { "buffer": 0, "byteOffset": 36, "byteLength": 6 }
+    ],
+    "accessors": [
+        { "bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3", "min": [0, 0, 0], "max": [1, 1, 0] },
+        { "bufferView": 1, "componentType": 5123, "count": 3, "type": "SCALAR" }
+    ]
+}"#;
+
+/// Kiekvienas testas rašo savo failą, kad lygiagretūs testai netrukdytų vienas kitam.
+fn triangle_path(test: &str) -> String {
+    let path = std::env::temp_dir().join(format!("vulkano_engine_{test}.gltf"));
+    std::fs::write(&path, TRIANGLE_GLTF).unwrap();
+    path.to_str().unwrap().to_string()
+}
+
+fn named(world: &World, root: EntityId, name: &str) -> EntityId {
+    find_by_name(world, root, name).unwrap_or_else(|| panic!("Nerastas mazgas {name}"))
+}
+
+#[test]
+fn test_spawn_creates_entity_per_node() {
+    let path = triangle_path("spawn");
+    let mut world = world_with_assets();
+    let root = spawn_gltf_scene(&mut world, &path).unwrap();
+    let base = named(&world, root, "Base");
+    let arm = named(&world, root, "Arm");
+
+    let names = world.borrow::<View<Name>>().unwrap();
+    assert_eq!(names.get(root).unwrap().0, "vulkano_engine_spawn");
+    let parents = world.borrow::<View<Parent>>().unwrap();
+    assert_eq!(parents.get(base).unwrap().0, root);
+    assert_eq!(parents.get(arm).unwrap().0, base);
+
+    let objects = world.borrow::<View<Object3D>>().unwrap();
+    assert!(
+        objects.get(base).is_err(),
+        "Mazgas be tinklo neturi modelio"
+    );
+    assert_eq!(objects.get(arm).unwrap().model.id, format!("{path}:0"));
+
+    // Vaizdavimo erdvėje Y ašis apversta
+    let transforms = world.borrow::<View<Transform>>().unwrap();
+    assert_close(
+        transforms.get(base).unwrap().get_position_vector(),
+        [0.0, -2.0, 0.0],
+    );
+    assert_close(
+        transforms.get(base).unwrap().get_scale_vector(),
+        [2.0, 1.0, 1.0],
+    );
+}
+
+#[test]
+fn test_spawned_nodes_match_baked_model() {
+    let path = triangle_path("baked");
+    let mut world = world_with_assets();
+    let root = spawn_gltf_scene(&mut world, &path).unwrap();
+    let arm = named(&world, root, "Arm");
+    world.run(transform_propagation_system);
+
+    let global = world
+        .borrow::<View<GlobalTransform>>()
+        .unwrap()
+        .get(arm)
+        .unwrap()
+        .model_matrix();
+    let mut assets = world.get_unique::<&mut AssetManager>().unwrap();
+    let baked = assets.load_model(&path).unwrap();
+    let per_mesh = AssetHandle {
+        id: format!("{path}:0"),
+    };
+    let baked = &assets.get_model(&baked).unwrap().meshes[0].vertices;
+    let local = &assets.get_model(&per_mesh).unwrap().meshes[0].vertices;
+
+    assert_eq!(baked.len(), local.len());
+    for (baked, local) in baked.iter().zip(local) {
+        let [x, y, z] = local.position;
+        let moved = global * vec4(x, y, z, 1.0);
+        // Mazgų transformacijos turi duoti tą pačią vietą kaip ir įkeptos į viršūnes
+        assert_close([moved.x, moved.y, moved.z], baked.position);
+    }
+    assert_close(baked[1].position, [2.0, -3.0, 0.0]);
+}
+
+#[test]
+fn test_spawn_soldier_hierarchy() {
+    let mut world = world_with_assets();
+    let root = spawn_gltf_scene(&mut world, "../data/models/Soldier.glb").unwrap();
+    let character = named(&world, root, "Character");
+
+    assert_eq!(
+        world.borrow::<View<Transform>>().unwrap().iter().count(),
+        69
+    );
+    assert_eq!(world.borrow::<View<Object3D>>().unwrap().iter().count(), 2);
+    assert_eq!(
+        world
+            .borrow::<View<Parent>>()
+            .unwrap()
+            .get(character)
+            .unwrap()
+            .0,
+        root
+    );
+    assert!(find_by_name(&world, root, "vanguard_visor").is_some());
+
+    // Antrą kartą sukurtos esybės naudoja jau įkeltus modelius
+    let again = spawn_gltf_scene(&mut world, "../data/models/Soldier.glb").unwrap();
+    assert_ne!(again, root);
+    world.run(
+        |assets: UniqueView<AssetManager>, objects: View<Object3D>| {
+            assert_eq!(objects.iter().count(), 4);
+            assert!(
+                objects
+                    .iter()
+                    .all(|object| assets.get_model(&object.model).is_some())
+            );
+        },
+    );
+}
+
+#[test]
+fn test_mesh_ids_load_single_meshes() {
+    let path = triangle_path("mesh_ids");
+    let mut assets = AssetManager::new();
+
+    let handle = assets.load_model(&format!("{path}:0")).unwrap();
+    assert_eq!(assets.get_model(&handle).unwrap().meshes.len(), 1);
+    match assets.load_model(&format!("{path}:3")) {
+        Err(AssetError::Malformed { message, .. }) => assert!(message.contains("mesh 3")),
+        Err(e) => panic!("Netikėta klaida: {e}"),
+        Ok(_) => panic!("Neegzistuojantis tinklas neturėjo būti įkeltas"),
+    }
+}
+
+#[test]
+fn test_names_are_saved_in_scene_files() {
+    let path = triangle_path("names");
+    let mut world = world_with_assets();
+    spawn_gltf_scene(&mut world, &path).unwrap();
+
+    let text = SceneRegistry::default().save(&world);
+    assert!(text.contains("name.value = Arm\n"), "{text}");
+    assert!(
+        text.contains(&format!("object3d.model = {path}:0\n")),
+        "{text}"
+    );
+}
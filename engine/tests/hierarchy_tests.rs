@@ -0,0 +1,216 @@
+mod common;
+
+use common::assert_close;
+use nalgebra_glm::{TMat4, vec3};
+use shipyard::{EntityId, Get, UniqueView, View, ViewMut, World};
+use vulkano_engine::physics::physics_engine::{
+    PhysicsEngine, RigidBodyComponent, physics_bodies_creation_system,
+};
+use vulkano_engine::scene::components::hierarchy::{
+    Children, GlobalTransform, Parent, delete_recursive, remove_parent, set_parent,
+    transform_propagation_system, world_matrix,
+};
+use vulkano_engine::scene::components::transform::Transform;
+use vulkano_engine::scene::scene_file::{SceneError, SceneRegistry, load_scene_str};
+
+fn global_position(world: &World, entity: EntityId) -> [f32; 3] {
+    world
+        .borrow::<View<GlobalTransform>>()
+        .unwrap()
+        .get(entity)
+        .unwrap()
+        .get_position_vector()
+}
+
+fn current_world_matrix(world: &World, entity: EntityId) -> TMat4<f32> {
+    world.run(|transforms: View<Transform>, parents: View<Parent>| {
+        world_matrix(entity, &transforms, &parents).unwrap()
+    })
+}
+
+#[test]
+fn test_propagation_combines_parents() {
+    let mut world = World::new();
+    let parent = world.add_entity(Transform::with_pos_scale(vec3(1.0, 0.0, 0.0), 2.0));
+    let child = world.add_entity((Transform::with_pos(vec3(0.0, 0.0, 1.0)), Parent(parent)));
+    let grandchild = world.add_entity((Transform::with_pos(vec3(0.0, 1.0, 0.0)), Parent(child)));
+
+    world.run(transform_propagation_system);
+    assert_close(global_position(&world, parent), [1.0, 0.0, 0.0]);
+    assert_close(global_position(&world, child), [1.0, 0.0, 2.0]);
+    assert_close(global_position(&world, grandchild), [1.0, 2.0, 2.0]);
+    assert_eq!(
+        world
+            .borrow::<View<Children>>()
+            .unwrap()
+            .get(parent)
+            .unwrap()
+            .0,
+        vec![child],
+        "Vaikų sąrašas sudaromas iš Parent komponentų"
+    );
+
+    // Pajudinus tėvą kartu juda ir vaikai
+    world.run(|mut transforms: ViewMut<Transform>| {
+        (&mut transforms)
+            .get(parent)
+            .unwrap()
+            .set_position(0.0, 0.0, 0.0);
+    });
+    world.run(transform_propagation_system);
+    assert_close(global_position(&world, grandchild), [0.0, 2.0, 2.0]);
+}
+
+#[test]
+fn test_reparenting_keeps_world_position() {
+    let mut world = World::new();
+    let mut parent_transform = Transform::with_pos_scale(vec3(1.0, 2.0, 0.0), 2.0);
+    parent_transform.rotate(std::f32::consts::FRAC_PI_2, vec3(0.0, 1.0, 0.0));
+    let parent = world.add_entity(parent_transform);
+    let child = world.add_entity(Transform::with_pos(vec3(5.0, 0.0, 3.0)));
+    let before = current_world_matrix(&world, child);
+
+    set_parent(&world, child, parent, true);
+    assert!(
+        (current_world_matrix(&world, child) - before).abs().max() < 1e-5,
+        "Pakeitus tėvą esybė lieka toje pačioje vietoje"
+    );
+    assert_eq!(
+        world
+            .borrow::<View<Children>>()
+            .unwrap()
+            .get(parent)
+            .unwrap()
+            .0,
+        vec![child]
+    );
+
+    remove_parent(&world, child, true);
+    assert!(world.borrow::<View<Parent>>().unwrap().get(child).is_err());
+    assert!(
+        world
+            .borrow::<View<Children>>()
+            .unwrap()
+            .get(parent)
+            .unwrap()
+            .0
+            .is_empty()
+    );
+    assert_close(
+        world
+            .borrow::<View<Transform>>()
+            .unwrap()
+            .get(child)
+            .unwrap()
+            .get_position_vector(),
+        [5.0, 0.0, 3.0],
+    );
+
+    // Be išlaikymo vietinė transformacija lieka ta pati
+    set_parent(&world, child, parent, false);
+    world.run(transform_propagation_system);
+    assert_close(global_position(&world, child), [7.0, 2.0, -10.0]);
+}
+
+#[test]
+fn test_cycles_are_refused() {
+    let mut world = World::new();
+    let parent = world.add_entity(Transform::new());
+    let child = world.add_entity((Transform::new(), Parent(parent)));
+
+    set_parent(&world, parent, child, false);
+    set_parent(&world, parent, parent, false);
+    assert!(
+        world.borrow::<View<Parent>>().unwrap().get(parent).is_err(),
+        "Esybė negali tapti savo palikuonio vaiku"
+    );
+}
+
+#[test]
+fn test_delete_recursive() {
+    let mut world = World::new();
+    let root = world.add_entity(Transform::new());
+    let parent = world.add_entity((Transform::new(), Parent(root)));
+    let child = world.add_entity((Transform::new(), Parent(parent)));
+    let sibling = world.add_entity((Transform::new(), Parent(root)));
+    world.run(transform_propagation_system);
+
+    delete_recursive(&mut world, parent);
+    world.run(transform_propagation_system);
+    let transforms = world.borrow::<View<Transform>>().unwrap();
+    assert!(transforms.get(parent).is_err());
+    assert!(transforms.get(child).is_err());
+    assert!(transforms.get(sibling).is_ok());
+    assert_eq!(
+        world
+            .borrow::<View<Children>>()
+            .unwrap()
+            .get(root)
+            .unwrap()
+            .0,
+        vec![sibling]
+    );
+}
+
+#[test]
+fn test_child_bodies_start_at_world_position() {
+    let mut world = World::new();
+    world.add_unique(PhysicsEngine::new());
+    let parent = world.add_entity(Transform::with_pos(vec3(1.0, -2.0, 0.0)));
+    world.add_entity((
+        Transform::with_pos(vec3(0.0, 0.0, 2.0)),
+        Parent(parent),
+        RigidBodyComponent::fixed(),
+    ));
+
+    world.run(physics_bodies_creation_system);
+    world.run(
+        |physics: UniqueView<PhysicsEngine>, bodies: View<RigidBodyComponent>| {
+            let translations: Vec<_> = physics
+                .rigid_body_set
+                .iter()
+                .map(|(_, body)| body.translation())
+                .map(|t| [t.x, t.y, t.z])
+                .collect();
+            assert_eq!(bodies.iter().count(), 1);
+            // Fizikoje Y ašis apversta
+            assert_eq!(translations, vec![[1.0, 2.0, 2.0]]);
+        },
+    );
+}
+
+#[test]
+fn test_scene_file_parents() {
+    let mut world = World::new();
+    let entities = load_scene_str(
+        &mut world,
+        "
+        [entity]
+        parent = 1
+        transform.position = 0, 1, 0
+
+        [entity]
+        transform.position = 3, 0, 0
+        ",
+    )
+    .unwrap();
+    assert_eq!(
+        world
+            .borrow::<View<Parent>>()
+            .unwrap()
+            .get(entities[0])
+            .unwrap()
+            .0,
+        entities[1]
+    );
+    world.run(transform_propagation_system);
+    assert_close(global_position(&world, entities[0]), [3.0, 1.0, 0.0]);
+
+    let text = SceneRegistry::default().save(&world);
+    assert!(text.starts_with("[entity]\nparent = 1\n"), "{text}");
+
+    match load_scene_str(&mut World::new(), "[entity]\nparent = 1\n") {
+        Err(SceneError::Parse { line, .. }) => assert_eq!(line, 2),
+        other => panic!("Tikėtasi klaidos, gauta {other:?}"),
+    }
+}
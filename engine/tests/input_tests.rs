@@ -0,0 +1,72 @@
+use sdl3::keyboard::Keycode;
+use sdl3::mouse::MouseButton;
+use vulkano_engine::input::input_manager::InputManager;
+use vulkano_engine::input::recording::{InputEvent, InputRecorder, InputRecording};
+
+#[test]
+fn test_recording_round_trip() {
+    let frames = [
+        (
+            1.0 / 60.0,
+            vec![
+                InputEvent::KeyDown(Keycode::W),
+                InputEvent::KeyDown(Keycode::LShift),
+                InputEvent::MouseMotion { dx: 3.0, dy: -1.25 },
+            ],
+        ),
+        (0.0171, vec![]),
+        (
+            0.016,
+            vec![
+                InputEvent::MouseButtonDown(MouseButton::Left),
+                InputEvent::KeyUp(Keycode::W),
+            ],
+        ),
+    ];
+
+    let path = std::env::temp_dir().join("vulkano_engine_input_round_trip.txt");
+    let mut recorder = InputRecorder::create(&path).unwrap();
+    for (dt, events) in &frames {
+        recorder.record_frame(*dt, events).unwrap();
+    }
+    drop(recorder);
+
+    let mut recording = InputRecording::load(&path).unwrap();
+    for (dt, events) in &frames {
+        let frame = recording.next_frame().expect("Trūksta įrašyto kadro");
+        assert_eq!(frame.dt, *dt, "Kadro laikas turi sutapti bitas į bitą");
+        assert_eq!(&frame.events, events);
+    }
+    assert!(recording.next_frame().is_none());
+}
+
+#[test]
+fn test_recording_parse_errors() {
+    assert!(InputRecording::parse("key_down W\n").is_err());
+    assert!(InputRecording::parse("frame fast\n").is_err());
+    assert!(InputRecording::parse("frame 0.016\nkey_down NotAKey\n").is_err());
+}
+
+#[test]
+fn test_input_manager_apply() {
+    let mut input = InputManager::new();
+
+    input.begin_frame();
+    input.apply(&InputEvent::KeyDown(Keycode::Space));
+    input.apply(&InputEvent::MouseMotion { dx: 1.0, dy: 2.0 });
+    input.apply(&InputEvent::MouseMotion { dx: 0.5, dy: -1.0 });
+
+    assert!(input.pressed_keys.contains(&Keycode::Space));
+    assert!(input.just_pressed_keys.contains(&Keycode::Space));
+    assert_eq!(input.mouse_motion, (1.5, 1.0));
+
+    // Laikomas klavišas nebėra "ką tik paspaustas" kitame kadre
+    input.begin_frame();
+    input.apply(&InputEvent::KeyDown(Keycode::Space));
+    assert!(input.pressed_keys.contains(&Keycode::Space));
+    assert!(!input.just_pressed_keys.contains(&Keycode::Space));
+
+    input.apply(&InputEvent::KeyUp(Keycode::Space));
+    assert!(!input.pressed_keys.contains(&Keycode::Space));
+    assert!(input.released_keys.contains(&Keycode::Space));
+}
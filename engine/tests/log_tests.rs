@@ -0,0 +1,113 @@
+use vulkano_engine::core::config::EngineConfig;
+use vulkano_engine::core::cvars::CVarRegistry;
+use vulkano_engine::core::log::{self, ConsoleSink, FileSink, Filter, Level, LogSink, Record};
+use vulkano_engine::{debug, info, trace, warn};
+
+#[test]
+fn test_filter_uses_most_specific_target() {
+    let filter: Filter = "warn,game=debug,game::ai=off,vulkano_engine::assets=trace"
+        .parse()
+        .unwrap();
+
+    assert!(filter.enabled(Level::Warn, "vulkano_engine::physics"));
+    assert!(!filter.enabled(Level::Info, "vulkano_engine::physics"));
+    assert!(filter.enabled(Level::Debug, "game::player"));
+    assert!(!filter.enabled(Level::Error, "game::ai::planner"));
+    assert!(filter.enabled(Level::Trace, "vulkano_engine::assets::gltf_loader"));
+    // Tik pilni modulių vardai, ne bet kokia pradžia
+    assert!(!filter.enabled(Level::Debug, "gameplay"));
+
+    assert_eq!(filter.to_string().parse::<Filter>().unwrap(), filter);
+    assert!("loud".parse::<Filter>().is_err());
+}
+
+#[test]
+fn test_log_config_section() {
+    let mut config = EngineConfig::default();
+    config
+        .apply_str(
+            "
+            [log]
+            level = error, game=debug
+            file = game.log
+            max_files = 5
+            ",
+        )
+        .unwrap();
+
+    assert!(config.log.level.enabled(Level::Debug, "game"));
+    assert!(!config.log.level.enabled(Level::Warn, "vulkano_engine"));
+    assert_eq!(config.log.file.as_deref(), Some("game.log"));
+    assert_eq!(config.log.max_files, 5);
+    assert!(config.apply_str("[log]\nlevel = loud").is_err());
+}
+
+#[test]
+fn test_file_sink_rotates() {
+    let dir = std::env::temp_dir().join(format!("vulkano_engine_log_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("test.log");
+    for index in 0..4 {
+        let _ = std::fs::remove_file(dir.join(format!("test.log.{index}")));
+    }
+    let _ = std::fs::remove_file(&path);
+
+    let mut sink = FileSink::new(&path, 100, 2).unwrap();
+    for i in 0..10 {
+        sink.write(&Record {
+            level: Level::Info,
+            target: "test",
+            args: format_args!("line {i} with some padding"),
+        });
+    }
+    sink.flush();
+
+    let current = std::fs::read_to_string(&path).unwrap();
+    assert!(current.len() <= 100, "Failas turi būti pasuktas");
+    assert!(current.ends_with("INFO  test: line 9 with some padding\n"));
+    assert!(dir.join("test.log.1").exists());
+    assert!(dir.join("test.log.2").exists());
+    assert!(
+        !dir.join("test.log.3").exists(),
+        "Senesni failai turi būti ištrinti"
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+/// The only test that changes the global logger, so the others can't interfere with it.
+#[test]
+fn test_messages_reach_console_sink() {
+    let console = ConsoleSink::new(3);
+    log::clear_sinks();
+    log::add_sink(console.clone());
+    log::set_filter("info,game=trace".parse().unwrap());
+
+    debug!("dropped");
+    info!("kept {}", 1);
+    trace!(target: "game::ai", "kept {}", 2);
+    warn!(target: "game", "kept {}", 3);
+
+    let lines = console.lines();
+    let messages: Vec<_> = lines.iter().map(|l| l.message.as_str()).collect();
+    assert_eq!(messages, ["kept 1", "kept 2", "kept 3"]);
+    assert_eq!(lines[0].target, module_path!());
+    assert_eq!(lines[1].level, Level::Trace);
+
+    // Konsolė laiko tik paskutines eilutes
+    info!("kept 4");
+    assert_eq!(console.lines()[0].message, "kept 2");
+
+    // Filtras keičiamas ir iš konsolės
+    let mut cvars = CVarRegistry::new();
+    assert_eq!(
+        cvars.execute("log warn, game=off").unwrap(),
+        "log = warn,game=off"
+    );
+    console.clear();
+    info!("dropped");
+    warn!(target: "game", "dropped");
+    warn!("kept 5");
+    let messages: Vec<_> = console.lines().into_iter().map(|l| l.message).collect();
+    assert_eq!(messages, ["kept 5"]);
+}
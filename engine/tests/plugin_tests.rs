@@ -0,0 +1,111 @@
+use shipyard::{Unique, UniqueViewMut, World};
+use vulkano_engine::assets::asset_manager::AssetManager;
+use vulkano_engine::core::config::EngineConfig;
+use vulkano_engine::core::plugin::{self, Plugin};
+use vulkano_engine::core::schedule::{Schedule, Stage};
+use vulkano_engine::graphics::RenderPlugin;
+use vulkano_engine::input::InputPlugin;
+use vulkano_engine::input::input_manager::InputManager;
+use vulkano_engine::physics::PhysicsPlugin;
+use vulkano_engine::physics::physics_engine::PhysicsEngine;
+use vulkano_engine::scene::components::delta_time::DeltaTime;
+use vulkano_engine::scene::components::fixed_time::FixedTime;
+use vulkano_engine::scene::{AnimationPlugin, TransformPlugin};
+
+#[derive(Unique, Default)]
+struct Ticks(u32);
+
+struct TickPlugin;
+impl Plugin for TickPlugin {
+    fn build(&mut self, world: &mut World, schedule: &mut Schedule, _config: &EngineConfig) {
+        world.add_unique(Ticks::default());
+        schedule.add_system(Stage::Update, tick_system);
+    }
+}
+
+fn tick_system(mut ticks: UniqueViewMut<Ticks>) {
+    ticks.0 += 1;
+}
+
+fn build(plugins: &mut [Box<dyn Plugin>], config: &EngineConfig) -> World {
+    let mut world = World::new();
+    // Normally provided by the application
+    world.add_unique(DeltaTime(1.0 / 60.0));
+    world.add_unique(FixedTime::new(config.physics.fixed_dt));
+    let mut schedule = Schedule::new();
+    for plugin in plugins {
+        plugin.build(&mut world, &mut schedule, config);
+    }
+    schedule.add_to_world(&world).unwrap();
+    world
+}
+
+#[test]
+fn test_default_plugins_in_order() {
+    let names: Vec<_> = plugin::default_plugins()
+        .iter()
+        .map(|plugin| plugin.name())
+        .collect();
+    assert_eq!(
+        names,
+        [
+            std::any::type_name::<InputPlugin>(),
+            std::any::type_name::<PhysicsPlugin>(),
+            std::any::type_name::<AnimationPlugin>(),
+            std::any::type_name::<TransformPlugin>(),
+            std::any::type_name::<RenderPlugin>(),
+        ]
+    );
+}
+
+#[test]
+fn test_plugin_adds_uniques_and_systems() {
+    let world = build(&mut [Box::new(TickPlugin)], &EngineConfig::default());
+
+    Schedule::run(&world, Stage::Update);
+    Schedule::run(&world, Stage::Update);
+
+    assert_eq!(world.get_unique::<&Ticks>().unwrap().0, 2);
+}
+
+#[test]
+fn test_default_plugins_add_engine_uniques() {
+    let world = build(&mut plugin::default_plugins(), &EngineConfig::default());
+
+    assert!(world.get_unique::<&InputManager>().is_ok());
+    assert!(world.get_unique::<&PhysicsEngine>().is_ok());
+    assert!(world.get_unique::<&AssetManager>().is_ok());
+
+    // Every stage can run without a renderer
+    for stage in [Stage::PreUpdate, Stage::FixedUpdate, Stage::PostUpdate] {
+        Schedule::run(&world, stage);
+    }
+}
+
+#[test]
+fn test_game_without_physics() {
+    let mut plugins = plugin::default_plugins();
+    plugins.retain(|plugin| plugin.name() != std::any::type_name::<PhysicsPlugin>());
+    let world = build(&mut plugins, &EngineConfig::default());
+
+    assert!(
+        world.get_unique::<&PhysicsEngine>().is_err(),
+        "Be fizikos papildinio fizikos variklio neturėjo būti"
+    );
+    Schedule::run(&world, Stage::FixedUpdate);
+}
+
+#[test]
+fn test_reset_restores_level_uniques() {
+    let config = EngineConfig::default();
+    let mut plugins = plugin::default_plugins();
+    let mut world = build(&mut plugins, &config);
+
+    world.clear();
+    for plugin in &mut plugins {
+        plugin.reset(&mut world, &config);
+    }
+
+    assert!(world.get_unique::<&PhysicsEngine>().is_ok());
+    assert!(world.get_unique::<&AssetManager>().is_ok());
+}
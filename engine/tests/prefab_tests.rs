@@ -0,0 +1,226 @@
+mod common;
+
+use common::world_with_assets;
+use nalgebra_glm::vec3;
+use shipyard::{Component, Get, IntoIter, View, World};
+use vulkano_engine::scene::components::animator::Animator;
+use vulkano_engine::scene::components::hierarchy::{
+    Children, Parent, transform_propagation_system,
+};
+use vulkano_engine::scene::components::name::{Name, find_by_name};
+use vulkano_engine::scene::components::transform::Transform;
+use vulkano_engine::scene::prefab::{self, Prefab, PrefabEntity, PrefabLibrary, PrefabOverrides};
+use vulkano_engine::scene::scene_file::{SceneError, SceneRegistry};
+
+#[derive(Component, Debug, PartialEq)]
+struct Health(u32);
+
+fn world_with_library() -> World {
+    let world = world_with_assets();
+    world.add_unique(PrefabLibrary::new());
+    world
+}
+
+fn crate_prefab() -> Prefab {
+    Prefab::new(
+        PrefabEntity::new()
+            .set("name.value", "Crate")
+            .set("transform.scale", 2.0)
+            .with(|| Health(100))
+            .child(
+                PrefabEntity::new()
+                    .set("name.value", "Lid")
+                    .set("transform.position", "0, 1, 0"),
+            ),
+    )
+}
+
+fn entity_count(world: &World) -> usize {
+    world.borrow::<View<Transform>>().unwrap().iter().count()
+}
+
+#[test]
+fn test_instances_get_their_own_entities() {
+    let mut world = world_with_library();
+    let prefab = crate_prefab();
+
+    let first = prefab
+        .instantiate(
+            &mut world,
+            PrefabOverrides::new().position(vec3(1.0, 0.0, 0.0)),
+        )
+        .unwrap();
+    let second = prefab
+        .instantiate(
+            &mut world,
+            PrefabOverrides::new().position(vec3(-4.0, 0.0, 2.0)),
+        )
+        .unwrap();
+    assert_ne!(first, second);
+    assert_eq!(entity_count(&world), 4);
+
+    let transforms = world.borrow::<View<Transform>>().unwrap();
+    let first_transform = transforms.get(first).unwrap();
+    assert_eq!(first_transform.get_position_vector(), [1.0, 0.0, 0.0]);
+    assert_eq!(
+        first_transform.get_scale_vector(),
+        [2.0; 3],
+        "Pozicijos pakeitimas neturi numesti prefabo mastelio"
+    );
+    assert_eq!(
+        transforms.get(second).unwrap().get_position_vector(),
+        [-4.0, 0.0, 2.0]
+    );
+
+    let lid = find_by_name(&world, second, "Lid").expect("Kiekvienas egzempliorius turi dangtį");
+    assert_eq!(
+        world.borrow::<View<Parent>>().unwrap().get(lid).unwrap().0,
+        second
+    );
+    let healths = world.borrow::<View<Health>>().unwrap();
+    assert_eq!(healths.get(first).unwrap(), &Health(100));
+    assert_eq!(healths.get(second).unwrap(), &Health(100));
+}
+
+#[test]
+fn test_overrides_replace_components() {
+    let mut world = world_with_library();
+    let root = crate_prefab()
+        .instantiate(
+            &mut world,
+            PrefabOverrides::new()
+                .set("name.value", "Big crate")
+                .with(Health(5)),
+        )
+        .unwrap();
+
+    assert_eq!(
+        world.borrow::<View<Name>>().unwrap().get(root).unwrap().0,
+        "Big crate"
+    );
+    assert_eq!(
+        world.borrow::<View<Health>>().unwrap().get(root).unwrap(),
+        &Health(5)
+    );
+}
+
+#[test]
+fn test_file_prefabs_from_the_library() {
+    let path = std::env::temp_dir().join("vulkano_engine_lamp.prefab");
+    std::fs::write(
+        &path,
+        "
+        [entity]
+        name.value = Lamp
+        transform.position = 0, -2, 0
+
+        [entity]
+        parent = 0
+        name.value = Bulb
+        pointlight.radius = 3
+        transform.position = 0, -1, 0
+        ",
+    )
+    .unwrap();
+
+    let mut world = world_with_library();
+    world
+        .get_unique::<&mut PrefabLibrary>()
+        .unwrap()
+        .load("lamp", &path)
+        .unwrap();
+    let lamp = prefab::instantiate(&mut world, "lamp", PrefabOverrides::new()).unwrap();
+    world.run(transform_propagation_system);
+
+    let bulb = find_by_name(&world, lamp, "Bulb").unwrap();
+    assert_eq!(
+        world
+            .borrow::<View<Children>>()
+            .unwrap()
+            .get(lamp)
+            .unwrap()
+            .0,
+        vec![bulb],
+        "Failo `parent` eilutės sudaro hierarchiją"
+    );
+
+    match prefab::instantiate(&mut world, "chair", PrefabOverrides::new()) {
+        Err(SceneError::UnknownPrefab(name)) => assert_eq!(name, "chair"),
+        other => panic!("Tikėtasi klaidos, gauta {other:?}"),
+    }
+}
+
+#[test]
+fn test_failed_instance_adds_nothing() {
+    let mut world = world_with_library();
+    let prefab =
+        Prefab::parse("[entity]\ntransform.position = 1, 2, 3\n\n[entity]\nparent = 0\ncrate\n")
+            .unwrap();
+
+    match prefab.instantiate(&mut world, PrefabOverrides::new()) {
+        Err(SceneError::Parse { line, message }) => {
+            assert_eq!(line, 6);
+            assert!(message.contains("unknown component `crate`"), "{message}");
+        }
+        other => panic!("Tikėtasi klaidos, gauta {other:?}"),
+    }
+    assert_eq!(
+        entity_count(&world),
+        0,
+        "Nepavykęs egzempliorius neturi palikti esybių"
+    );
+    assert!(matches!(
+        Prefab::parse("# tuščias\n"),
+        Err(SceneError::Parse { .. })
+    ));
+}
+
+#[test]
+fn test_prefab_entities_must_descend_from_root() {
+    // Antra šakninė esybė, ciklas ir šaknis su tėvu
+    let errors = [
+        ("[entity]\nname.value = A\n\n[entity]\nname.value = B\n", 4),
+        ("[entity]\n[entity]\nparent = 2\n[entity]\nparent = 1\n", 2),
+        ("[entity]\nparent = 1\n[entity]\nparent = 0\n", 2),
+    ];
+    for (text, expected_line) in errors {
+        match Prefab::parse(text).err() {
+            Some(SceneError::Parse { line, .. }) => assert_eq!(line, expected_line, "{text}"),
+            other => panic!("Tikėtasi klaidos, gauta {other:?}"),
+        }
+    }
+
+    assert!(Prefab::parse("[entity]\n[entity]\nparent = 0\n[entity]\nparent = 1\n").is_ok());
+}
+
+#[test]
+fn test_animated_prefab() {
+    let mut world = world_with_library();
+    let soldier = Prefab::new(
+        PrefabEntity::new()
+            .set("object3d.model", "../data/models/Soldier.glb")
+            .set("animator.model", "../data/models/Soldier.glb")
+            .set("animator.clip", 1)
+            .set("animator.playing", true),
+    );
+
+    let root = soldier
+        .instantiate(&mut world, PrefabOverrides::new())
+        .unwrap();
+    {
+        let animators = world.borrow::<View<Animator>>().unwrap();
+        let animator = animators
+            .get(root)
+            .expect("Prefabas turi sukurti animatorių");
+        assert!(animator.playing);
+        assert_eq!(animator.current_clip, 1);
+    }
+
+    // Animatoriai išsaugomi kaip modelis, iš kurio sukurti
+    let text = SceneRegistry::default().save(&world);
+    assert!(
+        text.contains("animator.model = ../data/models/Soldier.glb\n"),
+        "{text}"
+    );
+    assert!(text.contains("animator.playing = true\n"), "{text}");
+}
@@ -0,0 +1,46 @@
+#![cfg(feature = "profiler")]
+
+use std::time::Duration;
+
+use vulkano_engine::core::profiler::{self, Timeline};
+use vulkano_engine::profile_scope;
+
+fn simulate() {
+    profile_scope!("simulate");
+    std::thread::sleep(Duration::from_millis(2));
+}
+
+// Profiliuotojas yra globalus, todėl visa tai tikrinama viename teste.
+#[test]
+fn test_profiler_scopes_and_trace() {
+    profiler::reset();
+
+    for _ in 0..3 {
+        profiler::new_frame();
+        profile_scope!("frame");
+        simulate();
+        simulate();
+    }
+    std::thread::spawn(simulate).join().unwrap();
+
+    assert_eq!(profiler::frames(), 3);
+
+    let stats = profiler::stats();
+    let simulate_stats = stats
+        .iter()
+        .find(|s| s.name == "simulate")
+        .expect("Trūksta „simulate“ srities");
+    assert_eq!(simulate_stats.timeline, Timeline::Cpu);
+    assert_eq!(simulate_stats.calls, 7);
+    assert!(simulate_stats.total >= Duration::from_millis(14));
+    assert!(simulate_stats.max >= Duration::from_millis(2));
+    assert!(profiler::report().contains("simulate"));
+
+    let trace = profiler::chrome_trace();
+    assert!(trace.starts_with("{\"displayTimeUnit\":\"ms\",\"traceEvents\":["));
+    assert_eq!(trace.matches("\"name\":\"simulate\"").count(), 7);
+    assert_eq!(trace.matches("\"name\":\"frame\"").count(), 3);
+
+    profiler::reset();
+    assert!(profiler::stats().is_empty());
+}
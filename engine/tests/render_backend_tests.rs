@@ -0,0 +1,122 @@
+use nalgebra_glm::{look_at, vec3};
+use sdl3::event::Event;
+use shipyard::World;
+use vulkano_engine::assets::asset_manager::{AssetHandle, AssetManager};
+use vulkano_engine::core::application::{Application, Game};
+use vulkano_engine::core::config::EngineConfig;
+use vulkano_engine::graphics::recording_renderer::RecordingRenderer;
+use vulkano_engine::scene::components::camera::Camera;
+use vulkano_engine::scene::components::object3d::Object3D;
+use vulkano_engine::scene::components::pointlight::Pointlight;
+use vulkano_engine::scene::components::transform::Transform;
+
+const DT: f32 = 1.0 / 60.0;
+
+struct SoldierScene {
+    world: World,
+    soldier: Option<AssetHandle>,
+}
+
+impl Game for SoldierScene {
+    fn on_init(&mut self) {
+        let soldier = {
+            let mut asset_manager = self.world.get_unique::<&mut AssetManager>().unwrap();
+            asset_manager
+                .load_model("../data/models/Soldier.glb")
+                .expect("Testinis modelis turi būti įkeliamas")
+        };
+
+        let mut camera = Camera::new(vec3(0.0, 0.0, 0.0));
+        camera.yaw = std::f32::consts::FRAC_PI_2;
+        self.world
+            .add_entity((camera, Transform::with_pos(vec3(0.0, -1.0, -3.0))));
+        self.world.add_entity((
+            Transform::with_pos(vec3(0.0, 0.0, 0.0)),
+            Object3D::with_model(soldier.clone()),
+        ));
+        self.world.add_entity(Pointlight::new(
+            [0.0, -2.0, -1.5, 1.0],
+            [1.0, 1.0, 1.0],
+            5.0,
+            5.0,
+        ));
+        self.soldier = Some(soldier);
+    }
+    fn on_update(&mut self, _dt: f32) {}
+    fn on_render(&mut self) {}
+    fn on_event(&mut self, _event: &Event) {}
+    fn get_world(&self) -> &World {
+        &self.world
+    }
+    fn get_world_mut(&mut self) -> &mut World {
+        &mut self.world
+    }
+}
+
+fn app() -> Application<SoldierScene, RecordingRenderer> {
+    let game = SoldierScene {
+        world: World::new(),
+        soldier: None,
+    };
+    Application::with_backend(
+        game,
+        RecordingRenderer::new(320, 240),
+        EngineConfig::default(),
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_frames_are_submitted_to_backend() {
+    let mut app = app();
+    app.run_frames(3, DT);
+
+    let renderer = &app.renderer;
+    assert_eq!(renderer.frames.len(), 3);
+    assert_eq!(
+        renderer.geometry_builds, 1,
+        "Geometrija turi būti sukurta kartą"
+    );
+    assert!(renderer.uploaded_meshes > 0);
+    assert!(renderer.environment.is_some());
+
+    let draw_count = {
+        let world = app.simulation.game.get_world();
+        let asset_manager = world.get_unique::<&AssetManager>().unwrap();
+        let soldier = app.simulation.game.soldier.as_ref().unwrap();
+        asset_manager.get_model(soldier).unwrap().draw_range.len()
+    };
+    let frame = renderer.last_frame().unwrap();
+    assert!(draw_count > 0);
+    assert_eq!(
+        frame.draws.len(),
+        draw_count,
+        "Turi būti piešiami visi modelio primityvai"
+    );
+    assert_eq!(frame.pointlights.len(), 1);
+
+    let position = vec3(0.0, -1.0, -3.0);
+    let expected = look_at(
+        &position,
+        &(position + vec3(0.0, 0.0, 1.0)),
+        &vec3(0.0, 1.0, 0.0),
+    );
+    assert!(
+        (frame.view - expected).abs().max() < 1e-5,
+        "Vaizdas turi sekti aktyvią kamerą"
+    );
+}
+
+#[test]
+fn test_cvars_reach_backend() {
+    let mut app = app();
+    app.run_frames(1, DT);
+    assert_eq!(app.renderer.settings.as_ref().unwrap().exposure, 1.0);
+
+    app.execute("r.exposure 2.5; set r.fxaa off").unwrap();
+    app.run_frames(1, DT);
+
+    let settings = app.renderer.settings.as_ref().unwrap();
+    assert_eq!(settings.exposure, 2.5);
+    assert!(!settings.fxaa_enabled);
+}
@@ -0,0 +1,143 @@
+//! Renders small fixed scenes headlessly and compares them with checked-in references.
+//!
+//! Run on a machine without a GPU by installing a CPU Vulkan driver (e.g. Mesa lavapipe) and
+//! setting `VULKANO_ENGINE_DEVICE=cpu`.
+
+mod common;
+
+use common::{Tolerance, assert_matches_golden};
+use image::{DynamicImage, Rgb, Rgb32FImage};
+use nalgebra_glm::vec3;
+use sdl3::event::Event;
+use shipyard::World;
+use vulkano_engine::assets::asset_manager::AssetManager;
+use vulkano_engine::core::application::{Application, CaptureSequence, Game};
+use vulkano_engine::core::config::EngineConfig;
+use vulkano_engine::scene::components::camera::Camera;
+use vulkano_engine::scene::components::object3d::Object3D;
+use vulkano_engine::scene::components::pointlight::Pointlight;
+use vulkano_engine::scene::components::transform::Transform;
+
+const WIDTH: u32 = 320;
+const HEIGHT: u32 = 240;
+const FRAMES: u32 = 3;
+const DT: f32 = 1.0 / 60.0;
+
+struct SoldierScene {
+    world: World,
+}
+
+impl Game for SoldierScene {
+    fn on_init(&mut self) {
+        let soldier = {
+            let mut asset_manager = self.world.get_unique::<&mut AssetManager>().unwrap();
+            asset_manager
+                .load_model("../data/models/Soldier.glb")
+                .expect("Testinis modelis turi būti įkeliamas")
+        };
+
+        let mut camera = Camera::new(vec3(0.0, 0.0, 0.0));
+        camera.yaw = std::f32::consts::FRAC_PI_2;
+        self.world
+            .add_entity((camera, Transform::with_pos(vec3(0.0, -1.0, -3.0))));
+        self.world.add_entity((
+            Transform::with_pos(vec3(0.0, 0.0, 0.0)),
+            Object3D::with_model(soldier),
+        ));
+        self.world.add_entity(Pointlight::new(
+            [0.0, -2.0, -1.5, 1.0],
+            [1.0, 1.0, 1.0],
+            5.0,
+            5.0,
+        ));
+    }
+    fn on_update(&mut self, _dt: f32) {}
+    fn on_render(&mut self) {}
+    fn on_event(&mut self, _event: &Event) {}
+    fn get_world(&self) -> &World {
+        &self.world
+    }
+    fn get_world_mut(&mut self) -> &mut World {
+        &mut self.world
+    }
+}
+
+/// Writes a small procedural sky so the test doesn't depend on a multi-megabyte HDR asset.
+fn write_test_environment() -> String {
+    let sky = Rgb32FImage::from_fn(64, 32, |_, y| {
+        let t = y as f32 / 31.0;
+        Rgb([0.4 + 0.6 * (1.0 - t), 0.6 + 0.4 * (1.0 - t), 1.0])
+    });
+    let path = std::env::temp_dir().join("vulkano_engine_golden_sky.hdr");
+    DynamicImage::ImageRgb32F(sky).save(&path).unwrap();
+    path.to_string_lossy().into_owned()
+}
+
+#[test]
+#[ignore = "no reference yet: record tests/golden/soldier_scene.png with UPDATE_GOLDEN=1 on lavapipe"]
+fn test_golden_soldier_scene() {
+    let game = SoldierScene {
+        world: World::new(),
+    };
+
+    let mut config = EngineConfig::default();
+    config.window.width = WIDTH;
+    config.window.height = HEIGHT;
+    config.environment.skybox = write_test_environment();
+
+    let mut app = Application::new_headless(game, config).unwrap();
+    app.run_frames(FRAMES, DT);
+
+    let frame = app
+        .read_frame()
+        .expect("Headless aplikacija turi grąžinti kadrą");
+    assert_matches_golden("soldier_scene", WIDTH, HEIGHT, frame, Tolerance::default());
+}
+
+#[test]
+fn test_capture_sequence_matches_rendered_frames() {
+    let game = SoldierScene {
+        world: World::new(),
+    };
+
+    let mut config = EngineConfig::default();
+    config.window.width = WIDTH;
+    config.window.height = HEIGHT;
+    config.environment.skybox = write_test_environment();
+
+    let directory = std::env::temp_dir().join("vulkano_engine_capture_sequence");
+    let _ = std::fs::remove_dir_all(&directory);
+
+    let mut app = Application::new_headless(game, config).unwrap();
+    app.capture_sequence(CaptureSequence {
+        frames: Some(2),
+        hdr: true,
+        ..CaptureSequence::new(&directory, 30.0)
+    });
+    app.run_frames(FRAMES, DT);
+
+    assert!(!app.is_capturing_sequence(), "Seka turi baigtis po 2 kadrų");
+    assert!(directory.join("frame_00001.png").exists());
+    assert!(directory.join("frame_00001.exr").exists());
+    assert!(!directory.join("frame_00002.png").exists());
+
+    let hdr = image::open(directory.join("frame_00000.exr")).unwrap();
+    assert_eq!((hdr.width(), hdr.height()), (WIDTH, HEIGHT));
+
+    // The last frame wasn't captured, so it is compared against a fresh screenshot.
+    let screenshot = directory.join("screenshot.png");
+    app.screenshot(&screenshot, None);
+    app.run_frames(1, DT);
+    let captured = image::open(&screenshot).unwrap().to_rgb8();
+    let frame: Vec<u8> = app
+        .read_frame()
+        .unwrap()
+        .chunks_exact(4)
+        .flat_map(|rgba| [rgba[0], rgba[1], rgba[2]])
+        .collect();
+    assert_eq!(
+        captured.into_raw(),
+        frame,
+        "Ekrano kopija turi sutapti su kadru"
+    );
+}
@@ -0,0 +1,282 @@
+mod common;
+
+use common::world_with_assets;
+use nalgebra_glm::vec3;
+use shipyard::{Component, Get, IntoIter, View, World};
+use vulkano_engine::assets::asset_manager::AssetManager;
+use vulkano_engine::physics::physics_engine::{ColliderComponent, RigidBodyComponent};
+use vulkano_engine::scene::components::camera::Camera;
+use vulkano_engine::scene::components::name::Name;
+use vulkano_engine::scene::components::object3d::Object3D;
+use vulkano_engine::scene::components::pointlight::Pointlight;
+use vulkano_engine::scene::components::transform::Transform;
+use vulkano_engine::scene::scene_file::{
+    SceneComponent, SceneError, SceneFields, SceneRegistry, load_scene, load_scene_str, save_scene,
+};
+
+#[test]
+fn test_scene_round_trip() {
+    let mut world = world_with_assets();
+    let soldier = world
+        .get_unique::<&mut AssetManager>()
+        .unwrap()
+        .load_model("../data/models/Soldier.glb")
+        .unwrap();
+
+    let mut transform = Transform::with_pos(vec3(1.0, -2.0, 3.5));
+    transform.scale = vec3(0.5, 1.0, 2.0);
+    transform.rotate(std::f32::consts::FRAC_PI_2, vec3(0.0, 1.0, 0.0));
+    let model_matrix = transform.model_matrix();
+    world.add_entity((
+        transform,
+        Object3D::with_model(soldier),
+        RigidBodyComponent::fixed(),
+        ColliderComponent::cuboid(1.0, 0.1, 2.0),
+    ));
+    world.add_entity(Pointlight::new(
+        [0.0, 1.5, 0.0, 1.0],
+        [1.0, 10.0, 1.0],
+        5.0,
+        4.0,
+    ));
+    let mut camera = Camera::new(vec3(0.0, -5.0, 0.0));
+    camera.yaw = 0.25;
+    world.add_entity((camera, Transform::with_pos(vec3(-5.0, -10.0, 0.0))));
+
+    let text = SceneRegistry::default().save(&world);
+    assert_eq!(text.matches("[entity]").count(), 3);
+    assert!(text.contains("object3d.model = ../data/models/Soldier.glb\n"));
+    assert!(text.contains("collider.shape = cuboid 1 0.1 2\n"));
+    assert!(text.contains("transform.scale = 0.5, 1, 2\n"));
+    assert!(text.contains("transform.scale = 1\n"));
+
+    let mut loaded = world_with_assets();
+    let entities = load_scene_str(&mut loaded, &text).unwrap();
+    assert_eq!(entities.len(), 3);
+
+    let (transforms, objects, lights, cameras, colliders) = loaded
+        .borrow::<(
+            View<Transform>,
+            View<Object3D>,
+            View<Pointlight>,
+            View<Camera>,
+            View<ColliderComponent>,
+        )>()
+        .unwrap();
+    let loaded_matrix = transforms.get(entities[0]).unwrap().model_matrix();
+    assert!(
+        (loaded_matrix - model_matrix).abs().max() < 1e-5,
+        "Transformacija turi išlikti po įrašymo ir įkėlimo"
+    );
+    let model = &objects.get(entities[0]).unwrap().model;
+    assert!(
+        loaded
+            .get_unique::<&AssetManager>()
+            .unwrap()
+            .get_model(model)
+            .is_some(),
+        "Modelis turi būti įkeltas per AssetManager"
+    );
+    let half_extents = colliders
+        .get(entities[0])
+        .unwrap()
+        .shape
+        .as_cuboid()
+        .unwrap()
+        .half_extents;
+    assert_eq!(
+        [half_extents.x, half_extents.y, half_extents.z],
+        [1.0, 0.1, 2.0]
+    );
+    assert_eq!(lights.get(entities[1]).unwrap().radius, 4.0);
+    assert_eq!(cameras.get(entities[2]).unwrap().yaw, 0.25);
+
+    // Įrašius dar kartą tekstas nesikeičia, išskyrus paskutinius sukimo skaitmenis
+    let without_rotation = |text: &str| {
+        text.lines()
+            .filter(|line| !line.starts_with("transform.rotation"))
+            .map(str::to_string)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        without_rotation(&SceneRegistry::default().save(&loaded)),
+        without_rotation(&text)
+    );
+}
+
+#[test]
+fn test_values_with_comments_and_line_breaks_round_trip() {
+    let mut world = World::new();
+    let names = [
+        "Door #2",
+        "say \"hi\"",
+        "two\nlines",
+        " padded ",
+        "back\\slash",
+    ];
+    for name in names {
+        world.add_entity(Name(name.to_string()));
+    }
+
+    let text = SceneRegistry::default().save(&world);
+    assert!(text.contains("name.value = \"Door #2\"\n"), "{text}");
+    assert!(text.contains("name.value = back\\slash\n"), "{text}");
+
+    let mut loaded = World::new();
+    let entities = load_scene_str(&mut loaded, &text).unwrap();
+    let loaded_names = loaded.borrow::<View<Name>>().unwrap();
+    for (entity, name) in entities.iter().zip(names) {
+        assert_eq!(loaded_names.get(*entity).unwrap().0, name);
+    }
+
+    // Kabutėse esantis `#` nėra komentaras, o už jų esantis yra
+    let mut world = World::new();
+    let entities = load_scene_str(
+        &mut world,
+        "[entity]\nname.value = \"a # b\" # komentaras\n",
+    )
+    .unwrap();
+    assert_eq!(
+        world
+            .borrow::<View<Name>>()
+            .unwrap()
+            .get(entities[0])
+            .unwrap()
+            .0,
+        "a # b"
+    );
+}
+
+#[test]
+fn test_missing_fields_use_defaults() {
+    let mut world = World::new();
+    let entities = load_scene_str(
+        &mut world,
+        "
+        [entity]
+        transform.position = 0, 2, 0   # kiti laukai numatytieji
+        rigid_body
+        collider.shape = capsule_y 1 0.5
+        no_interpolation
+        ",
+    )
+    .unwrap();
+
+    let (transforms, bodies) = world
+        .borrow::<(View<Transform>, View<RigidBodyComponent>)>()
+        .unwrap();
+    let transform = transforms.get(entities[0]).unwrap();
+    assert_eq!(transform.get_position_vector(), [0.0, 2.0, 0.0]);
+    assert_eq!(transform.get_scale_vector(), [1.0; 3]);
+    assert_eq!(
+        bodies.get(entities[0]).unwrap().body_type,
+        rapier3d::prelude::RigidBodyType::Dynamic
+    );
+}
+
+#[derive(Component, Debug, PartialEq)]
+struct Health {
+    current: f32,
+    max: f32,
+}
+
+impl SceneComponent for Health {
+    const NAME: &'static str = "health";
+
+    fn save(&self, fields: &mut SceneFields) -> Result<(), String> {
+        fields.set("current", self.current);
+        fields.set("max", self.max);
+        Ok(())
+    }
+
+    fn load(fields: &mut SceneFields, _world: &World) -> Result<Self, String> {
+        let max = fields.take("max")?.unwrap_or(100.0);
+        Ok(Health {
+            current: fields.take("current")?.unwrap_or(max),
+            max,
+        })
+    }
+}
+
+fn world_with_health() -> World {
+    let world = World::new();
+    let mut registry = SceneRegistry::with_engine_components();
+    registry.register::<Health>();
+    world.add_unique(registry);
+    world
+}
+
+#[test]
+fn test_game_components_in_scene_file() {
+    let mut world = world_with_health();
+    world.add_entity((
+        Transform::with_pos(vec3(1.0, 0.0, 0.0)),
+        Health {
+            current: 40.0,
+            max: 50.0,
+        },
+    ));
+
+    let path =
+        std::env::temp_dir().join(format!("vulkano_engine_scene_{}.scene", std::process::id()));
+    save_scene(&world, &path).unwrap();
+
+    let mut loaded = world_with_health();
+    let entities = load_scene(&mut loaded, &path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let health = loaded.borrow::<View<Health>>().unwrap();
+    assert_eq!(
+        *health.get(entities[0]).unwrap(),
+        Health {
+            current: 40.0,
+            max: 50.0
+        }
+    );
+    assert!(
+        loaded.get_unique::<&SceneRegistry>().is_ok(),
+        "Registras turi likti pasaulyje"
+    );
+}
+
+#[test]
+fn test_scene_errors_report_line_and_add_nothing() {
+    let mut world = world_with_health();
+    let errors = [
+        (
+            "[entity]\npointlight.radius = 2\n[entity]\nhealth.armor = 3\n",
+            4,
+            "health.armor",
+        ),
+        (
+            "[entity]\npointlight\n\n[entity]\nmana.current = 3\n",
+            5,
+            "mana",
+        ),
+        ("[entity]\ncollider.shape = torus 1 2\n", 2, "torus"),
+        ("[entity]\n\nname.value = \"open\n", 3, "closing quote"),
+        ("pointlight.radius = 2\n", 1, "[entity]"),
+        (
+            "[entity]\nobject3d.model = missing.glb\n",
+            2,
+            "AssetManager",
+        ),
+    ];
+
+    for (text, expected_line, expected_message) in errors {
+        match load_scene_str(&mut world, text) {
+            Err(SceneError::Parse { line, message }) => {
+                assert_eq!(line, expected_line, "{text}");
+                assert!(message.contains(expected_message), "{message}");
+            }
+            other => panic!("Tikėtasi klaidos, gauta {other:?}"),
+        }
+    }
+
+    let lights = world.borrow::<View<Pointlight>>().unwrap();
+    assert_eq!(
+        lights.iter().count(),
+        0,
+        "Nepavykus įkelti neturi būti pridėta jokių esybių"
+    );
+}
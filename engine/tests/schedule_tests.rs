@@ -0,0 +1,127 @@
+use shipyard::{SystemModificator, Unique, UniqueViewMut, World};
+use vulkano_engine::core::schedule::{Schedule, Stage};
+
+#[derive(Unique, Default)]
+struct Log(Vec<&'static str>);
+
+fn physics(mut log: UniqueViewMut<Log>) {
+    log.0.push("physics");
+}
+
+fn steering(mut log: UniqueViewMut<Log>) {
+    log.0.push("steering");
+}
+
+fn animation(mut log: UniqueViewMut<Log>) {
+    log.0.push("animation");
+}
+
+fn gameplay(mut log: UniqueViewMut<Log>) {
+    log.0.push("gameplay");
+}
+
+#[test]
+fn test_schedule_runs_systems_in_stage() {
+    let world = World::new();
+    world.add_unique(Log::default());
+
+    let mut schedule = Schedule::new();
+    schedule
+        .add_system(Stage::PostUpdate, animation)
+        .add_system(Stage::Update, gameplay);
+    schedule.add_to_world(&world).unwrap();
+
+    Schedule::run(&world, Stage::Update);
+    Schedule::run(&world, Stage::PostUpdate);
+
+    assert_eq!(
+        world.get_unique::<&Log>().unwrap().0,
+        ["gameplay", "animation"],
+        "Sistemos turi vykti pagal etapus"
+    );
+}
+
+#[test]
+fn test_schedule_ordering_constraints() {
+    let world = World::new();
+    world.add_unique(Log::default());
+
+    let mut schedule = Schedule::new();
+    schedule
+        .add_system(Stage::FixedUpdate, physics)
+        .add_system(Stage::FixedUpdate, steering.before_all(physics));
+    schedule.add_to_world(&world).unwrap();
+
+    Schedule::run(&world, Stage::FixedUpdate);
+
+    assert_eq!(
+        world.get_unique::<&Log>().unwrap().0,
+        ["steering", "physics"],
+        "before_all apribojimas nebuvo įvykdytas"
+    );
+}
+
+#[test]
+fn test_empty_stages_run() {
+    let world = World::new();
+    Schedule::new().add_to_world(&world).unwrap();
+
+    for stage in Stage::ALL {
+        Schedule::run(&world, stage);
+    }
+}
+
+#[derive(Unique, Default)]
+struct Counter(u32);
+
+fn count(mut counter: UniqueViewMut<Counter>) {
+    counter.0 += 1;
+}
+
+/// The position of the batch `system` runs in within its stage.
+fn batch_of(world: &World, system: &str) -> usize {
+    world
+        .workloads_info()
+        .0
+        .values()
+        .find_map(|workload| {
+            workload.batch_info.iter().position(|batch| {
+                batch
+                    .systems()
+                    .any(|info| info.name.rsplit("::").next() == Some(system))
+            })
+        })
+        .unwrap_or_else(|| panic!("Plane nėra sistemos {system}"))
+}
+
+#[test]
+fn test_batch_plan_lists_systems() {
+    let world = World::new();
+    world.add_unique(Log::default());
+    world.add_unique(Counter::default());
+
+    let mut schedule = Schedule::new();
+    schedule
+        .add_system(Stage::Update, gameplay)
+        .add_system(Stage::Update, count)
+        .add_system(Stage::Update, steering);
+    schedule.add_to_world(&world).unwrap();
+
+    let plan = Schedule::batch_plan(&world);
+    assert!(plan.contains("gameplay"), "Plane trūksta sistemos: {plan}");
+    assert!(plan.contains("count"), "Plane trūksta sistemos: {plan}");
+
+    // `count` skolinasi kitą unikalų komponentą nei `gameplay`, o `steering` – tą patį `Log`
+    assert_eq!(
+        batch_of(&world, "gameplay"),
+        batch_of(&world, "count"),
+        "Nekonfliktuojančios sistemos turi būti vienoje grupėje: {plan}"
+    );
+    assert!(
+        batch_of(&world, "steering") > batch_of(&world, "gameplay"),
+        "Konfliktuojančios sistemos turi būti skirtingose grupėse: {plan}"
+    );
+
+    Schedule::run(&world, Stage::Update);
+    assert_eq!(world.get_unique::<&Counter>().unwrap().0, 1);
+}
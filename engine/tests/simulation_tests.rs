@@ -0,0 +1,232 @@
+use std::sync::{Arc, Mutex};
+
+use nalgebra_glm::vec3;
+use rapier3d::math::Vector;
+use rapier3d::prelude::{RigidBodyType, SharedShape};
+use sdl3::event::Event;
+use shipyard::{
+    EntityId, Get, IntoIter, SystemModificator, Unique, UniqueView, UniqueViewMut, View, ViewMut,
+    World,
+};
+use vulkano_engine::core::application::Game;
+use vulkano_engine::core::config::EngineConfig;
+use vulkano_engine::core::schedule::{Schedule, Stage};
+use vulkano_engine::core::simulation::Simulation;
+use vulkano_engine::core::state::AppControl;
+use vulkano_engine::physics::interpolation::NoInterpolation;
+use vulkano_engine::physics::physics_engine::{
+    ColliderComponent, KinematicCharacterComponent, RigidBodyComponent, physics_kinematic,
+};
+use vulkano_engine::scene::components::fixed_time::FixedTime;
+use vulkano_engine::scene::components::transform::Transform;
+
+/// Exactly representable, so a tick of the same length runs exactly one physics step.
+const STEP: f32 = 1.0 / 64.0;
+
+#[derive(Unique, Default)]
+struct Steps(u32);
+
+fn count_steps(mut steps: UniqueViewMut<Steps>) {
+    steps.0 += 1;
+}
+
+/// Drops a ball and quits after `quit_after` frames, if set.
+struct BallGame {
+    world: World,
+    ball: Option<EntityId>,
+    frames: u32,
+    quit_after: Option<u32>,
+    /// Shutdowns, shared so they can be checked after the drop.
+    shutdowns: Arc<Mutex<u32>>,
+}
+
+impl BallGame {
+    fn new() -> Self {
+        Self {
+            world: World::new(),
+            ball: None,
+            frames: 0,
+            quit_after: None,
+            shutdowns: Arc::default(),
+        }
+    }
+}
+
+impl Game for BallGame {
+    fn on_init(&mut self) {
+        self.world.add_unique(Steps::default());
+        self.ball = Some(self.world.add_entity((
+            Transform::with_pos(vec3(0.0, 0.0, 0.0)),
+            RigidBodyComponent::dynamic(),
+            ColliderComponent::ball(0.5),
+            NoInterpolation,
+        )));
+    }
+    fn on_update(&mut self, _dt: f32) {
+        self.frames += 1;
+        if self.quit_after == Some(self.frames) {
+            self.world.get_unique::<&mut AppControl>().unwrap().quit();
+        }
+    }
+    fn on_render(&mut self) {}
+    fn on_event(&mut self, _event: &Event) {}
+    fn on_shutdown(&mut self) {
+        *self.shutdowns.lock().unwrap() += 1;
+    }
+    fn build_schedule(&mut self, schedule: &mut Schedule) {
+        schedule.add_system(Stage::FixedUpdate, count_steps);
+    }
+    fn get_world(&self) -> &World {
+        &self.world
+    }
+    fn get_world_mut(&mut self) -> &mut World {
+        &mut self.world
+    }
+}
+
+fn config() -> EngineConfig {
+    let mut config = EngineConfig::default();
+    config.physics.fixed_dt = STEP;
+    config
+}
+
+fn steps(simulation: &Simulation<BallGame>) -> u32 {
+    simulation
+        .game
+        .get_world()
+        .get_unique::<&Steps>()
+        .unwrap()
+        .0
+}
+
+#[test]
+fn test_physics_runs_without_renderer() {
+    let mut simulation = Simulation::new(BallGame::new(), config());
+
+    assert_eq!(simulation.run_ticks(64, STEP), 64);
+    assert_eq!(steps(&simulation), 64);
+
+    // Rendering Y points down, so falling increases it
+    let transforms = simulation
+        .game
+        .get_world()
+        .borrow::<View<Transform>>()
+        .unwrap();
+    let y = transforms
+        .get(simulation.game.ball.unwrap())
+        .unwrap()
+        .get_position_vector()[1];
+    assert!(y > 1.0, "Kamuolys turėjo nukristi, y = {y}");
+}
+
+#[test]
+fn test_fixed_steps_follow_manual_clock() {
+    let mut simulation = Simulation::new(BallGame::new(), config());
+
+    simulation.run_ticks(9, STEP / 2.0);
+    assert_eq!(steps(&simulation), 4);
+
+    let fixed_time = *simulation
+        .game
+        .get_world()
+        .get_unique::<&FixedTime>()
+        .unwrap();
+    assert_eq!(fixed_time.step, STEP);
+    assert_eq!(fixed_time.alpha, 0.5);
+
+    simulation.step(STEP / 2.0);
+    assert_eq!(steps(&simulation), 5);
+}
+
+#[test]
+fn test_quit_stops_ticks_and_shuts_down_once() {
+    let mut game = BallGame::new();
+    game.quit_after = Some(3);
+    let shutdowns = game.shutdowns.clone();
+
+    let mut simulation = Simulation::new(game, config());
+    assert_eq!(simulation.run_ticks(10, STEP), 3);
+    assert!(simulation.is_quit_requested());
+
+    simulation.shutdown();
+    drop(simulation);
+    assert_eq!(
+        *shutdowns.lock().unwrap(),
+        1,
+        "on_shutdown turi būti kviečiamas kartą"
+    );
+}
+
+/// Walks a kinematic character along +X while it falls onto a floor.
+struct CharacterGame {
+    world: World,
+    character: Option<EntityId>,
+}
+
+impl Game for CharacterGame {
+    fn on_init(&mut self) {
+        self.world.add_entity((
+            Transform::with_pos(vec3(0.0, 0.0, 0.0)),
+            RigidBodyComponent::fixed(),
+            ColliderComponent::cuboid(50.0, 0.1, 50.0),
+        ));
+        self.character = Some(self.world.add_entity((
+            Transform::with_pos(vec3(0.0, -3.0, 0.0)),
+            KinematicCharacterComponent::new(),
+            RigidBodyComponent::new(RigidBodyType::KinematicVelocityBased),
+            ColliderComponent::new(SharedShape::capsule_y(1.0, 0.5)),
+            NoInterpolation,
+        )));
+    }
+    fn on_update(&mut self, _dt: f32) {}
+    fn on_render(&mut self) {}
+    fn on_event(&mut self, _event: &Event) {}
+    fn build_schedule(&mut self, schedule: &mut Schedule) {
+        schedule.add_system(Stage::FixedUpdate, walk.before_all(physics_kinematic));
+    }
+    fn get_world(&self) -> &World {
+        &self.world
+    }
+    fn get_world_mut(&mut self) -> &mut World {
+        &mut self.world
+    }
+}
+
+/// 2 units per second along +X and 5 down, in physics space (+Y up).
+fn walk(mut characters: ViewMut<KinematicCharacterComponent>, fixed_time: UniqueView<FixedTime>) {
+    for character in (&mut characters).iter() {
+        character.desired_movement = Vector::new(2.0, -5.0, 0.0) * fixed_time.step;
+    }
+}
+
+#[test]
+fn test_kinematic_character_walks_on_floor() {
+    let game = CharacterGame {
+        world: World::new(),
+        character: None,
+    };
+    let mut simulation = Simulation::new(game, config());
+    simulation.run_ticks(64, STEP);
+
+    let character = simulation.game.character.unwrap();
+    let (characters, transforms) = simulation
+        .game
+        .get_world()
+        .borrow::<(View<KinematicCharacterComponent>, View<Transform>)>()
+        .unwrap();
+    assert!(
+        characters.get(character).unwrap().grounded,
+        "Veikėjas turi stovėti ant grindų"
+    );
+
+    // Kapsulės centras virš grindų: 0.1 + 1.0 + 0.5, o atvaizdavime Y nukreipta žemyn
+    let [x, y, _] = transforms.get(character).unwrap().get_position_vector();
+    assert!(
+        (x - 2.0).abs() < 0.1,
+        "Veikėjas turėjo nueiti 2 vienetus, x = {x}"
+    );
+    assert!(
+        (-1.8..=-1.6).contains(&y),
+        "Veikėjas neturi prasmegti pro grindis, y = {y}"
+    );
+}
@@ -0,0 +1,279 @@
+use shipyard::{Unique, UniqueViewMut, World};
+use vulkano_engine::core::schedule::{Schedule, Stage};
+use vulkano_engine::core::state::{AppControl, GameState, StateStack};
+
+#[derive(Unique, Default)]
+struct Log(Vec<String>);
+
+fn log(world: &World, entry: impl Into<String>) {
+    world.get_unique::<&mut Log>().unwrap().0.push(entry.into());
+}
+
+fn take_log(world: &World) -> Vec<String> {
+    std::mem::take(&mut world.get_unique::<&mut Log>().unwrap().0)
+}
+
+fn world() -> World {
+    let world = World::new();
+    world.add_unique(Log::default());
+    world.add_unique(AppControl::default());
+    world
+}
+
+struct Level;
+impl GameState for Level {
+    fn on_enter(&mut self, world: &mut World) {
+        log(world, "level enter");
+    }
+    fn on_exit(&mut self, world: &mut World) {
+        log(world, "level exit");
+    }
+    fn on_pause(&mut self, world: &mut World) {
+        log(world, "level pause");
+    }
+    fn on_resume(&mut self, world: &mut World) {
+        log(world, "level resume");
+    }
+    fn on_update(&mut self, world: &mut World, _delta_time: f32) {
+        log(world, "level update");
+    }
+    fn build_schedule(&mut self, schedule: &mut Schedule) {
+        schedule.add_system(Stage::Update, level_system);
+    }
+}
+
+fn level_system(mut log: UniqueViewMut<Log>) {
+    log.0.push("level system".into());
+}
+
+struct Pause;
+impl GameState for Pause {
+    fn on_enter(&mut self, world: &mut World) {
+        log(world, "pause enter");
+    }
+    fn on_exit(&mut self, world: &mut World) {
+        log(world, "pause exit");
+    }
+    fn on_update(&mut self, world: &mut World, _delta_time: f32) {
+        log(world, "pause update");
+    }
+}
+
+struct Menu;
+impl GameState for Menu {
+    fn on_enter(&mut self, world: &mut World) {
+        log(world, "menu enter");
+        // Starting the game straight from the menu's enter hook
+        world
+            .get_unique::<&mut AppControl>()
+            .unwrap()
+            .replace(Level);
+    }
+    fn on_exit(&mut self, world: &mut World) {
+        log(world, "menu exit");
+    }
+}
+
+fn no_reset(_world: &mut World) {
+    panic!("Pasaulis neturėjo būti atstatytas");
+}
+
+#[test]
+fn test_push_pauses_and_pop_resumes() {
+    let mut world = world();
+    let mut states = StateStack::new();
+
+    world.get_unique::<&mut AppControl>().unwrap().push(Level);
+    let changes = states.apply_pending(&mut world, no_reset);
+    assert_eq!(changes.entered.len(), 1);
+    assert!(!changes.reset);
+
+    world.get_unique::<&mut AppControl>().unwrap().push(Pause);
+    states.apply_pending(&mut world, no_reset);
+    assert_eq!(states.names().len(), 2);
+    assert_eq!(states.current(), Some(std::any::type_name::<Pause>()));
+
+    states.update(&mut world, 0.016);
+
+    world.get_unique::<&mut AppControl>().unwrap().pop();
+    states.apply_pending(&mut world, no_reset);
+    states.update(&mut world, 0.016);
+
+    assert_eq!(
+        take_log(&world),
+        [
+            "level enter",
+            "level pause",
+            "pause enter",
+            "pause update",
+            "pause exit",
+            "level resume",
+            "level update",
+        ],
+        "Būsenų kablių eiliškumas neteisingas"
+    );
+}
+
+#[test]
+fn test_state_systems_run_only_while_on_top() {
+    let mut world = world();
+    let mut states = StateStack::new();
+
+    world.get_unique::<&mut AppControl>().unwrap().push(Level);
+    states.apply_pending(&mut world, no_reset);
+    take_log(&world);
+
+    states.run_stage(&world, Stage::Update);
+    assert_eq!(take_log(&world), ["level system"]);
+
+    world.get_unique::<&mut AppControl>().unwrap().push(Pause);
+    states.apply_pending(&mut world, no_reset);
+    take_log(&world);
+    states.run_stage(&world, Stage::Update);
+    assert!(
+        take_log(&world).is_empty(),
+        "Pristabdytos būsenos sistemos neturėjo vykti"
+    );
+
+    // Entering a state of the same type again reuses its workloads
+    world
+        .get_unique::<&mut AppControl>()
+        .unwrap()
+        .replace(Level);
+    states.apply_pending(&mut world, no_reset);
+    take_log(&world);
+    states.run_stage(&world, Stage::Update);
+    assert_eq!(take_log(&world), ["level system"]);
+}
+
+#[test]
+fn test_transitions_requested_by_hooks_are_applied() {
+    let mut world = world();
+    let mut states = StateStack::new();
+
+    world.get_unique::<&mut AppControl>().unwrap().push(Menu);
+    let changes = states.apply_pending(&mut world, no_reset);
+
+    assert_eq!(states.names(), [std::any::type_name::<Level>()]);
+    assert_eq!(changes.entered.len(), 2);
+    assert_eq!(take_log(&world), ["menu enter", "menu exit", "level enter"]);
+}
+
+#[test]
+fn test_switch_exits_all_states_and_resets_world() {
+    let mut world = world();
+    let mut states = StateStack::new();
+
+    {
+        let mut control = world.get_unique::<&mut AppControl>().unwrap();
+        control.push(Level);
+        control.push(Pause);
+    }
+    states.apply_pending(&mut world, no_reset);
+    take_log(&world);
+
+    world
+        .get_unique::<&mut AppControl>()
+        .unwrap()
+        .switch_to(Level);
+    let changes = states.apply_pending(&mut world, |world| {
+        log(world, "reset");
+    });
+
+    assert!(changes.reset);
+    assert_eq!(states.names(), [std::any::type_name::<Level>()]);
+    assert_eq!(
+        take_log(&world),
+        ["pause exit", "level exit", "reset", "level enter"]
+    );
+}
+
+#[test]
+fn test_popping_last_state_requests_quit() {
+    let mut world = world();
+    let mut states = StateStack::new();
+
+    world.get_unique::<&mut AppControl>().unwrap().push(Pause);
+    states.apply_pending(&mut world, no_reset);
+    assert!(
+        !world
+            .get_unique::<&AppControl>()
+            .unwrap()
+            .is_quit_requested()
+    );
+
+    world.get_unique::<&mut AppControl>().unwrap().pop();
+    states.apply_pending(&mut world, no_reset);
+
+    assert!(states.is_empty());
+    assert!(
+        world
+            .get_unique::<&AppControl>()
+            .unwrap()
+            .is_quit_requested(),
+        "Išėmus paskutinę būseną programa turi baigtis"
+    );
+}
+
+#[test]
+fn test_exit_all_exits_top_first() {
+    let mut world = world();
+    let mut states = StateStack::new();
+
+    {
+        let mut control = world.get_unique::<&mut AppControl>().unwrap();
+        control.push(Level);
+        control.push(Pause);
+    }
+    states.apply_pending(&mut world, no_reset);
+    take_log(&world);
+
+    states.exit_all(&mut world);
+
+    assert!(states.is_empty());
+    assert_eq!(take_log(&world), ["pause exit", "level exit"]);
+}
+
+struct Editor {
+    saved: bool,
+}
+impl GameState for Editor {
+    fn on_quit_requested(&mut self, world: &mut World) -> bool {
+        log(world, "editor asked");
+        self.saved
+    }
+}
+
+#[test]
+fn test_states_can_refuse_to_quit() {
+    let mut world = world();
+    let mut states = StateStack::new();
+
+    {
+        let mut control = world.get_unique::<&mut AppControl>().unwrap();
+        control.push(Editor { saved: false });
+        control.push(Pause);
+    }
+    states.apply_pending(&mut world, no_reset);
+    take_log(&world);
+
+    assert!(!states.confirm_quit(&mut world));
+    assert_eq!(take_log(&world), ["editor asked"]);
+
+    world.get_unique::<&mut AppControl>().unwrap().pop();
+    world
+        .get_unique::<&mut AppControl>()
+        .unwrap()
+        .replace(Editor { saved: true });
+    states.apply_pending(&mut world, no_reset);
+    assert!(states.confirm_quit(&mut world));
+}
+
+#[test]
+fn test_quit_request_can_be_cancelled() {
+    let mut control = AppControl::default();
+    control.quit();
+    assert!(control.is_quit_requested());
+    control.cancel_quit();
+    assert!(!control.is_quit_requested());
+}
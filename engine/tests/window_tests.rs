@@ -0,0 +1,58 @@
+use vulkano::swapchain::PresentMode;
+use vulkano_engine::core::config::EngineConfig;
+use vulkano_engine::core::window::{WindowMode, WindowSettings, choose_present_mode};
+
+#[test]
+fn test_supported_present_mode_is_kept() {
+    let supported = [
+        PresentMode::Fifo,
+        PresentMode::Mailbox,
+        PresentMode::Immediate,
+    ];
+    for mode in supported {
+        assert_eq!(choose_present_mode(mode, &supported), mode);
+    }
+}
+
+#[test]
+fn test_immediate_falls_back_to_mailbox() {
+    let supported = [PresentMode::Fifo, PresentMode::Mailbox];
+    assert_eq!(
+        choose_present_mode(PresentMode::Immediate, &supported),
+        PresentMode::Mailbox
+    );
+}
+
+#[test]
+fn test_unsupported_modes_fall_back_to_fifo() {
+    let supported = [PresentMode::Fifo];
+    for mode in [
+        PresentMode::Immediate,
+        PresentMode::Mailbox,
+        PresentMode::FifoRelaxed,
+    ] {
+        assert_eq!(
+            choose_present_mode(mode, &supported),
+            PresentMode::Fifo,
+            "Nepalaikomas režimas {mode:?} turi tapti FIFO"
+        );
+    }
+    // FIFO is guaranteed even if the driver forgot to list it
+    assert_eq!(
+        choose_present_mode(PresentMode::Mailbox, &[]),
+        PresentMode::Fifo
+    );
+}
+
+#[test]
+fn test_window_settings_from_config() {
+    let mut config = EngineConfig::default();
+    config.set("window.mode", "fullscreen").unwrap();
+    config.set("window.width", "1280").unwrap();
+    config.set("renderer.present_mode", "vsync").unwrap();
+
+    let settings = WindowSettings::from_config(&config);
+    assert_eq!(settings.mode, WindowMode::Fullscreen);
+    assert_eq!((settings.width, settings.height), (1280, 1080));
+    assert_eq!(settings.present_mode, PresentMode::Fifo);
+}
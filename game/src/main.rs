@@ -1,12 +1,62 @@
 mod my_game;
 mod player;
 mod soldier;
-use vulkano_engine::core::application::Application;
+use vulkano_engine::core::application::{Application, CaptureSequence};
+use vulkano_engine::core::config::EngineConfig;
+use vulkano_engine::core::log;
+use vulkano_engine::input::recording::InputRecording;
+use vulkano_engine::{error, warn};
 
 use crate::my_game::MyApp;
 
 fn main() {
     let client = MyApp::new();
-    let app = Application::new(client, "SDL3 + Vulkano", 1920, 1080);
+    let config = EngineConfig::load("engine.cfg").unwrap_or_else(|e| {
+        warn!("{e}, using default settings");
+        EngineConfig::default()
+    });
+    if let Err(e) = log::init(&config.log) {
+        warn!("Can't open the log file: {e}");
+    }
+    let mut app = Application::new(client, config).unwrap_or_else(|e| {
+        error!("{e}");
+        log::flush();
+        std::process::exit(1);
+    });
+
+    // `--record <file>` saves this session's input, `--replay <file>` plays one back,
+    // `--capture <dir>` writes every frame to an image sequence at 60 fps
+    let args: Vec<String> = std::env::args().collect();
+    #[cfg(feature = "profiler")]
+    let mut profile_path = None;
+    for pair in args[1..].windows(2) {
+        match pair[0].as_str() {
+            "--record" => app
+                .record_input(&pair[1])
+                .unwrap_or_else(|e| panic!("Failed to create {}: {e}", pair[1])),
+            "--replay" => app.replay_input(
+                InputRecording::load(&pair[1])
+                    .unwrap_or_else(|e| panic!("Failed to load {}: {e}", pair[1])),
+            ),
+            "--capture" => app.capture_sequence(CaptureSequence::new(&pair[1], 60.0)),
+            // Built with `--features profiler`: write a Chrome trace of the session on exit
+            #[cfg(feature = "profiler")]
+            "--profile" => profile_path = Some(pair[1].clone()),
+            _ => {}
+        }
+    }
+
     app.run();
+
+    #[cfg(feature = "profiler")]
+    if let Some(path) = profile_path {
+        use vulkano_engine::core::profiler;
+        use vulkano_engine::info;
+
+        info!("Frame profile:\n{}", profiler::report());
+        profiler::write_chrome_trace(&path)
+            .unwrap_or_else(|e| panic!("Failed to write {path}: {e}"));
+    }
+
+    log::flush();
 }
@@ -8,7 +8,11 @@ use shipyard::IntoIter;
 use shipyard::UniqueView;
 use shipyard::World;
 use shipyard::{View, ViewMut};
-use vulkano_engine::assets::asset_manager::AssetManager;
+use vulkano_engine::assets::asset_manager::{AssetHandle, AssetManager};
+use vulkano_engine::core::cvars::CVarRegistry;
+use vulkano_engine::core::schedule::Schedule;
+use vulkano_engine::core::state::AppControl;
+use vulkano_engine::core::window::{WindowMode, WindowSettings};
 use vulkano_engine::input::input_manager::InputManager;
 use vulkano_engine::physics::physics_engine::ColliderComponent;
 use vulkano_engine::physics::physics_engine::KinematicCharacterComponent;
@@ -18,6 +22,9 @@ use vulkano_engine::scene::components::animator::Animator;
 use vulkano_engine::scene::components::delta_time::DeltaTime;
 use vulkano_engine::scene::components::directional_light::DirectionalLight;
 use vulkano_engine::scene::components::object3d::Object3D;
+use vulkano_engine::scene::prefab::{self, PrefabLibrary, PrefabOverrides};
+use vulkano_engine::scene::scene_file::SceneRegistry;
+use vulkano_engine::{debug, info, warn};
 
 use vulkano_engine::{
     core::application::Game,
@@ -42,64 +49,48 @@ impl MyApp {
 
 impl Game for MyApp {
     fn on_init(&mut self) {
-        println!("initialized!");
+        info!("initialized!");
 
-        let horse = {
-            let mut asset_manager = self.world.get_unique::<&mut AssetManager>().unwrap();
-            asset_manager.load_model("data/models/Running_Horse_Animated.glb")
-        };
+        player::register_player_cvars(&mut self.world.get_unique::<&mut CVarRegistry>().unwrap());
+        self.world
+            .get_unique::<&mut SceneRegistry>()
+            .unwrap()
+            .register::<Player>()
+            .register::<Soldier>();
+
+        let horse = load_model(&self.world, "data/models/Running_Horse_Animated.glb");
 
         let horse_animator = {
             let mut asset_manager = self.world.get_unique::<&mut AssetManager>().unwrap();
-            asset_manager.create_animator(&horse)
+            horse
+                .as_ref()
+                .and_then(|model| asset_manager.create_animator(model))
         };
 
         // debug: show what clips were loaded
         match &horse_animator {
             Some(anim) => {
-                println!("[Animator] {} clip(s) loaded:", anim.clips.len());
+                debug!("[Animator] {} clip(s) loaded:", anim.clips.len());
                 for (i, clip) in anim.clips.iter().enumerate() {
-                    println!("  [{}] {:?}  ({:.2}s)", i, clip.name, clip.duration);
+                    debug!("  [{}] {:?}  ({:.2}s)", i, clip.name, clip.duration);
                 }
             }
-            None => println!("[Animator] create_animator returned None — model has no skin!"),
+            None => warn!("[Animator] create_animator returned None — model has no skin!"),
         }
 
-        let soldier = {
-            let mut asset_manager = self.world.get_unique::<&mut AssetManager>().unwrap();
-            asset_manager.load_model("data/models/SoldierRe.glb")
-        };
-
-        let soldier_animator = {
-            let mut asset_manager = self.world.get_unique::<&mut AssetManager>().unwrap();
-            asset_manager.create_animator(&soldier)
-        };
-
-        // debug: show what clips were loaded
-        match &soldier_animator {
-            Some(anim) => {
-                println!("[Animator] {} clip(s) loaded:", anim.clips.len());
-                for (i, clip) in anim.clips.iter().enumerate() {
-                    println!("  [{}] {:?}  ({:.2}s)", i, clip.name, clip.duration);
-                }
-            }
-            None => println!("[Animator] create_animator returned None — model has no skin!"),
-        }
+        self.world
+            .get_unique::<&mut PrefabLibrary>()
+            .unwrap()
+            .insert("soldier", soldier::prefab());
 
-        let sponza = {
-            let mut asset_manager = self.world.get_unique::<&mut AssetManager>().unwrap();
-            asset_manager.load_model("data/models/sponza_atrium_3.glb")
-        };
+        let sponza = load_model(&self.world, "data/models/sponza_atrium_3.glb");
 
         // let bistro = {
         //     let mut asset_manager = self.world.get_unique::<&mut AssetManager>().unwrap();
         //     asset_manager.load_model("data/models/Bistro_Godot.glb")
         // };
 
-        let dragon = {
-            let mut asset_manager = self.world.get_unique::<&mut AssetManager>().unwrap();
-            asset_manager.load_model("data/models/stanford_dragon_pbr.glb")
-        };
+        let dragon = load_model(&self.world, "data/models/stanford_dragon_pbr.glb");
 
         let player_entity = self.world.add_entity((
             Player::new(),
@@ -121,29 +112,27 @@ impl Game for MyApp {
         // //
         //main scene
         // //
-        let soldier_entity = &self.world.add_entity((
-            //Transform::with_pos_scale(vec3(0.0, -50.0, 0.0), 0.0125),
-            //Object3D::with_model(dragon.clone()),
-            Soldier::new(),
-            Transform::with_pos(vec3(-9.0, 0.0, 0.0)),
-            Object3D::with_model(soldier.clone()),
-            //RigidBodyComponent::new(RigidBodyType::Dynamic),
-            //ColliderComponent::new(SharedShape::ball(0.45)),
-            //ColliderComponent::new(SharedShape::capsule_z(0.2, 0.1)),
-            soldier_animator.unwrap(),
-        ));
+        // Models that failed to load are left out of the scene
+        let at = PrefabOverrides::new().position(vec3(-9.0, 0.0, 0.0));
+        if let Err(e) = prefab::instantiate(&mut self.world, "soldier", at) {
+            warn!("{e}, skipping the soldier");
+        }
 
-        let dragon_entity = &self.world.add_entity((
-            Transform::with_pos_scale(vec3(0.0, -50.0, 0.0), 0.0125),
-            Object3D::with_model(dragon.clone()),
-            RigidBodyComponent::new(RigidBodyType::Dynamic),
-            ColliderComponent::new(SharedShape::ball(0.45)),
-        ));
+        if let Some(dragon) = &dragon {
+            self.world.add_entity((
+                Transform::with_pos_scale(vec3(0.0, -50.0, 0.0), 0.0125),
+                Object3D::with_model(dragon.clone()),
+                RigidBodyComponent::new(RigidBodyType::Dynamic),
+                ColliderComponent::new(SharedShape::ball(0.45)),
+            ));
+        }
 
-        let sponza_scene = &self.world.add_entity((
-            Transform::with_pos(vec3(0.0, 0.0, 0.0)),
-            Object3D::with_model(sponza.clone()),
-        ));
+        if let Some(sponza) = &sponza {
+            self.world.add_entity((
+                Transform::with_pos(vec3(0.0, 0.0, 0.0)),
+                Object3D::with_model(sponza.clone()),
+            ));
+        }
 
         let pointlight = &self.world.add_entity(Pointlight::new(
             [0.0, 1.5, 0.0, 1.0],
@@ -175,19 +164,63 @@ impl Game for MyApp {
     fn on_update(&mut self, _delta_time: f32) {
         //println!("Updating game logic: {delta_time}s");
 
+        // Hotkeys read the keys pressed this frame from InputManager
+        let just_pressed = self
+            .world
+            .get_unique::<&InputManager>()
+            .unwrap()
+            .just_pressed_keys
+            .clone();
+
+        for keycode in just_pressed {
+            match keycode {
+                Keycode::P => {
+                    self.world.run(player::interact::pointlight_toggle);
+                }
+                Keycode::I => {
+                    self.world
+                        .run(player::interact::rotate_directional_light_left);
+                }
+                Keycode::O => {
+                    self.world
+                        .run(player::interact::rotate_directional_light_right);
+                }
+                Keycode::L => {
+                    self.world.run(animate_soldier_walk);
+                }
+                Keycode::K => {
+                    self.world.run(animate_soldier_run);
+                }
+                Keycode::F8 => {
+                    self.world.get_unique::<&mut AppControl>().unwrap().quit();
+                }
+                Keycode::F11 => {
+                    let mut window = self.world.get_unique::<&mut WindowSettings>().unwrap();
+                    window.mode = match window.mode {
+                        WindowMode::Windowed => WindowMode::Borderless,
+                        _ => WindowMode::Windowed,
+                    };
+                }
+                _ => {}
+            }
+        }
+
         //self.world.run(camera_movement);
         //self.world.run(move_suzanne);
 
-        player::run_player_systems(&mut self.world);
-        soldier::run_soldier_systems(&mut self.world);
         //self.world.run(animate_soldier);
     }
 
+    fn build_schedule(&mut self, schedule: &mut Schedule) {
+        player::add_player_systems(schedule);
+        soldier::add_soldier_systems(schedule);
+    }
+
     fn on_render(&mut self) {
         //println!("Rendering scene...");
     }
 
-    fn on_event(&mut self, event: &Event) {
+    fn on_event(&mut self, _event: &Event) {
         // let input_manager = self.world.get_unique::<&mut InputManager>().unwrap();
 
         // input_manager
@@ -196,30 +229,6 @@ impl Game for MyApp {
         //     .for_each(|x| println!("{}", x.name()));
 
         // println!("Event: {:?}", event);
-
-        match event {
-            Event::KeyDown { keycode, .. } => match keycode {
-                Some(Keycode::P) => {
-                    self.world.run(player::interact::pointlight_toggle);
-                }
-                Some(Keycode::I) => {
-                    self.world
-                        .run(player::interact::rotate_directional_light_left);
-                }
-                Some(Keycode::O) => {
-                    self.world
-                        .run(player::interact::rotate_directional_light_right);
-                }
-                Some(Keycode::L) => {
-                    self.world.run(animate_soldier_walk);
-                }
-                Some(Keycode::K) => {
-                    self.world.run(animate_soldier_run);
-                }
-                _ => {}
-            },
-            _ => {}
-        }
     }
 
     fn get_world(&self) -> &World {
@@ -231,6 +240,15 @@ impl Game for MyApp {
     }
 }
 
+/// Loads a model, or reports why it couldn't be loaded and carries on without it.
+fn load_model(world: &World, path: &str) -> Option<AssetHandle> {
+    let mut asset_manager = world.get_unique::<&mut AssetManager>().unwrap();
+    asset_manager
+        .load_model(path)
+        .inspect_err(|e| warn!("{e}, skipping it"))
+        .ok()
+}
+
 pub fn move_suzanne(
     object3ds: View<Object3D>,
     mut transforms: ViewMut<Transform>,
@@ -1,21 +1,25 @@
 use nalgebra_glm::vec3;
 use sdl3::keyboard::Keycode;
 use shipyard::{IntoIter, UniqueView, UniqueViewMut, ViewMut};
+use vulkano_engine::core::cvars::CVarRegistry;
 use vulkano_engine::input::input_manager::InputManager;
 use vulkano_engine::prelude::camera::Camera;
 use vulkano_engine::prelude::transform::Transform;
 use vulkano_engine::scene::components::delta_time::DeltaTime;
 
-use crate::player;
-
-pub fn mouse_look(mut cameras: ViewMut<Camera>, mut input_manager: UniqueViewMut<InputManager>) {
+pub fn mouse_look(
+    mut cameras: ViewMut<Camera>,
+    mut input_manager: UniqueViewMut<InputManager>,
+    cvars: UniqueView<CVarRegistry>,
+) {
+    let sensitivity = cvars.float("player.sensitivity");
     let dx = input_manager.mouse_motion.0;
     let dy = input_manager.mouse_motion.1;
 
     for camera in (&mut cameras).iter().filter(|c| c.active) {
         if dx != 0.0 || dy != 0.0 {
-            camera.yaw += input_manager.mouse_motion.0 * player::SENSITIVITY;
-            camera.pitch += input_manager.mouse_motion.1 * player::SENSITIVITY;
+            camera.yaw += input_manager.mouse_motion.0 * sensitivity;
+            camera.pitch += input_manager.mouse_motion.1 * sensitivity;
 
             camera.pitch = camera.pitch.clamp(
                 -std::f32::consts::FRAC_PI_2 + 0.01,
@@ -32,7 +36,9 @@ pub fn freecam_movement(
     mut cameras: ViewMut<Camera>,
     input_manager: UniqueView<InputManager>,
     dt: UniqueView<DeltaTime>,
+    cvars: UniqueView<CVarRegistry>,
 ) {
+    let move_speed = cvars.float("player.move_speed");
     for camera in (&mut cameras).iter().filter(|c| c.active) {
         let foward = camera.get_forward_vector();
         let right = camera.get_right_vector();
@@ -54,7 +60,7 @@ pub fn freecam_movement(
 
         if movement.magnitude() > 0.0 {
             movement = movement.normalize();
-            camera.position += movement * player::MOVE_SPEED * dt.0;
+            camera.position += movement * move_speed * dt.0;
         }
     }
 }
@@ -4,12 +4,11 @@ mod movement;
 
 use nalgebra_glm::vec3;
 use sdl3::keyboard::Keycode;
-use shipyard::{Component, IntoIter, Unique, View, ViewMut, World};
-
-// const MOVE_SPEED: f32 = 0.5;
-// const SENSITIVITY: f32 = 0.005;
-const MOVE_SPEED: f32 = 2.0;
-const SENSITIVITY: f32 = 0.005;
+use shipyard::{Component, IntoIter, SystemModificator, Unique, View, ViewMut, World};
+use vulkano_engine::core::cvars::CVarRegistry;
+use vulkano_engine::core::schedule::{Schedule, Stage};
+use vulkano_engine::physics::physics_engine;
+use vulkano_engine::scene::scene_file::{SceneComponent, SceneFields};
 
 #[derive(Component, Unique)]
 pub struct Player {}
@@ -20,7 +19,57 @@ impl Player {
     }
 }
 
-pub fn run_player_systems(world: &mut World) {
-    world.run(camera::mouse_look);
-    world.run(movement::player_movement);
+impl SceneComponent for Player {
+    const NAME: &'static str = "player";
+
+    fn save(&self, _fields: &mut SceneFields) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn load(_fields: &mut SceneFields, _world: &World) -> Result<Self, String> {
+        Ok(Player::new())
+    }
+}
+
+/// Player tunables, changeable from the console (`player.move_speed 3`).
+pub fn register_player_cvars(cvars: &mut CVarRegistry) {
+    cvars.register_float(
+        "player.move_speed",
+        2.0,
+        0.0,
+        20.0,
+        "Walking speed in m/s, halved while holding shift",
+    );
+    cvars.register_float(
+        "player.jump_force",
+        6.0,
+        0.0,
+        30.0,
+        "Upward velocity of a jump in m/s",
+    );
+    cvars.register_float(
+        "player.gravity",
+        20.0,
+        0.0,
+        100.0,
+        "Downward acceleration of the player in m/s²",
+    );
+    cvars.register_float(
+        "player.sensitivity",
+        0.005,
+        0.0001,
+        0.1,
+        "Mouse look radians per pixel",
+    );
+}
+
+/// Mouse look runs once per frame; movement feeds the character controller, so it runs every
+/// fixed step right before it.
+pub fn add_player_systems(schedule: &mut Schedule) {
+    schedule
+        .add_system(Stage::PreUpdate, camera::mouse_look)
+        .add_system(
+            Stage::FixedUpdate,
+            movement::player_movement.before_all(physics_engine::physics_kinematic),
+        );
 }
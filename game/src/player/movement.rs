@@ -2,15 +2,13 @@ use nalgebra_glm::vec3;
 use rapier3d::prelude::Vec3;
 use sdl3::keyboard::Keycode;
 use shipyard::{IntoIter, UniqueView, View, ViewMut};
+use vulkano_engine::core::cvars::CVarRegistry;
 use vulkano_engine::input::input_manager::InputManager;
 use vulkano_engine::physics::physics_engine::{KinematicCharacterComponent, PhysicsEngine};
 use vulkano_engine::prelude::camera::Camera;
-use vulkano_engine::scene::components::delta_time::DeltaTime;
+use vulkano_engine::scene::components::fixed_time::FixedTime;
 
-use crate::player::{MOVE_SPEED, Player};
-
-const JUMP_FORCE: f32 = 6.0;
-const GRAVITY: f32 = 20.0;
+use crate::player::Player;
 
 pub fn player_movement(
     players: View<Player>,
@@ -18,7 +16,8 @@ pub fn player_movement(
     mut kinematic_character_components: ViewMut<KinematicCharacterComponent>,
     input_manager: UniqueView<InputManager>,
     physics_engine: UniqueView<PhysicsEngine>,
-    delta_time: UniqueView<DeltaTime>,
+    fixed_time: UniqueView<FixedTime>,
+    cvars: UniqueView<CVarRegistry>,
 ) {
     let camera = cameras
         .iter()
@@ -27,7 +26,10 @@ pub fn player_movement(
 
     let forward = camera.get_forward_vector();
     let right = camera.get_right_vector();
-    let dt = delta_time.0;
+    let dt = fixed_time.step;
+    let move_speed = cvars.float("player.move_speed");
+    let jump_force = cvars.float("player.jump_force");
+    let gravity = cvars.float("player.gravity");
 
     for (_player, kinematic_character) in (&players, &mut kinematic_character_components).iter() {
         let mut direction = vec3(0.0, 0.0, 0.0);
@@ -45,9 +47,9 @@ pub fn player_movement(
             direction += right;
         }
 
-        let mut speed = crate::player::MOVE_SPEED;
+        let mut speed = move_speed;
         if kinematic_character.grounded && input_manager.pressed_keys.contains(&Keycode::LShift) {
-            speed = MOVE_SPEED * 0.5;
+            speed = move_speed * 0.5;
         }
 
         //direction.y -= 0.981 * dt;
@@ -58,11 +60,11 @@ pub fn player_movement(
         }
 
         if kinematic_character.grounded && input_manager.pressed_keys.contains(&Keycode::Space) {
-            kinematic_character.vertical_velocity = JUMP_FORCE;
+            kinematic_character.vertical_velocity = jump_force;
         }
 
         // Apply gravity
-        kinematic_character.vertical_velocity -= GRAVITY * dt;
+        kinematic_character.vertical_velocity -= gravity * dt;
 
         kinematic_character.desired_movement = Vec3::new(
             direction.x,
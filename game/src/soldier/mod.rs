@@ -1,6 +1,13 @@
 pub mod movement;
+#[cfg(test)]
+mod tests;
 
 use shipyard::{Component, IntoIter, Unique, View, ViewMut, World};
+use vulkano_engine::core::schedule::{Schedule, Stage};
+use vulkano_engine::scene::prefab::{Prefab, PrefabEntity};
+use vulkano_engine::scene::scene_file::{SceneComponent, SceneFields};
+
+const MODEL: &str = "data/models/SoldierRe.glb";
 
 #[derive(Component)]
 pub struct Soldier {
@@ -19,6 +26,32 @@ impl Soldier {
     }
 }
 
-pub fn run_soldier_systems(world: &mut World) {
-    world.run(movement::move_soldier);
+impl SceneComponent for Soldier {
+    const NAME: &'static str = "soldier";
+
+    fn save(&self, fields: &mut SceneFields) -> Result<(), String> {
+        fields.set("direction", self.direction);
+        Ok(())
+    }
+
+    fn load(fields: &mut SceneFields, _world: &World) -> Result<Self, String> {
+        Ok(Soldier {
+            direction: fields.take("direction")?.unwrap_or(1.0),
+            ..Soldier::new()
+        })
+    }
+}
+
+/// A soldier with its animated model, spawned with `prefab::instantiate(world, "soldier", ..)`.
+pub fn prefab() -> Prefab {
+    Prefab::new(
+        PrefabEntity::new()
+            .add(Soldier::NAME)
+            .set("object3d.model", MODEL)
+            .set("animator.model", MODEL),
+    )
+}
+
+pub fn add_soldier_systems(schedule: &mut Schedule) {
+    schedule.add_system(Stage::Update, movement::move_soldier);
 }
@@ -1,4 +1,3 @@
-use nalgebra_glm::{identity, rotate_normalized_axis, vec3};
 use shipyard::{IntoIter, UniqueView, ViewMut};
 use vulkano_engine::scene::components::{delta_time::DeltaTime, transform::Transform};
 
@@ -44,6 +43,6 @@ pub fn move_soldier(
         // Rotate to face the walk direction. The soldier model's rest pose faces +Z,
         // so +X direction = -90°, -X direction = +90° around Y.
         let angle = -soldier.direction * std::f32::consts::FRAC_PI_2;
-        transform.rotation = rotate_normalized_axis(&identity(), angle, &vec3(0.0, 1.0, 0.0));
+        transform.set_euler_angles(0.0, angle, 0.0);
     }
 }
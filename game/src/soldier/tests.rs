@@ -0,0 +1,83 @@
+use nalgebra_glm::vec3;
+use sdl3::event::Event;
+use shipyard::{EntityId, Get, View, World};
+use vulkano_engine::core::application::Game;
+use vulkano_engine::core::config::EngineConfig;
+use vulkano_engine::core::schedule::Schedule;
+use vulkano_engine::core::simulation::Simulation;
+use vulkano_engine::scene::components::transform::Transform;
+
+use crate::soldier::{Soldier, add_soldier_systems};
+
+/// Exactly representable, so positions add up without rounding.
+const STEP: f32 = 1.0 / 64.0;
+
+/// A running soldier and nothing else.
+struct SoldierGame {
+    world: World,
+    start_x: f32,
+    soldier: Option<EntityId>,
+}
+
+impl Game for SoldierGame {
+    fn on_init(&mut self) {
+        self.soldier = Some(self.world.add_entity((
+            Transform::with_pos(vec3(self.start_x, 0.0, 0.0)),
+            Soldier {
+                is_running: true,
+                ..Soldier::new()
+            },
+        )));
+    }
+    fn on_update(&mut self, _dt: f32) {}
+    fn on_render(&mut self) {}
+    fn on_event(&mut self, _event: &Event) {}
+    fn build_schedule(&mut self, schedule: &mut Schedule) {
+        add_soldier_systems(schedule);
+    }
+    fn get_world(&self) -> &World {
+        &self.world
+    }
+    fn get_world_mut(&mut self) -> &mut World {
+        &mut self.world
+    }
+}
+
+fn run(start_x: f32, ticks: u32) -> (f32, f32) {
+    let game = SoldierGame {
+        world: World::new(),
+        start_x,
+        soldier: None,
+    };
+    let mut simulation = Simulation::new(game, EngineConfig::default());
+    simulation.run_ticks(ticks, STEP);
+
+    let soldier = simulation.game.soldier.unwrap();
+    let (soldiers, transforms) = simulation
+        .game
+        .get_world()
+        .borrow::<(View<Soldier>, View<Transform>)>()
+        .unwrap();
+    (
+        transforms.get(soldier).unwrap().get_position_vector()[0],
+        soldiers.get(soldier).unwrap().direction,
+    )
+}
+
+#[test]
+fn test_soldier_runs_along_x() {
+    let (x, direction) = run(0.0, 64);
+    assert!(
+        (x - 3.0).abs() < 1e-4,
+        "Per sekundę bėgama 3 vienetus, x = {x}"
+    );
+    assert_eq!(direction, 1.0);
+}
+
+#[test]
+fn test_soldier_turns_at_the_edge() {
+    // 22 žingsniai iki krašto ties x = 7, likę 42 atgal
+    let (x, direction) = run(6.0, 64);
+    assert_eq!(direction, -1.0, "Pasiekęs kraštą kareivis turi apsisukti");
+    assert!((x - (7.0 - 42.0 * 3.0 / 64.0)).abs() < 1e-4, "x = {x}");
+}